@@ -7,8 +7,15 @@ use bsp_stm32h7::Board;
 use bsp_stm32h7::hal::prelude::*;
 use rtt_target::{rtt_init_print, rprintln};
 
-// IMPORTE CRÍTICO: El trait para leer el ADC
-use embedded_hal::adc::OneShot; 
+// Lectura de los sensores ya calibrados (unidades de ingeniería)
+use bsp_stm32h7::ecu_traits::engine_io::{AnalogInput, AnalogSensor};
+use bsp_stm32h7::ecu_traits::timer::TimerBackend;
+use engine_core::battery::SupplyVoltage;
+use engine_core::diagnostics::{DiagnosticLog, SensorId};
+use engine_core::sensor_fault::{Fallback, MonitoredSensor, RangeCheckConfig};
+
+// Voltaje compartido con los modelos de inyector y bobina
+static SUPPLY: SupplyVoltage = SupplyVoltage::new(13.5);
 
 #[entry]
 fn main() -> ! {
@@ -16,15 +23,49 @@ fn main() -> ! {
     rprintln!("--- TEST ANALOGICO ---");
     let mut board = Board::init();
 
+    // CTS con diagnóstico: desconectar el sensor debe mostrar 80 C y el código P0118
+    let mut cts = MonitoredSensor::new(
+        board.cts,
+        SensorId::Cts,
+        RangeCheckConfig::new(0.1, 4.9, 500_000),
+        Fallback::Fixed(80.0),
+    );
+    let mut diag: DiagnosticLog<8> = DiagnosticLog::new();
 
     loop {
-        // En este HAL, read devuelve un Result<u32, _> (incluso si configuraste 16 bits)
-        // Usamos unwrap_or(0) para que no crashee si falla una lectura
-        let tps_val: u32 = board.adc1.read(&mut board.tps).unwrap_or(0);
-        let map_val: u32 = board.adc1.read(&mut board.map).unwrap_or(0);
-        
-        rprintln!("TPS: {} | MAP: {}", tps_val, map_val);
+        // Una lectura fallida se muestra como NaN para que no crashee
+        let tps = board.tps.read().unwrap_or(f32::NAN);
+        let map = board.map.read().unwrap_or(f32::NAN);
+        let iat = board.iat.read().unwrap_or(f32::NAN);
+        let now = board.timer.now_us();
+        let cts_c = cts.update(now, None, &mut diag);
+        let battery = board.battery.update(now, &mut diag, &SUPPLY);
+
+        rprintln!(
+            "TPS: {:.1} % ({:.2} V) | MAP: {:.1} kPa ({:.2} V) | IAT: {:.1} C | CTS: {:.1} C ({:.2} V)",
+            tps,
+            board.tps.last_volts(),
+            map,
+            board.map.last_volts(),
+            iat,
+            cts_c,
+            cts.sensor().last_volts(),
+        );
+        rprintln!(
+            "  O2 banda ancha: {:.2} V | banda angosta: {:.3} V",
+            board.wideband.read_volts().unwrap_or(f32::NAN),
+            board.narrowband.read_volts().unwrap_or(f32::NAN),
+        );
+        rprintln!(
+            "  Batería: {:.2} V ({:?}){}",
+            SUPPLY.volts(),
+            battery,
+            if SUPPLY.outputs_limited() { " SALIDAS LIMITADAS" } else { "" },
+        );
+        for record in diag.records().filter(|r| r.active) {
+            rprintln!("  falla P{:04}", record.code.obd_code());
+        }
 
         board.delay.delay_ms(500u32);
     }
-}
\ No newline at end of file
+}
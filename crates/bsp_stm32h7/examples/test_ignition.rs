@@ -7,6 +7,9 @@ use bsp_stm32h7::Board;
 use bsp_stm32h7::hal::prelude::*;
 
 use bsp_stm32h7::ecu_traits::engine_io::{Injector, IgnitionCoil};
+use bsp_stm32h7::ignition::IgnitionError;
+use bsp_stm32h7::injector::InjectorError;
+use engine_core::ignition_map::{IgnitionConfig, IgnitionMode};
 
 // I: Tipo generico que implementa el Trait Injector
 // C: Tipo generico que impleneta el trait coil
@@ -16,8 +19,8 @@ fn probar_cilindro<I, C>(
     delay: &mut bsp_stm32h7::hal::delay::Delay
 )
 where 
-    I: Injector,
-    C: IgnitionCoil,
+    I: Injector + ?Sized,
+    C: IgnitionCoil + ?Sized,
 {
     // 1. Se inicia el tiempo de carga de la bobina
     let _ = coil.start_dwell();
@@ -43,22 +46,35 @@ fn main() -> ! {
 
     let mut board = Board::init();
 
-    loop{
-        // Se simula en encendido de las bobinas en secuancia con delay
-
-        probar_cilindro(&mut board.inyector_1, &mut board.coil_1,&mut board.delay);
-        board.delay.delay_ms(100u32);
-
-        probar_cilindro(&mut board.inyector_3, &mut board.coil_3,&mut board.delay);
-        board.delay.delay_ms(100u32);
-
-        probar_cilindro(&mut board.inyector_4, &mut board.coil_4,&mut board.delay);
-        board.delay.delay_ms(100u32);
-
-        probar_cilindro(&mut board.inyector_2, &mut board.coil_2,&mut board.delay);
-        board.delay.delay_ms(100u32);
+    // El orden de encendido y el reparto de bobinas vienen de la configuración.
+    // Cambiando el modo (WastedSpark / Distributor) se prueba otro cableado sin tocar el loop.
+    let config = IgnitionConfig::even_fire(IgnitionMode::CoilOnPlug, [1, 3, 4, 2]);
+    if config.validate(4).is_err() {
+        panic!("Configuracion de encendido invalida");
+    }
 
-        // Ultimo delay para esperar el siguiente ciclo
-        //board.delay.delay_ms(1000u32);
+    loop{
+        // Salidas de la Board indexadas (0..4)
+        let coils: [&mut dyn IgnitionCoil<Error = IgnitionError>; 4] = [
+            &mut board.coil_1,
+            &mut board.coil_2,
+            &mut board.coil_3,
+            &mut board.coil_4,
+        ];
+        let injectors: [&mut dyn Injector<Error = InjectorError>; 4] = [
+            &mut board.inyector_1,
+            &mut board.inyector_2,
+            &mut board.inyector_3,
+            &mut board.inyector_4,
+        ];
+
+        // Se simula el encendido en secuencia con delay
+        for event in config.events() {
+            let inyector = &mut *injectors[(event.cylinder - 1) as usize];
+            let coil = &mut *coils[event.output as usize];
+
+            probar_cilindro(inyector, coil, &mut board.delay);
+            board.delay.delay_ms(100u32);
+        }
     }
 }
@@ -5,7 +5,7 @@ use panic_halt as _;
 use cortex_m_rt::entry;
 use bsp_stm32h7::Board; // Solo importamos la Board
 use bsp_stm32h7::ecu_traits::engine_io::Injector;
-use bsp_stm32h7::hal::prelude::*;
+use bsp_stm32h7::ecu_traits::timer::TimerBackend;
 
 
 #[entry]
@@ -14,24 +14,20 @@ fn main() -> ! {
     // Esto prueba implícitamente que el mapeo de pines y relojes es correcto.
     let mut board = Board::init();
 
+    let mut next_cycle = board.timer.now_us().wrapping_add(1_000);
+
     // 2. Bucle de Prueba
     loop {
-        // --- CILINDRO 1 ---
-        let _ = board.inyector_1.open();
-        
-        // Usamos el delay que vive dentro de la board
-        board.delay.delay_ms(4u32); 
-        
-        let _ = board.inyector_1.close();
+        // Cada 100 ms: pulso de 4 ms en el cilindro 1 y de 10 ms en el 2.
+        // La interrupción del timer cierra cada inyector; aquí no se espera a nadie.
+        if next_cycle.wrapping_sub(board.timer.now_us()) as i32 <= 0 {
+            // --- CILINDRO 1 ---
+            let _ = board.inyector_1.pulse_us(4_000);
 
-        // --- CILINDRO 2 ---
-        let _ = board.inyector_2.open();
-        
-        board.delay.delay_ms(10u32);
-        
-        let _ = board.inyector_2.close();
+            // --- CILINDRO 2 --- (cierre en tiempo absoluto)
+            let _ = board.inyector_2.pulse_until(next_cycle.wrapping_add(10_000));
 
-        // Ciclo muerto
-        board.delay.delay_ms(100u32);
+            next_cycle = next_cycle.wrapping_add(100_000);
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,40 @@
+#![no_main]
+#![no_std]
+
+use panic_halt as _;
+use cortex_m_rt::entry;
+use bsp_stm32h7::Board;
+use bsp_stm32h7::ecu_traits::timer::{CompareAction, TimerBackend};
+use bsp_stm32h7::timer::{IsrTimer, COIL_CHANNELS};
+
+// Inyector 1 y bobina 1 manejados por output compare (sin delays bloqueantes)
+const INJ1: u8 = 0;
+const COIL1: u8 = COIL_CHANNELS.start;
+
+/// Se llama desde las interrupciones del timer, cuando el pin ya se movió:
+/// al activar se programa el fin del pulso
+fn on_compare(channel: u8, action: CompareAction, timer: &mut IsrTimer) {
+    if action == CompareAction::Activate {
+        let width = if channel == INJ1 { 4_000 } else { 3_000 };
+        let now = timer.now_us();
+        timer.arm(channel, now.wrapping_add(width), CompareAction::Deactivate);
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    let mut board = Board::init();
+    board.timer.set_compare_handler(on_compare);
+
+    let mut next_cycle = board.timer.now_us().wrapping_add(1_000);
+
+    loop {
+        // Cada 100 ms: pulso de inyección de 4 ms y dwell de 3 ms 20 ms después.
+        // Los flancos los mueve el BSP desde la interrupción; el loop solo programa.
+        if next_cycle.wrapping_sub(board.timer.now_us()) as i32 <= 0 {
+            board.timer.arm(INJ1, next_cycle, CompareAction::Activate);
+            board.timer.arm(COIL1, next_cycle.wrapping_add(20_000), CompareAction::Activate);
+            next_cycle = next_cycle.wrapping_add(100_000);
+        }
+    }
+}
@@ -4,63 +4,65 @@
 use panic_halt as _;
 use cortex_m_rt::entry;
 use bsp_stm32h7::Board;
-use bsp_stm32h7::ecu_traits::engine_io::RotationSensor;
-use bsp_stm32h7::hal::prelude::*;
+use bsp_stm32h7::ecu_traits::engine_io::EdgeCapture;
+use bsp_stm32h7::stim::StimOutput;
+use engine_core::crank_decoder::{CrankDecoder, MissingToothWheel};
+use engine_core::signal_gen::{SignalGenConfig, SignalGenerator, SignalNoise, TriggerSignal};
 use rtt_target::{rtt_init_print, rprintln};
 
 #[entry]
 fn main() -> ! {
     rtt_init_print!();
-    rprintln!("--- INICIO DE TEST DE SENAL CKP ---");
-    rprintln!("Conecta tu generador de señales a PA4 (CKP)");
+    rprintln!("--- INICIO DE TEST DE SENAL CKP/CMP ---");
+    rprintln!("Puentea PB0 -> PB4 (CKP) y PB1 -> PC8 (CMP)");
 
     let mut board = Board::init();
-    
-    // Variables para el algoritmo de conteo
-    let mut pulsos = 0;
-    let mut estado_anterior = false;
-    
-    // Para medir tiempo (usamos el delay de la board como referencia burda)
-    // En una prueba real usariamos un Timer, pero para validar señal esto basta.
-    let ventanas_de_muestreo = 1000; // 1000 iteraciones de 1ms = 1 segundo aprox
+
+    // Rueda 36-1 con leva de un diente: arranque a 250 RPM y rampa a 3000
+    let wheel = MissingToothWheel::new(36, 1);
+    let config = SignalGenConfig {
+        start_us: board.stim.now_us().wrapping_add(10_000),
+        start_angle_deg: 0.0,
+        rpm: 250.0,
+        cam_teeth_deg: [630.0],
+        cam_tooth_width_deg: 30.0,
+        noise: SignalNoise::default(),
+    };
+    let mut generator = SignalGenerator::new(wheel, config);
+    generator.ramp_to(3000.0, 5_000_000);
+
+    let mut edges = generator.map(|e| {
+        let output = if e.signal == TriggerSignal::Crank { StimOutput::Crank } else { StimOutput::Cam };
+        (output, e.captured())
+    });
+
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    let mut cam_edges = 0u32;
+    let mut next_report = board.stim.now_us();
 
     loop {
-        pulsos = 0;
-        
-        // Ventana de tiempo de 1 segundo (aprox)
-        for _ in 0..ventanas_de_muestreo {
-            // Muestreamos rápido dentro del milisegundo
-            // Hacemos 100 lecturas rápidas para no perder pulsos
-            for _ in 0..100 {
-                // 1. Lectura del Pin (Polling)
-                let estado_actual = board.ckp.get_state().unwrap_or(false);
+        // 1. Generamos la señal
+        board.stim.poll(&mut edges);
 
-                // 2. Detección de Flanco de Subida (Rising Edge)
-                // Si antes estaba bajo (false) y ahora es alto (true) -> ¡Pulso!
-                if estado_actual && !estado_anterior {
-                    pulsos += 1;
-                }
-                
-                estado_anterior = estado_actual;
-                
-                // Pequeño delay para estabilizar (muy corto)
-                //board.delay.delay_us(100u32);
-                //cortex_m::asm::delay(150); 
-            }
-            
-            // Avanzamos el reloj de "tiempo real"
-            board.delay.delay_ms(1u32);
+        // 2. La leemos de regreso con la captura por hardware
+        while let Ok(Some(edge)) = board.ckp.read_edge() {
+            decoder.on_edge(edge);
+        }
+        while let Ok(Some(_)) = board.cmp.read_edge() {
+            cam_edges += 1;
         }
 
-        // --- CÁLCULOS ---
-        // Frecuencia = Pulsos por segundo
-        let hertz = pulsos;
-        
-        // RPM (Asumiendo rueda 60-2 o similar, digamos 60 dientes por vuelta)
-        // RPM = (Frecuencia * 60 segundos) / Dientes
-        let dientes_rueda = 60; 
-        let rpm = (hertz * 60) / dientes_rueda;
-
-        rprintln!("Frecuencia: {} Hz | RPM Estimadas (60t): {}", hertz, rpm);
+        // 3. Reporte cada 500 ms
+        if next_report.wrapping_sub(board.stim.now_us()) as i32 <= 0 {
+            next_report = next_report.wrapping_add(500_000);
+            rprintln!(
+                "Sync: {:?} | RPM: {} | Flancos leva: {} | Flancos tarde: {} | Perdidas: {}",
+                decoder.state(),
+                decoder.rpm() as u32,
+                cam_edges,
+                board.stim.late_edges(),
+                decoder.sync_losses(),
+            );
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,63 @@
+use core::cell::RefCell;
+
+use cortex_m::interrupt::{self, Mutex};
+use ecu_traits::engine_io::AnalogInput;
+use embedded_hal::adc::{Channel, OneShot};
+use crate::hal::adc::{Adc, Enabled};
+use crate::hal::device::ADC1;
+
+/// Referencia del ADC (V)
+const VREF: f32 = 3.3;
+
+/// Divisor de acondicionamiento de la placa: los sensores de 5 V llegan
+/// al pin escalados por este factor (depende del PCB)
+pub const SENSOR_DIVIDER: f32 = 1.5;
+
+/// ADC compartido por todos los canales analógicos.
+/// Se instala en `Board::init`; hasta entonces las lecturas fallan.
+static ADC1_SHARED: Mutex<RefCell<Option<Adc<ADC1, Enabled>>>> = Mutex::new(RefCell::new(None));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalogError {
+    /// El ADC todavía no se instaló
+    NotReady,
+    Conversion,
+}
+
+/// Deja el ADC disponible para los canales (se llama una vez al iniciar)
+pub(crate) fn install_adc(adc: Adc<ADC1, Enabled>) {
+    interrupt::free(|cs| ADC1_SHARED.borrow(cs).replace(Some(adc)));
+}
+
+/// Canal analógico de ADC1: entrega el voltaje en la terminal del sensor.
+/// P: El pin fisico (en modo analógico)
+pub struct Stm32h7AnalogInput<P> {
+    pin: P,
+    divider: f32,
+}
+
+impl<P> Stm32h7AnalogInput<P>
+where
+    P: Channel<ADC1, ID = u8>,
+{
+    /// divider: factor entre el voltaje del sensor y el del pin
+    pub fn new(pin: P, divider: f32) -> Self {
+        Self { pin, divider }
+    }
+}
+
+impl<P> AnalogInput for Stm32h7AnalogInput<P>
+where
+    P: Channel<ADC1, ID = u8>,
+{
+    type Error = AnalogError;
+
+    fn read_volts(&mut self) -> Result<f32, Self::Error> {
+        interrupt::free(|cs| {
+            let mut adc = ADC1_SHARED.borrow(cs).borrow_mut();
+            let adc = adc.as_mut().ok_or(AnalogError::NotReady)?;
+            let counts: u32 = nb::block!(adc.read(&mut self.pin)).map_err(|_| AnalogError::Conversion)?;
+            Ok(counts as f32 * VREF / adc.slope() as f32 * self.divider)
+        })
+    }
+}
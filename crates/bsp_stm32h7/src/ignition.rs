@@ -1,5 +1,7 @@
 use ecu_traits::engine_io::IgnitionCoil;
 use embedded_hal::digital::v2::OutputPin;
+use crate::hal::gpio::PinExt;
+use crate::timer::bind_output;
 
 
 #[derive(Debug)]
@@ -9,15 +11,20 @@ pub enum IgnitionError {
 
 /// Driver generico para represtar una bobina
 /// P: pin fisico
+///
+/// Los compares del canal (dwell y chispa programados con el timer) los
+/// ejecuta la interrupción TIM2 del BSP sobre el mismo pin.
 pub struct Stm32h7Coil<P> {
     pin: P,
 }
 
 impl <P> Stm32h7Coil<P>
 where
-    P: OutputPin
+    P: OutputPin + PinExt
 {
-    pub fn new(pin: P) -> Self {
+    /// channel: canal del timer de salidas (ver timer::COIL_CHANNELS)
+    pub fn new(pin: P, channel: u8) -> Self {
+        bind_output(channel, &pin);
         let mut driver = Self { pin };
         // Por seguridad el coil debe arrancar apagado
         let _ = driver.pin.set_low();
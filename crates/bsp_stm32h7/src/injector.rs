@@ -1,11 +1,8 @@
 use ecu_traits::engine_io::Injector;
-use embedded_hal::digital::v2::OutputPin;
-//use stm32h7xx_hal as hal;
-//use hal::device::TIM2; // Usaremos TIM2 como ejemplo, pero puede ser genérico
-//use hal::timer::{Event, Counter};
-//use hal::gpio::{Output, PushPull, Pin};
-//use hal::prelude::*;
-//use hal::delay::Delay;
+use ecu_traits::timer::CompareAction;
+use embedded_hal::digital::v2::{OutputPin, StatefulOutputPin};
+use crate::hal::gpio::PinExt;
+use crate::timer::{arm_channel, bind_output, cancel_channel, time_base_us};
 
 
 // Definimos un error básico
@@ -14,21 +11,28 @@ pub enum InjectorError {
     ElectricalFailure,
 }
 
-/// Driver de Inyector para STM32H7 solo la logica para dejar 
-/// control de tiempos a la aplicacion
+/// Driver de Inyector para STM32H7.
+/// `open`/`close` mueven el pin directamente; `pulse_us`/`pulse_until`
+/// abren y programan el cierre en el canal de output compare del inyector
+/// (TIM5), así que quien llama no se bloquea.
+///
+/// El cierre lo escribe la interrupción TIM5 del BSP sobre el pin que el
+/// driver registra al crearse, sin pasar por la aplicación: un bloqueo del
+/// loop principal no deja el inyector abierto. `is_open` lee el nivel del pin.
 pub struct Stm32h7Injector<P> {
-    // El pin físico (ej. PA1)
+    // El pin físico (ej. PE2)
     pin: P,
-    // Un contador de hardware que nos da la base de tiempo
-    //timer: Delay, // 1 MHz = 1 tick por microsegundo
+    // Canal del timer de salidas (ver timer::INJECTOR_CHANNELS)
+    channel: u8,
 }
 
 impl<P> Stm32h7Injector<P>
 where
-    P:OutputPin,
+    P: OutputPin + StatefulOutputPin + PinExt,
 {
-    pub fn new(pin: P) -> Self {
-        let mut driver = Self { pin };
+    pub fn new(pin: P, channel: u8) -> Self {
+        bind_output(channel, &pin);
+        let mut driver = Self { pin, channel };
         let _ = driver.pin.set_low(); // Asegurar inyector cerrado al inicio
         driver
     }
@@ -36,19 +40,31 @@ where
 
 impl<P> Injector for Stm32h7Injector<P> 
 where 
-    P: OutputPin
+    P: OutputPin + StatefulOutputPin
 {
     type Error = InjectorError;
 
     fn open(&mut self) -> Result<(), Self::Error> {
         // 1. Abrimos el inyector (GPIO HIGH)
-        let _ = self.pin.set_high();
-        Ok(())
+        self.pin.set_high().map_err(|_| InjectorError::ElectricalFailure)
     }
 
     fn close(&mut self) -> Result<(), Self::Error> {
-        let _ = self.pin.set_low();
+        cancel_channel(self.channel);
+        self.pin.set_low().map_err(|_| InjectorError::ElectricalFailure)
+    }
+
+    fn pulse_us(&mut self, duration_us: u32) -> Result<(), Self::Error> {
+        self.pulse_until(time_base_us().wrapping_add(duration_us))
+    }
+
+    fn pulse_until(&mut self, close_at_us: u32) -> Result<(), Self::Error> {
+        self.open()?;
+        arm_channel(self.channel, close_at_us, CompareAction::Deactivate);
         Ok(())
-        //self.timer.cancel().ok();
     }
-}
\ No newline at end of file
+
+    fn is_open(&self) -> bool {
+        self.pin.is_set_high().unwrap_or(false)
+    }
+}
@@ -5,20 +5,24 @@
 pub use stm32h7xx_hal as hal;
 pub use ecu_traits;
 
+pub mod analog;
 pub mod injector;
 pub mod ignition;
 pub mod pinout; // <--- Nuevo módulo
 pub mod sensors;
+pub mod stim;
+pub mod timer;
 
 use hal::prelude::*;
 use hal::gpio::GpioExt;
 //use injector::Stm32h7Injector;
 use pinout::{map_hardware, RawPorts};
+use timer::Stm32h7OutputTimer;
 //use embedded_hal::digital::OutputPin;
 use hal::delay::Delay; // Importar Delay
 //use hal::gpio::ExtiPin;
 
-use hal::adc::{Adc, Enabled, Resolution};
+use hal::adc::{Adc, Resolution};
 use hal::rcc::rec::AdcClkSel;
 //use hal::traits::Adc as AdcTrait;
 
@@ -39,16 +43,29 @@ pub struct Board {
     pub ckp: pinout::CkpDriver, // <--- Público para el firmware
     pub cmp: pinout::CmpDriver, // <--- Público para el firmware
 
+    pub clutch: pinout::ClutchDriver,
+
+    // Señales sintéticas de CKP/CMP para el banco
+    pub stim: pinout::TriggerStimDriver,
+
     pub delay: Delay, // <--- La board incluye su propio reloj de espera
 
-    pub tps: pinout::TpsPin,
-    pub map: pinout::MapPin,
-    pub iat: pinout::IatPin,
-    pub cts: pinout::CtsPin,
+    // Output compare de inyectores y bobinas (base de tiempo de 1 MHz)
+    pub timer: Stm32h7OutputTimer,
+
+    // Sensores analógicos ya calibrados; comparten ADC1
+    pub tps: pinout::TpsSensor,
+    pub tps2: pinout::Tps2Input,
+    pub map: pinout::MapSensor,
+    pub iat: pinout::IatSensor,
+    pub cts: pinout::CtsSensor,
+
+    // Sondas de oxígeno (voltaje filtrado)
+    pub wideband: pinout::WidebandInput,
+    pub narrowband: pinout::NarrowbandInput,
 
-    // EL COMPONENTE CLAVE: El ADC (Convertidor)
-    // Usaremos ADC1 para todos estos sensores
-    pub adc1: Adc<hal::device::ADC1, Enabled>,
+    // Voltaje de alimentación (publicarlo en un SupplyVoltage compartido)
+    pub battery: pinout::BatteryDriver,
 }
 
 impl Board {
@@ -71,17 +88,19 @@ impl Board {
         // 3. Dividir los GPIOs (Split)
         let gpioe = dp.GPIOE.split(ccdr.peripheral.GPIOE);
         let gpioa = dp.GPIOA.split(ccdr.peripheral.GPIOA);
+        let gpiob = dp.GPIOB.split(ccdr.peripheral.GPIOB);
         let gpioc = dp.GPIOC.split(ccdr.peripheral.GPIOC);
 
         // 4. Empaquetamos los recursos crudos
         let raw_ports = RawPorts {
             gpioa,
+            gpiob,
             gpioc,
             gpioe,
         };
 
         // 5. LLAMAMOS AL MAPEO (Aquí ocurre la abstracción)
-        let hardware = map_hardware(raw_ports);
+        let mut hardware = map_hardware(raw_ports);
 
         // Configuración adicional de interrupción para CKP
         // Queremos que interrumpa en el flanco de SUBIDA (Rising Edge)
@@ -93,6 +112,22 @@ impl Board {
 
         let mut sys_delay = Delay::new(cp.SYST, ccdr.clocks);
 
+        // TIM2 (bobinas) y TIM5 (inyectores) como timers de salida,
+        // TIM3 para la captura de CKP/CMP y TIM4 para los canales por software
+        let timer = Stm32h7OutputTimer::new(
+            dp.TIM2,
+            ccdr.peripheral.TIM2,
+            dp.TIM5,
+            ccdr.peripheral.TIM5,
+            dp.TIM3,
+            ccdr.peripheral.TIM3,
+            dp.TIM4,
+            ccdr.peripheral.TIM4,
+            &ccdr.clocks,
+        );
+        hardware.ckp.enable_capture();
+        hardware.cmp.enable_capture();
+
         let mut adc1 = Adc::adc1(
             dp.ADC1, 
             4.MHz(),
@@ -102,6 +137,7 @@ impl Board {
         ).enable();
 
         adc1.set_resolution(Resolution::SixteenBit);
+        analog::install_adc(adc1);
 
         // 6. Retornar la estructura empaquetada
         Board {
@@ -117,15 +153,23 @@ impl Board {
 
             ckp: hardware.ckp,
             cmp: hardware.cmp,
+
+            clutch: hardware.clutch,
+            stim: hardware.stim,
             
             delay: sys_delay, // <--- Lo guardamos
+            timer,
 
-            // Sensores y ADC
+            // Sensores
             tps: hardware.tps,
+            tps2: hardware.tps2,
             map: hardware.map,
             iat: hardware.iat,
             cts: hardware.cts,
-            adc1: adc1,
+            wideband: hardware.wideband,
+            narrowband: hardware.narrowband,
+            battery: hardware.battery,
         }
     }
-}
\ No newline at end of file
+
+}
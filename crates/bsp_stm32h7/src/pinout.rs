@@ -2,13 +2,21 @@
 use crate::hal::gpio;
 use crate::injector::Stm32h7Injector; // Importamos el driver genérico
 use crate::ignition::Stm32h7Coil; // Importamos el driver genérico
-use crate::sensors::Stm32h7HallSensor;
+use crate::sensors::{CaptureChannel, Stm32h7CaptureSensor, Stm32h7Switch};
+use crate::stim::Stm32h7TriggerStim;
+use crate::analog::{Stm32h7AnalogInput, SENSOR_DIVIDER};
+use engine_core::battery::{BatteryConfig, BatteryMonitor};
+use engine_core::filters::{FilterConfig, FilteredInput};
+use engine_core::analog::{
+    CalibratedSensor, LinearConverter, SteinhartHart, Thermistor, DEFAULT_MAP, DEFAULT_TPS, GM_THERMISTOR,
+};
 
 // --- DEFINICIONES FÍSICAS (El "define" de Rust) ---
 // --- 1. DEFINICIÓN DE RECURSOS (EL "HARDWARE") ---
 // Para no pasar 10 argumentos, agrupamos todos los puertos crudos del micro aquí.
 pub struct RawPorts {
     pub gpioa: gpio::gpioa::Parts,
+    pub gpiob: gpio::gpiob::Parts,
     pub gpioc: gpio::gpioc::Parts,
     pub gpioe: gpio::gpioe::Parts,
     // Agrega más puertos (GPIOB, GPIOC) si es necesario
@@ -19,6 +27,8 @@ macro_rules! define_hardware_map {
     (
         outputs: { $($OutAlias:ident : $out_port:ident . $out_pin:ident as $OutPinType:ident),* $(,)? },
         inputs: { $($InAlias:ident : $in_port:ident . $in_pin:ident as $InPinType:ident),* $(,)? },
+        // Entradas a timer (input capture): el número es la función alterna (AF)
+        capture: { $($CapAlias:ident : $cap_port:ident . $cap_pin:ident as $CapPinType:ident : $af:literal),* $(,)? },
         // --- NUEVA SECCIÓN ---
         analog: {
             $($AnaAlias:ident : $ana_port:ident . $ana_pin:ident as $AnaPinType:ident),* $(,)?
@@ -27,16 +37,18 @@ macro_rules! define_hardware_map {
         // ... (Generación de Outputs e Inputs igual que antes) ...
         $( pub type $OutAlias = gpio::$OutPinType<gpio::Output<gpio::PushPull>>; )*
         $( pub type $InAlias = gpio::$InPinType<gpio::Input>; )*
+        $( pub type $CapAlias = gpio::$CapPinType<gpio::Alternate<$af>>; )*
 
         // 1. Generar Tipos ANALÓGICOS
         $(
             pub type $AnaAlias = gpio::$AnaPinType<gpio::Analog>;
         )*
 
-        fn extract_pins(ports: RawPorts) -> ( ($($OutAlias,)*), ($($InAlias,)*), ($($AnaAlias,)*) ) {
+        fn extract_pins(ports: RawPorts) -> ( ($($OutAlias,)*), ($($InAlias,)*), ($($CapAlias,)*), ($($AnaAlias,)*) ) {
             (
                 ( $( ports.$out_port.$out_pin.into_push_pull_output(), )* ),
                 ( $( ports.$in_port.$in_pin.into_pull_up_input(), )* ),
+                ( $( ports.$cap_port.$cap_pin.into_alternate::<$af>().internal_pull_up(true), )* ),
                 // 2. Inicializar Analógicos
                 (
                     $(
@@ -63,27 +75,48 @@ define_hardware_map!(
         Ign2Pin       : gpioa  . pa1   as PA1,
         Ign3Pin       : gpioa  . pa2   as PA2,
         Ign4Pin       : gpioa  . pa3   as PA3,
+
+        // Estimulador de banco (señales sintéticas de CKP/CMP para otra ECU)
+        StimCkpPin    : gpiob  . pb0   as PB0,
+        StimCmpPin    : gpiob  . pb1   as PB1,
     },
     inputs: {
         // Alias      : Puerto . Pin   as Tipo
-        CkpPin        : gpioa  . pa4   as PA4,
-        CmpPin        : gpioa  . pa5   as PA5,
+        // Switch de clutch a tierra (launch control / flat shift)
+        ClutchPin     : gpioa  . pa6   as PA6,
+    },
+    capture: {
+        // Alias      : Puerto . Pin   as Tipo : AF
+        // CKP y CMP con marca de tiempo por hardware (TIM3_CH1 / TIM3_CH3);
+        // cada uno ocupa también el canal vecino para capturar el flanco de bajada
+        CkpPin        : gpiob  . pb4   as PB4  : 2,
+        CmpPin        : gpioc  . pc8   as PC8  : 2,
     },
     analog: {
         // Definimos los sensores típicos de una ECU
         // TPS: Throttle Position Sensor (PA0 es ADC1_INP16 en H750)
         TpsPin  : gpioc . pc0 as PC0, 
         
+        // Segunda pista del TPS de doble pista (PC2 -> ADC1_INP12)
+        Tps2Pin : gpioc . pc2 as PC2,
+
         // MAP: Manifold Absolute Pressure (PA1 es ADC1_INP17)
         MapPin  : gpioc . pc1 as PC1,
 
-        // IAT: Intake Air Temp (Digamos PC0 -> ADC1_INP10)
-        // Necesitas agregar gpioc a RawPorts si usas PC0
-        // Por simplicidad usaré PA6 (ADC1_INP3)
-        IatPin  : gpioc . pc6 as PC6,
+        // IAT: Intake Air Temp (PC4 -> ADC1_INP4)
+        IatPin  : gpioc . pc4 as PC4,
         
-        // CTS: Coolant Temp Sensor (PA7 -> ADC1_INP7)
-        CtsPin  : gpioc . pc7 as PC7,
+        // CTS: Coolant Temp Sensor (PC5 -> ADC1_INP8)
+        CtsPin  : gpioc . pc5 as PC5,
+
+        // Salida analógica del controlador de banda ancha, 0-5 V (PA4 -> ADC1_INP18)
+        WidebandPin   : gpioa . pa4 as PA4,
+
+        // Sonda de banda angosta, 0-1 V (PA5 -> ADC1_INP19)
+        NarrowbandPin : gpioa . pa5 as PA5,
+
+        // Voltaje de batería por divisor 10k/2k2 (PA7 -> ADC1_INP7)
+        BatteryPin    : gpioa . pa7 as PA7,
     }
 );
 
@@ -100,8 +133,87 @@ pub type Ign2Driver = Stm32h7Coil<Ign2Pin>;
 pub type Ign3Driver = Stm32h7Coil<Ign3Pin>;
 pub type Ign4Driver = Stm32h7Coil<Ign4Pin>;
 
-pub type CkpDriver = Stm32h7HallSensor<CkpPin>;
-pub type CmpDriver = Stm32h7HallSensor<CmpPin>;
+pub type CkpDriver = Stm32h7CaptureSensor<CkpPin>;
+pub type CmpDriver = Stm32h7CaptureSensor<CmpPin>;
+
+pub type ClutchDriver = Stm32h7Switch<ClutchPin>;
+
+pub type TriggerStimDriver = Stm32h7TriggerStim<StimCkpPin, StimCmpPin>;
+
+// Sensores analógicos filtrados y calibrados (unidades de ingeniería)
+pub type TpsSensor = CalibratedSensor<FilteredInput<Stm32h7AnalogInput<TpsPin>>, LinearConverter>;
+pub type MapSensor = CalibratedSensor<FilteredInput<Stm32h7AnalogInput<MapPin>>, LinearConverter>;
+pub type IatSensor = CalibratedSensor<FilteredInput<Stm32h7AnalogInput<IatPin>>, Thermistor<SteinhartHart>>;
+pub type CtsSensor = CalibratedSensor<FilteredInput<Stm32h7AnalogInput<CtsPin>>, Thermistor<SteinhartHart>>;
+
+// Segunda pista del TPS (voltaje filtrado); la plausibilidad entre pistas vive en engine_core::tps
+pub type Tps2Input = FilteredInput<Stm32h7AnalogInput<Tps2Pin>>;
+
+// Sondas de oxígeno: la conversión a lambda y la validez viven en engine_core::o2
+pub type WidebandInput = FilteredInput<Stm32h7AnalogInput<WidebandPin>>;
+pub type NarrowbandInput = FilteredInput<Stm32h7AnalogInput<NarrowbandPin>>;
+
+/// La banda angosta entra sin divisor (0-1 V)
+const NARROWBAND_DIVIDER: f32 = 1.0;
+
+// Monitor de batería (el divisor se configura en BATTERY_CONFIG, no en el canal)
+pub type BatteryDriver = BatteryMonitor<FilteredInput<Stm32h7AnalogInput<BatteryPin>>>;
+
+const BATTERY_CONFIG: BatteryConfig = BatteryConfig {
+    divider_ratio: (10_000.0 + 2_200.0) / 2_200.0,
+    low_volts: 11.0,
+    high_volts: 15.5,
+    // Load dump: arriba de esto los inyectores y bobinas se apagan
+    max_safe_volts: 18.0,
+    limit_hysteresis_volts: 1.0,
+    limit_hold_us: 500_000,
+    debounce_us: 2_000_000,
+};
+
+// Filtros por canal (el firmware lee los canales cada 10 ms)
+const ANALOG_PERIOD_US: u32 = 10_000;
+
+/// TPS: respuesta rápida, solo sobremuestreo y rechazo de picos
+const TPS_FILTER: FilterConfig = FilterConfig {
+    oversample: 4,
+    median_window: 3,
+    ..FilterConfig::none(ANALOG_PERIOD_US)
+};
+/// MAP: sobremuestreo y primer orden corto (la pulsación por admisión se trata aparte)
+const MAP_FILTER: FilterConfig = FilterConfig {
+    oversample: 4,
+    lag_tau_us: 20_000,
+    ..FilterConfig::none(ANALOG_PERIOD_US)
+};
+/// Temperaturas: cambian lento, filtro fuerte y límite de cambio (V/s)
+const TEMP_FILTER: FilterConfig = FilterConfig {
+    oversample: 8,
+    median_window: 5,
+    lag_tau_us: 1_000_000,
+    rate_limit_per_s: 1.0,
+    sample_period_us: ANALOG_PERIOD_US,
+};
+/// Batería: sobremuestreo y primer orden corto (sigue la caída del arranque)
+const BATTERY_FILTER: FilterConfig = FilterConfig {
+    oversample: 4,
+    median_window: 3,
+    lag_tau_us: 50_000,
+    ..FilterConfig::none(ANALOG_PERIOD_US)
+};
+/// O2: sin primer orden para no atrasar el lazo cerrado
+const O2_FILTER: FilterConfig = FilterConfig {
+    oversample: 4,
+    median_window: 3,
+    ..FilterConfig::none(ANALOG_PERIOD_US)
+};
+
+/// Pull-up de los termistores en la placa (Ω)
+const THERMISTOR_PULLUP_OHMS: f32 = 2490.0;
+/// Alimentación de sensores (V)
+const SENSOR_SUPPLY_VOLTS: f32 = 5.0;
+
+const THERMISTOR: Thermistor<SteinhartHart> =
+    Thermistor::new(GM_THERMISTOR, THERMISTOR_PULLUP_OHMS, SENSOR_SUPPLY_VOLTS);
 
 // Estructura que devuelve los pines ya convertidos en drivers
 pub struct ConfiguredHardware {
@@ -118,11 +230,21 @@ pub struct ConfiguredHardware {
     pub ckp: CkpDriver,
     pub cmp: CmpDriver,
 
+    pub clutch: ClutchDriver,
+
+    pub stim: TriggerStimDriver,
+
     // Sensores Analógicos
-    pub tps: TpsPin,
-    pub map: MapPin,
-    pub iat: IatPin,
-    pub cts: CtsPin,
+    pub tps: TpsSensor,
+    pub tps2: Tps2Input,
+    pub map: MapSensor,
+    pub iat: IatSensor,
+    pub cts: CtsSensor,
+
+    pub wideband: WidebandInput,
+    pub narrowband: NarrowbandInput,
+
+    pub battery: BatteryDriver,
 }
 
 // --- 3. EL MAPEO (LA "CONEXIÓN") ---
@@ -137,30 +259,55 @@ pub fn map_hardware(ports: RawPorts) -> ConfiguredHardware {
         p_ign1,
         p_ign2,
         p_ign3,
-        p_ign4),
-        (p_ckp,
-        p_cmp,),
-        (p_tps, p_map, p_iat, p_cts)
+        p_ign4,
+
+        p_stim_ckp,
+        p_stim_cmp),
+        (p_clutch,),
+        (p_ckp, p_cmp),
+        (p_tps, p_tps2, p_map, p_iat, p_cts, p_wideband, p_narrowband, p_battery)
     ) = extract_pins(ports);
 
     // C) Creamos los drivers
     ConfiguredHardware {
-        inj1: Stm32h7Injector::new(p_inj1),
-        inj2: Stm32h7Injector::new(p_inj2),
-        inj3: Stm32h7Injector::new(p_inj3),
-        inj4: Stm32h7Injector::new(p_inj4),
-
-        ing1: Stm32h7Coil::new(p_ign1),
-        ing2: Stm32h7Coil::new(p_ign2),
-        ing3: Stm32h7Coil::new(p_ign3),
-        ing4: Stm32h7Coil::new(p_ign4),
-
-        ckp: Stm32h7HallSensor::new(p_ckp),
-        cmp: Stm32h7HallSensor::new(p_cmp),
-
-        tps: p_tps,
-        map: p_map,
-        iat: p_iat,
-        cts: p_cts,
+        inj1: Stm32h7Injector::new(p_inj1, 0),
+        inj2: Stm32h7Injector::new(p_inj2, 1),
+        inj3: Stm32h7Injector::new(p_inj3, 2),
+        inj4: Stm32h7Injector::new(p_inj4, 3),
+
+        ing1: Stm32h7Coil::new(p_ign1, 4),
+        ing2: Stm32h7Coil::new(p_ign2, 5),
+        ing3: Stm32h7Coil::new(p_ign3, 6),
+        ing4: Stm32h7Coil::new(p_ign4, 7),
+
+        ckp: Stm32h7CaptureSensor::new(p_ckp, CaptureChannel::Ch1),
+        cmp: Stm32h7CaptureSensor::new(p_cmp, CaptureChannel::Ch3),
+
+        // El pin tiene pull-up: clutch pisado = LOW
+        clutch: Stm32h7Switch::new(p_clutch, true),
+
+        stim: Stm32h7TriggerStim::new(p_stim_ckp, p_stim_cmp),
+
+        tps: CalibratedSensor::new(analog_channel(p_tps, TPS_FILTER), DEFAULT_TPS),
+        tps2: analog_channel(p_tps2, TPS_FILTER),
+        map: CalibratedSensor::new(analog_channel(p_map, MAP_FILTER), DEFAULT_MAP),
+        iat: CalibratedSensor::new(analog_channel(p_iat, TEMP_FILTER), THERMISTOR),
+        cts: CalibratedSensor::new(analog_channel(p_cts, TEMP_FILTER), THERMISTOR),
+
+        wideband: analog_channel(p_wideband, O2_FILTER),
+        narrowband: FilteredInput::new(Stm32h7AnalogInput::new(p_narrowband, NARROWBAND_DIVIDER), O2_FILTER),
+
+        battery: BatteryMonitor::new(
+            FilteredInput::new(Stm32h7AnalogInput::new(p_battery, 1.0), BATTERY_FILTER),
+            BATTERY_CONFIG,
+        ),
     }
-}
\ No newline at end of file
+}
+
+/// Canal analógico de la placa con su cadena de filtros
+fn analog_channel<P>(pin: P, filter: FilterConfig) -> FilteredInput<Stm32h7AnalogInput<P>>
+where
+    P: embedded_hal::adc::Channel<crate::hal::device::ADC1, ID = u8>,
+{
+    FilteredInput::new(Stm32h7AnalogInput::new(pin, SENSOR_DIVIDER), filter)
+}
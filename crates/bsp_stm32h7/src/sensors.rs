@@ -1,11 +1,14 @@
-use ecu_traits::engine_io::RotationSensor;
+use ecu_traits::engine_io::{CapturedEdge, DigitalInput, EdgeCapture, RotationSensor};
 use embedded_hal::digital::v2::InputPin;
 use crate::hal::gpio::{ExtiPin, Edge}; // <--- Importamos Edge
-use crate::hal::device::{SYSCFG, EXTI}; // <--- Importamos los periféricos necesarios
+use crate::hal::device::{SYSCFG, EXTI, TIM3}; // <--- Importamos los periféricos necesarios
+use crate::timer::time_base_us;
 
 #[derive(Debug)]
 pub enum SensorError {
     ReadError,
+    /// El timer capturó un flanco nuevo antes de leer el anterior
+    Overrun,
 }
 
 
@@ -52,3 +55,181 @@ where
         self.pin.clear_interrupt_pending_bit();
     }
 }
+
+/// Driver generico para switches digitales (clutch, freno, etc.)
+/// P: El pin fisico (entrada con pull-up)
+pub struct Stm32h7Switch<P> {
+    pin: P,
+    // true si el switch cierra a tierra (activo en LOW)
+    active_low: bool,
+}
+
+impl<P> Stm32h7Switch<P>
+where
+    P: InputPin
+{
+    pub fn new(pin: P, active_low: bool) -> Self {
+        Self { pin, active_low }
+    }
+}
+
+impl<P> DigitalInput for Stm32h7Switch<P>
+where
+    P: InputPin
+{
+    type Error = SensorError;
+
+    fn is_active(&mut self) -> Result<bool, Self::Error> {
+        let high = self.pin.is_high().map_err(|_| SensorError::ReadError)?;
+        Ok(high != self.active_low)
+    }
+}
+
+/// Entrada de captura de TIM3. Cada entrada usa un par de canales sobre
+/// el mismo pin (TIx): el canal directo captura el flanco de subida y su
+/// vecino el de bajada, así la polaridad sale de la configuración y no del
+/// nivel del pin al momento de leer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureChannel {
+    /// Pin TIM3_CH1: IC1 subida, IC2 bajada
+    Ch1,
+    /// Pin TIM3_CH3: IC3 subida, IC4 bajada
+    Ch3,
+}
+
+impl CaptureChannel {
+    /// Canales (0..3) de subida y de bajada
+    fn pair(self) -> (usize, usize) {
+        match self {
+            CaptureChannel::Ch1 => (0, 1),
+            CaptureChannel::Ch3 => (2, 3),
+        }
+    }
+}
+
+/// Bandera de captura (CCxIF) y de sobre-captura (CCxOF) del canal en SR / DIER
+fn capture_masks(channel: usize) -> (u32, u32) {
+    let i = channel as u32;
+    (1 << (i + 1), 1 << (i + 9))
+}
+
+/// Driver de sensor de rotación con captura de entrada en TIM3.
+/// P: El pin físico en modo alterno (AF2 = TIM3_CHx)
+///
+/// TIM3 es de 16 bits pero corre a 1 MHz alineado con TIM2; la captura se
+/// extiende a 32 bits con la base de tiempo del timer de salidas, por lo que
+/// cada flanco debe leerse antes de 65 ms.
+pub struct Stm32h7CaptureSensor<P> {
+    pin: P,
+    channel: CaptureChannel,
+    // Con ambos canales pendientes se entrega el más antiguo y se guarda el otro
+    held: Option<CapturedEdge>,
+}
+
+impl<P> Stm32h7CaptureSensor<P>
+where
+    P: InputPin
+{
+    pub fn new(pin: P, channel: CaptureChannel) -> Self {
+        Self { pin, channel, held: None }
+    }
+
+    /// Configura el par de canales (subida y bajada) y habilita sus interrupciones.
+    /// TIM3 ya debe estar corriendo (lo arranca `Stm32h7OutputTimer::new`).
+    pub fn enable_capture(&mut self) {
+        let tim = unsafe { &*TIM3::ptr() };
+        let (rising, falling) = self.channel.pair();
+        let pair_ccer = 0xFFu32 << (4 * rising as u32);
+
+        // Los canales deben estar apagados para cambiar CCxS
+        tim.ccer.modify(|r, w| unsafe { w.bits(r.bits() & !pair_ccer) });
+
+        // Canal directo CCxS = 01 (TIx), vecino CCyS = 10 (la misma TIx);
+        // ICxF = 0011 (filtro de 8 muestras contra ruido) en ambos
+        let ccmr = 0b0011_0001 | (0b0011_0010 << 8);
+        match self.channel {
+            CaptureChannel::Ch1 => tim.ccmr1_input().modify(|r, w| unsafe {
+                w.bits((r.bits() & !0xFFFF) | ccmr)
+            }),
+            CaptureChannel::Ch3 => tim.ccmr2_input().modify(|r, w| unsafe {
+                w.bits((r.bits() & !0xFFFF) | ccmr)
+            }),
+        }
+
+        // Directo: CCxP = 0 (subida), CCxE = 1. Vecino: CCyP = 1 (bajada), CCyE = 1
+        let ccer = (0b0001 << (4 * rising as u32)) | (0b0011 << (4 * falling as u32));
+        tim.ccer.modify(|r, w| unsafe { w.bits(r.bits() | ccer) });
+
+        let flags = capture_masks(rising).0 | capture_masks(falling).0;
+        tim.dier.modify(|r, w| unsafe { w.bits(r.bits() | flags) });
+    }
+}
+
+impl<P> EdgeCapture for Stm32h7CaptureSensor<P>
+where
+    P: InputPin
+{
+    type Error = SensorError;
+
+    /// Entrega el flanco más antiguo pendiente del par de canales
+    fn read_edge(&mut self) -> Result<Option<CapturedEdge>, Self::Error> {
+        // Un flanco guardado ocurrió antes que cualquier captura nueva
+        if let Some(edge) = self.held.take() {
+            return Ok(Some(edge));
+        }
+
+        let tim = unsafe { &*TIM3::ptr() };
+        let (rising, falling) = self.channel.pair();
+        let sr = tim.sr.read().bits();
+
+        let overcapture = capture_masks(rising).1 | capture_masks(falling).1;
+        if sr & overcapture != 0 {
+            // SR es rc_w0: escribir 0 limpia la bandera
+            tim.sr.write(|w| unsafe { w.bits(!overcapture) });
+            return Err(SensorError::Overrun);
+        }
+
+        // La base de tiempo se lee después de las banderas: las capturas
+        // pendientes ya ocurrieron. Leer CCR limpia CCxIF.
+        let now = time_base_us();
+        let edge = |channel: usize, is_rising: bool| {
+            let captured = tim.ccr[channel].read().bits() as u16;
+            let age = (now as u16).wrapping_sub(captured);
+            (age, CapturedEdge { timestamp_us: now.wrapping_sub(age as u32), rising: is_rising })
+        };
+        let pending_rising = sr & capture_masks(rising).0 != 0;
+        let pending_falling = sr & capture_masks(falling).0 != 0;
+
+        Ok(match (pending_rising, pending_falling) {
+            (false, false) => None,
+            (true, false) => Some(edge(rising, true).1),
+            (false, true) => Some(edge(falling, false).1),
+            (true, true) => {
+                let (rise_age, rise) = edge(rising, true);
+                let (fall_age, fall) = edge(falling, false);
+                // El de mayor edad ocurrió primero
+                let (first, second) = if rise_age >= fall_age { (rise, fall) } else { (fall, rise) };
+                self.held = Some(second);
+                Some(first)
+            }
+        })
+    }
+}
+
+impl<P> RotationSensor for Stm32h7CaptureSensor<P>
+where
+    P: InputPin
+{
+    type Error = SensorError;
+
+    fn get_state(&mut self) -> Result<bool, Self::Error> {
+        self.pin.is_high().map_err(|_| SensorError::ReadError)
+    }
+
+    fn clear_sensor_flag(&mut self) {
+        let tim = unsafe { &*TIM3::ptr() };
+        let (rising, falling) = self.channel.pair();
+        let flags = capture_masks(rising).0 | capture_masks(falling).0;
+        tim.sr.write(|w| unsafe { w.bits(!flags) });
+    }
+}
@@ -0,0 +1,86 @@
+use ecu_traits::engine_io::CapturedEdge;
+use embedded_hal::digital::v2::OutputPin;
+use crate::timer::time_base_us;
+
+/// Atraso (µs) a partir del cual un flanco se cuenta como tarde
+const LATE_US: u32 = 20;
+
+/// Salida del estimulador
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StimOutput {
+    Crank,
+    Cam,
+}
+
+/// Estimulador de banco: reproduce una línea de tiempo de flancos de
+/// cigüeñal y leva en dos pines libres para probar otra ECU (o esta misma
+/// con un puente a CKP/CMP).
+///
+/// Funciona por sondeo contra la base de tiempo de la ECU: `poll` se llama
+/// seguido desde el lazo principal y escribe los flancos que ya vencieron.
+/// C, M: pines de salida de cigüeñal y leva
+pub struct Stm32h7TriggerStim<C, M> {
+    crank: C,
+    cam: M,
+    pending: Option<(StimOutput, CapturedEdge)>,
+    late_edges: u32,
+}
+
+impl<C, M> Stm32h7TriggerStim<C, M>
+where
+    C: OutputPin,
+    M: OutputPin,
+{
+    pub fn new(crank: C, cam: M) -> Self {
+        Self { crank, cam, pending: None, late_edges: 0 }
+    }
+
+    /// Base de tiempo de la ECU (µs); sirve para alinear el inicio de la línea de tiempo
+    pub fn now_us(&self) -> u32 {
+        time_base_us()
+    }
+
+    /// Escribe los flancos vencidos de `edges` (en orden de tiempo).
+    /// El primer flanco futuro queda pendiente para la siguiente llamada.
+    /// Regresa cuántos flancos se escribieron.
+    pub fn poll<I>(&mut self, edges: &mut I) -> usize
+    where
+        I: Iterator<Item = (StimOutput, CapturedEdge)>,
+    {
+        let mut written = 0;
+        loop {
+            let Some((output, edge)) = self.pending.take().or_else(|| edges.next()) else {
+                return written;
+            };
+            let late = time_base_us().wrapping_sub(edge.timestamp_us) as i32;
+            if late < 0 {
+                self.pending = Some((output, edge));
+                return written;
+            }
+            if late as u32 > LATE_US {
+                self.late_edges = self.late_edges.wrapping_add(1);
+            }
+
+            // Los pines GPIO de este micro no regresan errores
+            match (output, edge.rising) {
+                (StimOutput::Crank, true) => { let _ = self.crank.set_high(); }
+                (StimOutput::Crank, false) => { let _ = self.crank.set_low(); }
+                (StimOutput::Cam, true) => { let _ = self.cam.set_high(); }
+                (StimOutput::Cam, false) => { let _ = self.cam.set_low(); }
+            }
+            written += 1;
+        }
+    }
+
+    /// Descarta el flanco pendiente y deja ambas salidas en bajo
+    pub fn stop(&mut self) {
+        self.pending = None;
+        let _ = self.crank.set_low();
+        let _ = self.cam.set_low();
+    }
+
+    /// Flancos escritos con más de 20 µs de atraso (el lazo no alcanza a la señal)
+    pub fn late_edges(&self) -> u32 {
+        self.late_edges
+    }
+}
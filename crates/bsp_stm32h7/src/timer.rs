@@ -0,0 +1,315 @@
+use core::cell::Cell;
+use core::ops::Range;
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::NVIC;
+use ecu_traits::timer::{CompareAction, TimerBackend};
+use crate::hal::device::{TIM2, TIM3, TIM4, TIM5};
+use crate::hal::gpio::PinExt;
+use crate::hal::pac::tim2::RegisterBlock;
+use crate::hal::pac::{gpioa, interrupt, Interrupt, GPIOA};
+use crate::hal::prelude::*;
+use crate::hal::rcc::{rec, CoreClocks};
+use crate::hal::timer::Timer;
+
+/// Canales por timer (CC1..CC4)
+const CHANNELS_PER_TIMER: u8 = 4;
+
+/// Un instante a menos de este margen (µs) se da por vencido al programarlo
+const ARM_MARGIN_US: i32 = 1;
+
+/// Canales del timer
+/// 0..3 = inyectores 1..4 (TIM5 CC1..CC4)
+/// 4..7 = bobinas 1..4 (TIM2 CC1..CC4)
+/// 8..11 = canales por software sin pin (TIM4 CC1..CC4), ej. ventana y
+///         conversiones del muestreo de MAP
+pub const INJECTOR_CHANNELS: Range<u8> = 0..4;
+pub const COIL_CHANNELS: Range<u8> = 4..8;
+pub const SOFTWARE_CHANNELS: Range<u8> = 8..12;
+
+/// Total de canales
+const CHANNEL_COUNT: usize = 12;
+
+/// Aviso a la aplicación de un compare ejecutado (canal, acción), desde la
+/// interrupción y después de mover el pin. Con `IsrTimer` se programa lo
+/// que sigue (ej. `AngleScheduler::on_compare`).
+pub type CompareHandler = fn(u8, CompareAction, &mut IsrTimer);
+
+static COMPARE_HANDLER: Mutex<Cell<Option<CompareHandler>>> = Mutex::new(Cell::new(None));
+
+/// Timer de salidas visto desde el handler de compare (mismos canales y
+/// base de tiempo que `Stm32h7OutputTimer`)
+pub struct IsrTimer {
+    _private: (),
+}
+
+impl TimerBackend for IsrTimer {
+    fn now_us(&mut self) -> u32 {
+        time_base_us()
+    }
+
+    fn arm(&mut self, channel: u8, at_us: u32, action: CompareAction) {
+        arm_channel(channel, at_us, action);
+    }
+
+    fn cancel(&mut self, channel: u8) {
+        cancel_channel(channel);
+    }
+}
+
+// Pin de cada canal de salida (puerto << 4 | pin); lo registra el driver
+// al crearse y la interrupción lo mueve con BSRR (escritura atómica)
+const NO_PIN: u8 = 0xFF;
+static OUTPUT_PINS: [AtomicU8; COIL_CHANNELS.end as usize] =
+    [const { AtomicU8::new(NO_PIN) }; COIL_CHANNELS.end as usize];
+
+/// Registra el pin que mueve el compare del canal (activo en alto)
+pub(crate) fn bind_output<P: PinExt>(channel: u8, pin: &P) {
+    if let Some(slot) = OUTPUT_PINS.get(channel as usize) {
+        slot.store(pin.port_id() << 4 | pin.pin_id(), Ordering::Release);
+    }
+}
+
+fn drive_output(channel: u8, action: CompareAction) {
+    let code = OUTPUT_PINS[channel as usize].load(Ordering::Acquire);
+    if code == NO_PIN {
+        return;
+    }
+    // Los bloques GPIO están cada 0x400 a partir de GPIOA
+    let port = (GPIOA::ptr() as usize + 0x400 * (code >> 4) as usize) as *const gpioa::RegisterBlock;
+    let pin = code & 0x0F;
+    let bit = match action {
+        CompareAction::Activate => 1u32 << pin,
+        CompareAction::Deactivate => 1u32 << (pin + 16),
+    };
+    unsafe { (*port).bsrr.write(|w| w.bits(bit)) };
+}
+
+// Acción pendiente de cada canal (0 = ninguna). Es compartida porque los
+// drivers de inyector programan su propio cierre sin pasar por el timer.
+const NO_ACTION: u8 = 0;
+static ACTIONS: [AtomicU8; CHANNEL_COUNT] = [const { AtomicU8::new(NO_ACTION) }; CHANNEL_COUNT];
+
+// Instante completo de cada canal. TIM4 es de 16 bits y su compare
+// coincide cada 65 ms: solo cuenta cuando la base de 32 bits ya llegó.
+static TARGETS: [AtomicU32; CHANNEL_COUNT] = [const { AtomicU32::new(0) }; CHANNEL_COUNT];
+
+fn encode_action(action: CompareAction) -> u8 {
+    match action {
+        CompareAction::Activate => 1,
+        CompareAction::Deactivate => 2,
+    }
+}
+
+fn decode_action(code: u8) -> Option<CompareAction> {
+    match code {
+        1 => Some(CompareAction::Activate),
+        2 => Some(CompareAction::Deactivate),
+        _ => None,
+    }
+}
+
+/// Registros del timer del canal y su número de compare (0..3)
+fn registers(channel: u8) -> (&'static RegisterBlock, u8) {
+    // Solo se tocan CCR, SR, DIER y EGR del canal; la configuración del
+    // contador es de Stm32h7OutputTimer
+    unsafe {
+        if COIL_CHANNELS.contains(&channel) {
+            (&*TIM2::ptr(), channel - COIL_CHANNELS.start)
+        } else if SOFTWARE_CHANNELS.contains(&channel) {
+            // TIM4 tiene el mismo mapa de registros que TIM2 (CCR de 16 bits)
+            (&*(TIM4::ptr() as *const RegisterBlock), channel - SOFTWARE_CHANNELS.start)
+        } else {
+            (&*TIM5::ptr(), channel % CHANNELS_PER_TIMER)
+        }
+    }
+}
+
+/// Programa la acción del canal en `at_us`; si ya pasó se ejecuta de inmediato
+pub(crate) fn arm_channel(channel: u8, at_us: u32, action: CompareAction) {
+    if channel as usize >= CHANNEL_COUNT {
+        return;
+    }
+    TARGETS[channel as usize].store(at_us, Ordering::Relaxed);
+    ACTIONS[channel as usize].store(encode_action(action), Ordering::Release);
+
+    let (regs, cc) = registers(channel);
+    let mask = 1u32 << (cc + 1);
+    let ccr = if SOFTWARE_CHANNELS.contains(&channel) { at_us & 0xFFFF } else { at_us };
+    regs.ccr[cc as usize].write(|w| w.ccr().bits(ccr));
+    regs.sr.write(|w| unsafe { w.bits(!mask) });
+    regs.dier.modify(|r, w| unsafe { w.bits(r.bits() | mask) });
+
+    // Si el instante ya pasó el compare no llegaría hasta la vuelta del
+    // contador (71 min): se genera el evento por software para ejecutarlo ya.
+    // El tiempo se lee después de escribir CCR, así un instante que vence
+    // mientras se programa el canal tampoco se pierde.
+    if !is_future(at_us, time_base_us()) {
+        regs.egr.write(|w| unsafe { w.bits(mask) });
+    }
+}
+
+/// true si `at_us` todavía no llega (con margen para que el contador no lo
+/// alcance entre la lectura y la comparación del hardware)
+fn is_future(at_us: u32, now_us: u32) -> bool {
+    at_us.wrapping_sub(now_us) as i32 > ARM_MARGIN_US
+}
+
+/// Cancela la acción pendiente del canal
+pub(crate) fn cancel_channel(channel: u8) {
+    if channel as usize >= CHANNEL_COUNT {
+        return;
+    }
+    ACTIONS[channel as usize].store(NO_ACTION, Ordering::Release);
+    let (regs, cc) = registers(channel);
+    let mask = 1u32 << (cc + 1);
+    regs.dier.modify(|r, w| unsafe { w.bits(r.bits() & !mask) });
+    regs.sr.write(|w| unsafe { w.bits(!mask) });
+}
+
+/// Base de tiempo de la ECU en µs (contador de TIM2).
+/// Solo lectura: la usan los drivers de captura para extender sus 16 bits
+/// y los inyectores para programar su cierre.
+pub(crate) fn time_base_us() -> u32 {
+    unsafe { (*TIM2::ptr()).cnt.read().bits() }
+}
+
+/// Timer de salidas con output compare sobre TIM2 y TIM5 (32 bits, 1 MHz).
+/// Ambos corren sincronizados y forman una sola base de tiempo; TIM3
+/// (16 bits, captura de CKP/CMP) y TIM4 (16 bits, canales por software)
+/// arrancan con ellos y quedan alineados con los 16 bits bajos.
+///
+/// Las interrupciones de compare son del BSP: `new` las habilita y sus
+/// handlers (TIM2 bobinas, TIM5 inyectores, TIM4 canales por software)
+/// mueven el pin que registró el driver del canal sin pasar por la
+/// aplicación; la aplicación no debe declarar esos vectores. Después se
+/// llama al handler de `set_compare_handler`, si hay, para encadenar el
+/// siguiente evento (ej. el cierre de `AngleScheduler` o la ventana de
+/// `MapSampler`). Los canales de inyector también los programa el propio
+/// driver con `pulse_us`.
+pub struct Stm32h7OutputTimer {
+    coils: Timer<TIM2>,
+    // Solo se guardan para que nadie más los reconfigure
+    _injectors: Timer<TIM5>,
+    _capture: Timer<TIM3>,
+    _software: Timer<TIM4>,
+}
+
+impl Stm32h7OutputTimer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        tim2: TIM2,
+        rec2: rec::Tim2,
+        tim5: TIM5,
+        rec5: rec::Tim5,
+        tim3: TIM3,
+        rec3: rec::Tim3,
+        tim4: TIM4,
+        rec4: rec::Tim4,
+        clocks: &CoreClocks,
+    ) -> Self {
+        // 1 tick = 1 µs, ARR al máximo de 32 bits
+        let mut coils = tim2.tick_timer(1.MHz(), rec2, clocks);
+        let mut injectors = tim5.tick_timer(1.MHz(), rec5, clocks);
+        let mut capture = tim3.tick_timer(1.MHz(), rec3, clocks);
+        let mut software = tim4.tick_timer(1.MHz(), rec4, clocks);
+
+        // Arrancamos los contadores juntos para compartir la base de tiempo
+        coils.pause();
+        injectors.pause();
+        capture.pause();
+        software.pause();
+        coils.reset_counter();
+        injectors.reset_counter();
+        capture.reset_counter();
+        software.reset_counter();
+        coils.resume();
+        injectors.resume();
+        capture.resume();
+        software.resume();
+
+        // Sin estas interrupciones ningún compare llega a los pines
+        unsafe {
+            NVIC::unmask(Interrupt::TIM2);
+            NVIC::unmask(Interrupt::TIM4);
+            NVIC::unmask(Interrupt::TIM5);
+        }
+
+        Self { coils, _injectors: injectors, _capture: capture, _software: software }
+    }
+
+    /// Aviso de cada compare ejecutado. Los pines se mueven aunque no haya
+    /// handler; los canales por software solo tienen efecto a través de él.
+    pub fn set_compare_handler(&mut self, handler: CompareHandler) {
+        cortex_m::interrupt::free(|cs| COMPARE_HANDLER.borrow(cs).set(Some(handler)));
+    }
+}
+
+/// Toma la acción del canal si su compare ya venció (limpia bandera e interrupción)
+fn take_fired(channel: u8, now_us: u32) -> Option<CompareAction> {
+    let (regs, cc) = registers(channel);
+    let mask = 1u32 << (cc + 1);
+    let enabled = regs.dier.read().bits() & mask != 0;
+    let flagged = regs.sr.read().bits() & mask != 0;
+    if !(enabled && flagged) {
+        return None;
+    }
+
+    // SR es rc_w0: escribir 0 limpia la bandera, 1 no tiene efecto
+    regs.sr.write(|w| unsafe { w.bits(!mask) });
+    // Coincidencia de los 16 bits de TIM4 antes del instante real:
+    // el canal sigue armado para la siguiente vuelta
+    if SOFTWARE_CHANNELS.contains(&channel) && is_future(TARGETS[channel as usize].load(Ordering::Relaxed), now_us) {
+        return None;
+    }
+    regs.dier.modify(|r, w| unsafe { w.bits(r.bits() & !mask) });
+
+    decode_action(ACTIONS[channel as usize].swap(NO_ACTION, Ordering::AcqRel))
+}
+
+/// Ejecuta los compares vencidos de un timer
+fn dispatch(channels: Range<u8>) {
+    let now_us = time_base_us();
+    for channel in channels {
+        let Some(action) = take_fired(channel, now_us) else {
+            continue;
+        };
+        if !SOFTWARE_CHANNELS.contains(&channel) {
+            drive_output(channel, action);
+        }
+        let handler = cortex_m::interrupt::free(|cs| COMPARE_HANDLER.borrow(cs).get());
+        if let Some(handler) = handler {
+            handler(channel, action, &mut IsrTimer { _private: () });
+        }
+    }
+}
+
+#[interrupt]
+fn TIM2() {
+    dispatch(COIL_CHANNELS);
+}
+
+#[interrupt]
+fn TIM5() {
+    dispatch(INJECTOR_CHANNELS);
+}
+
+#[interrupt]
+fn TIM4() {
+    dispatch(SOFTWARE_CHANNELS);
+}
+
+impl TimerBackend for Stm32h7OutputTimer {
+    fn now_us(&mut self) -> u32 {
+        self.coils.counter()
+    }
+
+    fn arm(&mut self, channel: u8, at_us: u32, action: CompareAction) {
+        arm_channel(channel, at_us, action);
+    }
+
+    fn cancel(&mut self, channel: u8) {
+        cancel_channel(channel);
+    }
+}
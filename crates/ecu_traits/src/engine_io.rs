@@ -2,12 +2,22 @@
 pub trait Injector {
     type Error;
 
-    /// Abre el inyector por una duración específica en microsegundos.
-    /// Esta función debe ser NO bloqueante (el hardware hace el trabajo).
+    /// Abre el inyector hasta que se llame a `close`
     fn open(&mut self) -> Result<(), Self::Error>;
     
-    /// (Opcional) Cierra de emergencia
+    /// Cierra el inyector y cancela el cierre programado (si había uno)
     fn close(&mut self) -> Result<(), Self::Error>;
+
+    /// Abre el inyector por una duración específica en microsegundos.
+    /// Esta función debe ser NO bloqueante (el hardware hace el trabajo).
+    fn pulse_us(&mut self, duration_us: u32) -> Result<(), Self::Error>;
+
+    /// Abre el inyector y lo cierra en `close_at_us` (base de tiempo del
+    /// timer de salidas). Un instante que ya pasó lo cierra de inmediato.
+    fn pulse_until(&mut self, close_at_us: u32) -> Result<(), Self::Error>;
+
+    /// true mientras el inyector está abierto
+    fn is_open(&self) -> bool;
 }
 
 // Interface para la bobina de encendido
@@ -34,4 +44,48 @@ pub trait RotationSensor {
 
     /// Limpiar bandera de la interrupcion pendiente de HW
     fn clear_sensor_flag(&mut self);
-}
\ No newline at end of file
+}
+
+/// Interface para entradas digitales tipo switch (clutch, freno, etc.)
+/// La implementación se encarga de la polaridad del cableado.
+pub trait DigitalInput {
+    type Error;
+
+    /// Regresa true si el switch está activo (ej. pedal de clutch pisado)
+    fn is_active(&mut self) -> Result<bool, Self::Error>;
+}
+
+/// Flanco de un sensor de rotación con la marca de tiempo tomada por hardware
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapturedEdge {
+    /// Instante del flanco en µs (misma base de tiempo que el timer de salidas)
+    pub timestamp_us: u32,
+    /// true = flanco de subida
+    pub rising: bool,
+}
+
+/// Interface para sensores de rotación con captura de entrada (input capture).
+/// El timer guarda el instante del flanco, así la latencia de la interrupción
+/// no afecta la medición de los dientes.
+pub trait EdgeCapture {
+    type Error;
+
+    /// Regresa el siguiente flanco capturado o None si no hay uno nuevo.
+    /// Error si el hardware perdió una captura (sobre-captura).
+    fn read_edge(&mut self) -> Result<Option<CapturedEdge>, Self::Error>;
+}
+
+/// Canal analógico: voltaje en la terminal del sensor (ya descontado el
+/// divisor de acondicionamiento de la placa)
+pub trait AnalogInput {
+    type Error;
+
+    fn read_volts(&mut self) -> Result<f32, Self::Error>;
+}
+
+/// Sensor analógico en unidades de ingeniería (kPa, %, °C, etc.)
+pub trait AnalogSensor {
+    type Error;
+
+    fn read(&mut self) -> Result<f32, Self::Error>;
+}
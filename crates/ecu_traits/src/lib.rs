@@ -1,4 +1,8 @@
 #![no_std]
 
 // Definimos el módulo (asegúrate de crear el archivo engine_io.rs también)
-pub mod engine_io;
\ No newline at end of file
+pub mod engine_io;
+pub mod timer;
+// Simulaciones para PC: solo con la feature `mock` (no entran al firmware)
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
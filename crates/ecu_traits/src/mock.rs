@@ -0,0 +1,684 @@
+//! Implementaciones simuladas de los traits para correr la lógica en PC.
+//! Las salidas registran sus cambios con marca de tiempo, las entradas
+//! siguen un guion y todas aceptan fallas inyectadas (`Faults`).
+
+use crate::engine_io::{
+    AnalogInput, CapturedEdge, DigitalInput, EdgeCapture, IgnitionCoil, Injector, RotationSensor,
+};
+use crate::timer::{CompareAction, TimerBackend};
+
+/// Error de los drivers simulados
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockError {
+    /// Falla provocada con `Faults`
+    Injected,
+}
+
+/// Inyección de fallas: hace que las llamadas de un driver simulado
+/// regresen error. Una llamada que falla no cambia el estado del driver.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Faults {
+    fail_next: u32,
+    always: bool,
+    injected: u32,
+}
+
+impl Faults {
+    /// Las siguientes `n` llamadas fallan
+    pub fn fail_next(&mut self, n: u32) {
+        self.fail_next = n;
+    }
+
+    /// Todas las llamadas fallan hasta volver a llamar con false
+    pub fn set_failing(&mut self, failing: bool) {
+        self.always = failing;
+    }
+
+    /// Fallas entregadas hasta ahora
+    pub fn injected(&self) -> u32 {
+        self.injected
+    }
+
+    /// Consume una llamada: Err si le toca fallar
+    pub fn check(&mut self) -> Result<(), MockError> {
+        if self.always || self.fail_next > 0 {
+            self.fail_next = self.fail_next.saturating_sub(1);
+            self.injected = self.injected.wrapping_add(1);
+            return Err(MockError::Injected);
+        }
+        Ok(())
+    }
+}
+
+/// Cambio de una salida registrado por el timer simulado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PinChange {
+    pub timestamp_us: u32,
+    pub channel: u8,
+    pub action: CompareAction,
+}
+
+impl PinChange {
+    const EMPTY: PinChange = PinChange { timestamp_us: 0, channel: 0, action: CompareAction::Deactivate };
+}
+
+/// Timer de salidas simulado.
+/// C: canales, L: cambios que se guardan en la línea de tiempo
+///
+/// El tiempo solo avanza con `run_until`, que ejecuta en orden las acciones
+/// que vencen y registra el estado de cada salida.
+///
+/// # Ejemplo
+///
+/// ```
+/// use ecu_traits::mock::SimTimer;
+/// use ecu_traits::timer::{CompareAction, TimerBackend};
+///
+/// let mut timer: SimTimer<2, 8> = SimTimer::new();
+/// timer.arm(1, 500, CompareAction::Activate);
+///
+/// // El callback puede volver a programar el canal (ej. el cierre del pulso)
+/// timer.run_until(2_000, |timer, channel, action| {
+///     if action == CompareAction::Activate {
+///         timer.arm(channel, 1_500, CompareAction::Deactivate);
+///     }
+/// });
+///
+/// assert_eq!(timer.timeline().len(), 2);
+/// assert_eq!(timer.timeline()[1].timestamp_us, 1_500);
+/// assert!(!timer.pin(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SimTimer<const C: usize, const L: usize> {
+    now_us: u32,
+    armed: [Option<(u32, CompareAction)>; C],
+    pins: [bool; C],
+    timeline: [PinChange; L],
+    len: usize,
+    overflowed: bool,
+}
+
+impl<const C: usize, const L: usize> SimTimer<C, L> {
+    pub fn new() -> Self {
+        Self {
+            now_us: 0,
+            armed: [None; C],
+            pins: [false; C],
+            timeline: [PinChange::EMPTY; L],
+            len: 0,
+            overflowed: false,
+        }
+    }
+
+    /// Mueve el reloj sin ejecutar acciones (ej. para simular una interrupción tardía)
+    pub fn set_now(&mut self, now_us: u32) {
+        self.now_us = now_us;
+    }
+
+    /// Avanza hasta `until_us` ejecutando en orden las acciones que vencen.
+    /// `on_compare` se llama después de cada acción, como la interrupción del timer.
+    pub fn run_until<F>(&mut self, until_us: u32, mut on_compare: F)
+    where
+        F: FnMut(&mut Self, u8, CompareAction),
+    {
+        while let Some((channel, at_us, action)) = self.next_due(until_us) {
+            // Una acción atrasada se ejecuta en el instante actual
+            if at_us.wrapping_sub(self.now_us) as i32 > 0 {
+                self.now_us = at_us;
+            }
+            self.armed[channel] = None;
+            self.pins[channel] = action == CompareAction::Activate;
+            self.record(PinChange { timestamp_us: self.now_us, channel: channel as u8, action });
+            on_compare(self, channel as u8, action);
+        }
+        if until_us.wrapping_sub(self.now_us) as i32 > 0 {
+            self.now_us = until_us;
+        }
+    }
+
+    /// Estado actual de la salida (true = activa)
+    pub fn pin(&self, channel: u8) -> bool {
+        self.pins.get(channel as usize).copied().unwrap_or(false)
+    }
+
+    /// Acción pendiente del canal
+    pub fn armed(&self, channel: u8) -> Option<(u32, CompareAction)> {
+        self.armed.get(channel as usize).copied().flatten()
+    }
+
+    /// Cambios registrados en orden
+    pub fn timeline(&self) -> &[PinChange] {
+        &self.timeline[..self.len]
+    }
+
+    /// Cambios registrados de un canal
+    pub fn changes(&self, channel: u8) -> impl Iterator<Item = PinChange> + '_ {
+        self.timeline().iter().copied().filter(move |c| c.channel == channel)
+    }
+
+    /// true si se llenó la línea de tiempo y se perdieron cambios
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    pub fn clear_timeline(&mut self) {
+        self.len = 0;
+        self.overflowed = false;
+    }
+
+    fn next_due(&self, until_us: u32) -> Option<(usize, u32, CompareAction)> {
+        let mut best: Option<(usize, u32, CompareAction)> = None;
+        for (channel, armed) in self.armed.iter().enumerate() {
+            let Some((at_us, action)) = *armed else { continue };
+            // Tiempo relativo al instante actual; lo atrasado cuenta como 0
+            let due_in = (at_us.wrapping_sub(self.now_us) as i32).max(0) as u32;
+            if due_in > until_us.wrapping_sub(self.now_us) {
+                continue;
+            }
+            let earlier = match best {
+                Some((_, best_at, _)) => due_in < (best_at.wrapping_sub(self.now_us) as i32).max(0) as u32,
+                None => true,
+            };
+            if earlier {
+                best = Some((channel, at_us, action));
+            }
+        }
+        best
+    }
+
+    fn record(&mut self, change: PinChange) {
+        if self.len < L {
+            self.timeline[self.len] = change;
+            self.len += 1;
+        } else {
+            self.overflowed = true;
+        }
+    }
+}
+
+impl<const C: usize, const L: usize> Default for SimTimer<C, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const C: usize, const L: usize> TimerBackend for SimTimer<C, L> {
+    fn now_us(&mut self) -> u32 {
+        self.now_us
+    }
+
+    fn arm(&mut self, channel: u8, at_us: u32, action: CompareAction) {
+        if let Some(slot) = self.armed.get_mut(channel as usize) {
+            *slot = Some((at_us, action));
+        }
+    }
+
+    fn cancel(&mut self, channel: u8) {
+        if let Some(slot) = self.armed.get_mut(channel as usize) {
+            *slot = None;
+        }
+    }
+}
+
+/// Pulso de inyección registrado por el inyector simulado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InjectorPulse {
+    pub open_us: u32,
+    pub close_us: u32,
+}
+
+impl InjectorPulse {
+    const EMPTY: InjectorPulse = InjectorPulse { open_us: 0, close_us: 0 };
+
+    pub fn width_us(&self) -> u32 {
+        self.close_us.wrapping_sub(self.open_us)
+    }
+}
+
+/// Inyector simulado que registra los tiempos de cada pulso.
+/// L: pulsos que se guardan
+///
+/// El tiempo avanza con `set_now`; un cierre programado que vence en ese
+/// intervalo se registra en su instante exacto.
+///
+/// # Ejemplo
+///
+/// ```
+/// use ecu_traits::engine_io::Injector;
+/// use ecu_traits::mock::MockInjector;
+///
+/// let mut injector: MockInjector<4> = MockInjector::new();
+/// injector.set_now(1_000);
+/// injector.pulse_us(2_500).unwrap();
+/// assert!(injector.is_open());
+///
+/// injector.set_now(10_000);
+/// assert!(!injector.is_open());
+/// assert_eq!(injector.pulses()[0].close_us, 3_500);
+/// assert_eq!(injector.pulses()[0].width_us(), 2_500);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockInjector<const L: usize> {
+    now_us: u32,
+    open_since: Option<u32>,
+    close_at: Option<u32>,
+    pulses: [InjectorPulse; L],
+    len: usize,
+    overflowed: bool,
+    faults: Faults,
+}
+
+impl<const L: usize> MockInjector<L> {
+    pub fn new() -> Self {
+        Self {
+            now_us: 0,
+            open_since: None,
+            close_at: None,
+            pulses: [InjectorPulse::EMPTY; L],
+            len: 0,
+            overflowed: false,
+            faults: Faults::default(),
+        }
+    }
+
+    /// Avanza el reloj y ejecuta el cierre programado si vence
+    pub fn set_now(&mut self, now_us: u32) {
+        if let Some(close_at) = self.close_at {
+            if close_at.wrapping_sub(now_us) as i32 <= 0 {
+                self.finish(close_at);
+            }
+        }
+        self.now_us = now_us;
+    }
+
+    /// Pulsos completos en orden
+    pub fn pulses(&self) -> &[InjectorPulse] {
+        &self.pulses[..self.len]
+    }
+
+    /// Cierre programado pendiente
+    pub fn close_at(&self) -> Option<u32> {
+        self.close_at
+    }
+
+    /// true si se llenó el registro y se perdieron pulsos
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    pub fn clear_pulses(&mut self) {
+        self.len = 0;
+        self.overflowed = false;
+    }
+
+    pub fn faults(&mut self) -> &mut Faults {
+        &mut self.faults
+    }
+
+    fn finish(&mut self, close_us: u32) {
+        self.close_at = None;
+        let Some(open_us) = self.open_since.take() else { return };
+        if self.len < L {
+            self.pulses[self.len] = InjectorPulse { open_us, close_us };
+            self.len += 1;
+        } else {
+            self.overflowed = true;
+        }
+    }
+}
+
+impl<const L: usize> Default for MockInjector<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const L: usize> Injector for MockInjector<L> {
+    type Error = MockError;
+
+    fn open(&mut self) -> Result<(), Self::Error> {
+        self.faults.check()?;
+        self.open_since.get_or_insert(self.now_us);
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), Self::Error> {
+        self.faults.check()?;
+        self.finish(self.now_us);
+        Ok(())
+    }
+
+    fn pulse_us(&mut self, duration_us: u32) -> Result<(), Self::Error> {
+        self.pulse_until(self.now_us.wrapping_add(duration_us))
+    }
+
+    fn pulse_until(&mut self, close_at_us: u32) -> Result<(), Self::Error> {
+        self.faults.check()?;
+        self.open_since.get_or_insert(self.now_us);
+        // Igual que el hardware: un instante que ya pasó cierra de inmediato
+        if close_at_us.wrapping_sub(self.now_us) as i32 <= 0 {
+            self.finish(self.now_us);
+        } else {
+            self.close_at = Some(close_at_us);
+        }
+        Ok(())
+    }
+
+    fn is_open(&self) -> bool {
+        self.open_since.is_some()
+    }
+}
+
+/// Chispa registrada por la bobina simulada
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SparkRecord {
+    pub dwell_start_us: u32,
+    pub fire_us: u32,
+}
+
+impl SparkRecord {
+    const EMPTY: SparkRecord = SparkRecord { dwell_start_us: 0, fire_us: 0 };
+
+    pub fn dwell_us(&self) -> u32 {
+        self.fire_us.wrapping_sub(self.dwell_start_us)
+    }
+}
+
+/// Bobina simulada que registra el inicio del dwell y el disparo de cada chispa.
+/// L: chispas que se guardan
+#[derive(Debug, Clone)]
+pub struct MockCoil<const L: usize> {
+    now_us: u32,
+    dwell_since: Option<u32>,
+    sparks: [SparkRecord; L],
+    len: usize,
+    overflowed: bool,
+    fires_without_dwell: u32,
+    faults: Faults,
+}
+
+impl<const L: usize> MockCoil<L> {
+    pub fn new() -> Self {
+        Self {
+            now_us: 0,
+            dwell_since: None,
+            sparks: [SparkRecord::EMPTY; L],
+            len: 0,
+            overflowed: false,
+            fires_without_dwell: 0,
+            faults: Faults::default(),
+        }
+    }
+
+    pub fn set_now(&mut self, now_us: u32) {
+        self.now_us = now_us;
+    }
+
+    /// Chispas en orden
+    pub fn sparks(&self) -> &[SparkRecord] {
+        &self.sparks[..self.len]
+    }
+
+    /// true mientras la bobina está cargando
+    pub fn is_dwelling(&self) -> bool {
+        self.dwell_since.is_some()
+    }
+
+    /// Disparos pedidos sin dwell previo (no producen chispa)
+    pub fn fires_without_dwell(&self) -> u32 {
+        self.fires_without_dwell
+    }
+
+    /// true si se llenó el registro y se perdieron chispas
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    pub fn clear_sparks(&mut self) {
+        self.len = 0;
+        self.overflowed = false;
+    }
+
+    pub fn faults(&mut self) -> &mut Faults {
+        &mut self.faults
+    }
+}
+
+impl<const L: usize> Default for MockCoil<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const L: usize> IgnitionCoil for MockCoil<L> {
+    type Error = MockError;
+
+    fn start_dwell(&mut self) -> Result<(), Self::Error> {
+        self.faults.check()?;
+        self.dwell_since.get_or_insert(self.now_us);
+        Ok(())
+    }
+
+    fn coil_fire(&mut self) -> Result<(), Self::Error> {
+        self.faults.check()?;
+        let Some(dwell_start_us) = self.dwell_since.take() else {
+            self.fires_without_dwell = self.fires_without_dwell.wrapping_add(1);
+            return Ok(());
+        };
+        if self.len < L {
+            self.sparks[self.len] = SparkRecord { dwell_start_us, fire_us: self.now_us };
+            self.len += 1;
+        } else {
+            self.overflowed = true;
+        }
+        Ok(())
+    }
+}
+
+/// Sensor de rotación con niveles programados.
+/// N: niveles del guion
+///
+/// Cada `get_state` entrega el siguiente nivel del guion; al terminarse se
+/// queda en el último (o en el que se fije con `set_level`).
+#[derive(Debug, Clone)]
+pub struct MockRotationSensor<const N: usize> {
+    script: [bool; N],
+    next: usize,
+    level: bool,
+    flag_clears: u32,
+    faults: Faults,
+}
+
+impl<const N: usize> MockRotationSensor<N> {
+    pub fn new(script: [bool; N]) -> Self {
+        Self { script, next: 0, level: false, flag_clears: 0, faults: Faults::default() }
+    }
+
+    /// Termina el guion y fija el nivel
+    pub fn set_level(&mut self, level: bool) {
+        self.next = N;
+        self.level = level;
+    }
+
+    /// Veces que se limpió la bandera de interrupción
+    pub fn flag_clears(&self) -> u32 {
+        self.flag_clears
+    }
+
+    pub fn faults(&mut self) -> &mut Faults {
+        &mut self.faults
+    }
+}
+
+impl<const N: usize> RotationSensor for MockRotationSensor<N> {
+    type Error = MockError;
+
+    fn get_state(&mut self) -> Result<bool, Self::Error> {
+        self.faults.check()?;
+        if self.next < N {
+            self.level = self.script[self.next];
+            self.next += 1;
+        }
+        Ok(self.level)
+    }
+
+    fn clear_sensor_flag(&mut self) {
+        self.flag_clears = self.flag_clears.wrapping_add(1);
+    }
+}
+
+/// Captura de flancos simulada: entrega en orden los flancos cargados.
+/// N: flancos en espera
+///
+/// # Ejemplo
+///
+/// ```
+/// use ecu_traits::engine_io::{CapturedEdge, EdgeCapture};
+/// use ecu_traits::mock::{MockEdgeCapture, MockError};
+///
+/// let mut capture: MockEdgeCapture<8> = MockEdgeCapture::new();
+/// capture.push(CapturedEdge { timestamp_us: 100, rising: true });
+/// capture.faults().fail_next(1);
+///
+/// // Una falla simula una sobre-captura sin perder el flanco
+/// assert_eq!(capture.read_edge(), Err(MockError::Injected));
+/// assert_eq!(capture.read_edge().unwrap().unwrap().timestamp_us, 100);
+/// assert_eq!(capture.read_edge(), Ok(None));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockEdgeCapture<const N: usize> {
+    edges: [CapturedEdge; N],
+    head: usize,
+    len: usize,
+    faults: Faults,
+}
+
+impl<const N: usize> MockEdgeCapture<N> {
+    pub fn new() -> Self {
+        Self {
+            edges: [CapturedEdge { timestamp_us: 0, rising: false }; N],
+            head: 0,
+            len: 0,
+            faults: Faults::default(),
+        }
+    }
+
+    /// Agrega un flanco. false si la cola está llena.
+    pub fn push(&mut self, edge: CapturedEdge) -> bool {
+        if self.len == N {
+            return false;
+        }
+        self.edges[(self.head + self.len) % N] = edge;
+        self.len += 1;
+        true
+    }
+
+    /// Flancos que faltan por leer
+    pub fn pending(&self) -> usize {
+        self.len
+    }
+
+    pub fn faults(&mut self) -> &mut Faults {
+        &mut self.faults
+    }
+}
+
+impl<const N: usize> Default for MockEdgeCapture<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> EdgeCapture for MockEdgeCapture<N> {
+    type Error = MockError;
+
+    fn read_edge(&mut self) -> Result<Option<CapturedEdge>, Self::Error> {
+        self.faults.check()?;
+        if self.len == 0 {
+            return Ok(None);
+        }
+        let edge = self.edges[self.head];
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Ok(Some(edge))
+    }
+}
+
+/// Switch simulado (clutch, freno, etc.)
+#[derive(Debug, Clone, Default)]
+pub struct MockSwitch {
+    active: bool,
+    faults: Faults,
+}
+
+impl MockSwitch {
+    pub fn new(active: bool) -> Self {
+        Self { active, faults: Faults::default() }
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+    }
+
+    pub fn faults(&mut self) -> &mut Faults {
+        &mut self.faults
+    }
+}
+
+impl DigitalInput for MockSwitch {
+    type Error = MockError;
+
+    fn is_active(&mut self) -> Result<bool, Self::Error> {
+        self.faults.check()?;
+        Ok(self.active)
+    }
+}
+
+/// Fuente analógica simulada con voltajes programados.
+/// N: muestras del guion
+///
+/// Cada lectura entrega la siguiente muestra; al terminarse el guion se
+/// queda en la última (o en la que se fije con `set_volts`).
+#[derive(Debug, Clone)]
+pub struct MockAnalogInput<const N: usize> {
+    script: [f32; N],
+    next: usize,
+    volts: f32,
+    reads: u32,
+    faults: Faults,
+}
+
+impl<const N: usize> MockAnalogInput<N> {
+    pub fn new(script: [f32; N]) -> Self {
+        Self { script, next: 0, volts: 0.0, reads: 0, faults: Faults::default() }
+    }
+
+    /// Termina el guion y fija el voltaje
+    pub fn set_volts(&mut self, volts: f32) {
+        self.next = N;
+        self.volts = volts;
+    }
+
+    /// Lecturas exitosas
+    pub fn reads(&self) -> u32 {
+        self.reads
+    }
+
+    pub fn faults(&mut self) -> &mut Faults {
+        &mut self.faults
+    }
+}
+
+impl<const N: usize> AnalogInput for MockAnalogInput<N> {
+    type Error = MockError;
+
+    fn read_volts(&mut self) -> Result<f32, Self::Error> {
+        self.faults.check()?;
+        if self.next < N {
+            self.volts = self.script[self.next];
+            self.next += 1;
+        }
+        self.reads = self.reads.wrapping_add(1);
+        Ok(self.volts)
+    }
+}
@@ -0,0 +1,23 @@
+/// Acción que ejecuta el timer al llegar a la marca de tiempo programada
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareAction {
+    /// Activa la salida (abrir inyector / iniciar dwell)
+    Activate,
+    /// Desactiva la salida (cerrar inyector / disparar la chispa)
+    Deactivate,
+}
+
+/// Interface para el timer de salidas (output compare).
+/// Cada canal corresponde a una salida (inyector o bobina) y tiene a lo más
+/// una acción pendiente. La base de tiempo corre libre a 1 MHz.
+pub trait TimerBackend {
+    /// Base de tiempo libre en µs (da la vuelta en u32::MAX)
+    fn now_us(&mut self) -> u32;
+
+    /// Programa `action` en `channel` para `at_us`, reemplazando lo pendiente.
+    /// Si `at_us` ya pasó, la acción se ejecuta de inmediato.
+    fn arm(&mut self, channel: u8, at_us: u32, action: CompareAction);
+
+    /// Cancela la acción pendiente del canal
+    fn cancel(&mut self, channel: u8);
+}
@@ -0,0 +1,65 @@
+//! Visor del trigger logger en la PC.
+//!
+//! Lee una captura tal como llega por el protocolo de calibración (las
+//! respuestas de `CMD_READ` concatenadas) y dibuja los periodos de cigüeñal,
+//! o la convierte a CSV:
+//!
+//! ```text
+//! cargo run --target x86_64-unknown-linux-gnu -p engine_core --features std \
+//!     --example trigger_viewer -- captura.bin [--csv] [--ancho 60]
+//! ```
+
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+use engine_core::trigger_log::{decode_entry, render_periods, write_csv, LogEntry, ENTRY_BYTES};
+
+fn main() -> ExitCode {
+    let mut csv = false;
+    let mut width = 60;
+    let mut path = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--csv" => csv = true,
+            "--ancho" => match args.next().and_then(|w| w.parse().ok()) {
+                Some(w) => width = w,
+                None => {
+                    eprintln!("--ancho necesita un número");
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ if path.is_none() => path = Some(arg),
+            _ => {}
+        }
+    }
+    let Some(path) = path else {
+        eprintln!("uso: trigger_viewer <captura.bin> [--csv] [--ancho N]");
+        return ExitCode::FAILURE;
+    };
+
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            eprintln!("no se pudo leer {}: {}", path, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    if bytes.len() % ENTRY_BYTES != 0 {
+        eprintln!("aviso: {} bytes sobrantes al final de la captura", bytes.len() % ENTRY_BYTES);
+    }
+    let entries: Vec<LogEntry> = bytes
+        .chunks_exact(ENTRY_BYTES)
+        .map(|chunk| decode_entry(chunk.try_into().unwrap()))
+        .collect();
+
+    let mut out = String::new();
+    let result = if csv { write_csv(&entries, &mut out) } else { render_periods(&entries, &mut out, width) };
+    if result.is_err() {
+        eprintln!("no se pudo formatear la captura");
+        return ExitCode::FAILURE;
+    }
+    print!("{}", out);
+    ExitCode::SUCCESS
+}
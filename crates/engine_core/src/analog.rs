@@ -0,0 +1,192 @@
+use libm::logf;
+
+use ecu_traits::engine_io::{AnalogInput, AnalogSensor};
+use crate::tables::Table2D;
+
+/// 0 °C en Kelvin
+const KELVIN_OFFSET: f32 = 273.15;
+
+/// TPS típico: 0.5 V cerrado, 4.5 V a fondo (%)
+pub const DEFAULT_TPS: LinearConverter = LinearConverter::new(0.5, 0.0, 4.5, 100.0);
+
+/// MAP típico de 2.5 bar (kPa)
+pub const DEFAULT_MAP: LinearConverter = LinearConverter::new(0.4, 20.0, 4.65, 250.0);
+
+/// Termistor GM de IAT/CTS, ajustado con (-40 °C, 100700 Ω), (30 °C, 2238 Ω) y (100 °C, 177 Ω)
+pub const GM_THERMISTOR: SteinhartHart = SteinhartHart::new(1.4715612e-3, 2.3062375e-4, 1.0515564e-7);
+
+/// Conversión de voltaje del sensor a unidades de ingeniería
+pub trait Converter {
+    fn convert(&self, volts: f32) -> f32;
+}
+
+/// Sensor lineal definido por dos puntos (ej. MAP en kPa, TPS en %)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearConverter {
+    pub volts_low: f32,
+    pub value_low: f32,
+    pub volts_high: f32,
+    pub value_high: f32,
+}
+
+impl LinearConverter {
+    pub const fn new(volts_low: f32, value_low: f32, volts_high: f32, value_high: f32) -> Self {
+        Self { volts_low, value_low, volts_high, value_high }
+    }
+}
+
+impl Converter for LinearConverter {
+    /// Extrapola fuera de los dos puntos (el rango lo valida el diagnóstico del sensor)
+    fn convert(&self, volts: f32) -> f32 {
+        let span = self.volts_high - self.volts_low;
+        if span == 0.0 {
+            return self.value_low;
+        }
+        self.value_low + (volts - self.volts_low) * (self.value_high - self.value_low) / span
+    }
+}
+
+/// Curva arbitraria voltaje -> unidades (sensores no lineales, calibración por puntos)
+impl<const N: usize> Converter for Table2D<N> {
+    fn convert(&self, volts: f32) -> f32 {
+        self.interpolate(volts)
+    }
+}
+
+/// Modelo resistencia -> temperatura de un termistor
+pub trait ThermistorModel {
+    /// Temperatura (°C) a una resistencia (Ω)
+    fn temperature_c(&self, ohms: f32) -> f32;
+}
+
+/// Coeficientes de Steinhart-Hart: 1/T = A + B·ln(R) + C·ln(R)³ (T en Kelvin)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SteinhartHart {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+}
+
+impl SteinhartHart {
+    pub const fn new(a: f32, b: f32, c: f32) -> Self {
+        Self { a, b, c }
+    }
+
+    /// Calcula los coeficientes con tres puntos (°C, Ω) de la hoja de datos,
+    /// idealmente en frío, a media escala y en caliente
+    pub fn from_points(points: [(f32, f32); 3]) -> Self {
+        let l = points.map(|(_, ohms)| logf(ohms));
+        let y = points.map(|(celsius, _)| 1.0 / (celsius + KELVIN_OFFSET));
+
+        let g2 = (y[1] - y[0]) / (l[1] - l[0]);
+        let g3 = (y[2] - y[0]) / (l[2] - l[0]);
+        let c = (g3 - g2) / (l[2] - l[1]) / (l[0] + l[1] + l[2]);
+        let b = g2 - c * (l[0] * l[0] + l[0] * l[1] + l[1] * l[1]);
+        let a = y[0] - (b + l[0] * l[0] * c) * l[0];
+        Self { a, b, c }
+    }
+}
+
+impl ThermistorModel for SteinhartHart {
+    fn temperature_c(&self, ohms: f32) -> f32 {
+        let l = logf(ohms);
+        1.0 / (self.a + self.b * l + self.c * l * l * l) - KELVIN_OFFSET
+    }
+}
+
+/// Curva resistencia (Ω, ascendente) -> temperatura (°C) tomada de la hoja de datos
+impl<const N: usize> ThermistorModel for Table2D<N> {
+    fn temperature_c(&self, ohms: f32) -> f32 {
+        self.interpolate(ohms)
+    }
+}
+
+/// Termistor NTC en un divisor con resistencia de pull-up a `supply_volts`
+/// (IAT/CTS). El voltaje medido es el del termistor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thermistor<M: ThermistorModel> {
+    pub model: M,
+    pub pullup_ohms: f32,
+    pub supply_volts: f32,
+}
+
+impl<M: ThermistorModel> Thermistor<M> {
+    pub const fn new(model: M, pullup_ohms: f32, supply_volts: f32) -> Self {
+        Self { model, pullup_ohms, supply_volts }
+    }
+
+    /// Resistencia del termistor a partir del voltaje del divisor.
+    /// Con el circuito abierto (voltaje de alimentación o más) es infinita.
+    pub fn resistance_ohms(&self, volts: f32) -> f32 {
+        if volts >= self.supply_volts {
+            return f32::INFINITY;
+        }
+        self.pullup_ohms * volts.max(0.0) / (self.supply_volts - volts)
+    }
+}
+
+impl<M: ThermistorModel> Converter for Thermistor<M> {
+    fn convert(&self, volts: f32) -> f32 {
+        self.model.temperature_c(self.resistance_ohms(volts))
+    }
+}
+
+/// Canal analógico con su conversión: entrega unidades de ingeniería.
+///
+/// # Ejemplo
+///
+/// ```
+/// use ecu_traits::engine_io::AnalogSensor;
+/// use ecu_traits::mock::MockAnalogInput;
+/// use engine_core::analog::{CalibratedSensor, LinearConverter};
+///
+/// // TPS: 0.5 V = 0%, 4.5 V = 100%
+/// let input = MockAnalogInput::new([2.5]);
+/// let mut tps = CalibratedSensor::new(input, LinearConverter::new(0.5, 0.0, 4.5, 100.0));
+///
+/// assert_eq!(tps.read().unwrap(), 50.0);
+/// assert_eq!(tps.last_volts(), 2.5);
+/// ```
+pub struct CalibratedSensor<I: AnalogInput, C: Converter> {
+    input: I,
+    converter: C,
+    last_volts: f32,
+}
+
+impl<I: AnalogInput, C: Converter> CalibratedSensor<I, C> {
+    pub fn new(input: I, converter: C) -> Self {
+        Self { input, converter, last_volts: 0.0 }
+    }
+
+    /// Cambia la calibración (ej. al cargar la configuración)
+    pub fn set_converter(&mut self, converter: C) {
+        self.converter = converter;
+    }
+
+    pub fn converter(&self) -> &C {
+        &self.converter
+    }
+
+    /// Voltaje de la última lectura exitosa
+    pub fn last_volts(&self) -> f32 {
+        self.last_volts
+    }
+
+    pub fn input(&self) -> &I {
+        &self.input
+    }
+
+    pub fn input_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+}
+
+impl<I: AnalogInput, C: Converter> AnalogSensor for CalibratedSensor<I, C> {
+    type Error = I::Error;
+
+    fn read(&mut self) -> Result<f32, Self::Error> {
+        let volts = self.input.read_volts()?;
+        self.last_volts = volts;
+        Ok(self.converter.convert(volts))
+    }
+}
@@ -0,0 +1,206 @@
+use libm::sqrtf;
+
+/// Configuración del estimador de velocidad angular
+#[derive(Debug, Clone, Copy)]
+pub struct AnglePredictorConfig {
+    /// Grados del dominio angular: 360 con ángulo de cigüeñal, 720 con fase de leva
+    pub cycle_deg: f32,
+    /// Compensación de aceleración de primer orden al predecir
+    pub acceleration_compensation: bool,
+    /// Constante del filtro exponencial de las RPM suavizadas (0..1, 1 = sin filtro)
+    pub rpm_alpha: f32,
+}
+
+impl Default for AnglePredictorConfig {
+    fn default() -> Self {
+        Self { cycle_deg: 360.0, acceleration_compensation: true, rpm_alpha: 0.1 }
+    }
+}
+
+/// Velocidad angular instantánea y predicción de ángulo entre dientes.
+///
+/// Se alimenta con cada diente sincronizado (marca de tiempo y ángulo). La
+/// velocidad se mide con el último periodo; con compensación de aceleración
+/// se estima además la aceleración con los dos últimos periodos y la
+/// predicción resuelve `Δθ = ω·t + ½·α·t²`.
+///
+/// # Ejemplo
+///
+/// ```
+/// use engine_core::angle_predictor::{AnglePredictor, AnglePredictorConfig};
+///
+/// let mut predictor = AnglePredictor::new(AnglePredictorConfig::default());
+///
+/// // Dientes cada 10° a 1000 µs = 1666 RPM
+/// for i in 0..4u32 {
+///     predictor.on_tooth(i * 1000, (i * 10) as f32);
+/// }
+///
+/// // 25° después del último diente (30°) = 2500 µs
+/// assert_eq!(predictor.predict_time_of_angle(55.0), Some(5500));
+/// ```
+#[derive(Debug, Clone)]
+pub struct AnglePredictor {
+    config: AnglePredictorConfig,
+    last_timestamp_us: u32,
+    last_angle_deg: f32,
+    last_period_us: u32,
+    // Velocidad promedio del último periodo (grados/µs)
+    mean_deg_per_us: f32,
+    // Velocidad estimada en el instante del último diente (grados/µs)
+    deg_per_us: f32,
+    // Aceleración (grados/µs²)
+    accel_deg_per_us2: f32,
+    smoothed_rpm: f32,
+    // Dientes recibidos desde el último reset (saturado)
+    teeth: u8,
+}
+
+impl AnglePredictor {
+    pub fn new(config: AnglePredictorConfig) -> Self {
+        Self {
+            config,
+            last_timestamp_us: 0,
+            last_angle_deg: 0.0,
+            last_period_us: 0,
+            mean_deg_per_us: 0.0,
+            deg_per_us: 0.0,
+            accel_deg_per_us2: 0.0,
+            smoothed_rpm: 0.0,
+            teeth: 0,
+        }
+    }
+
+    /// Grados del dominio angular actual
+    pub fn cycle_deg(&self) -> f32 {
+        self.config.cycle_deg
+    }
+
+    /// Cambia el dominio angular (ej. 720° al obtener la fase, 360° al perderla)
+    pub fn set_cycle_deg(&mut self, cycle_deg: f32) {
+        self.config.cycle_deg = cycle_deg;
+    }
+
+    /// Se llama con cada diente sincronizado.
+    /// angle_deg: ángulo del diente en el dominio de `cycle_deg`
+    pub fn on_tooth(&mut self, timestamp_us: u32, angle_deg: f32) {
+        let period = timestamp_us.wrapping_sub(self.last_timestamp_us);
+        let delta = self.wrap(angle_deg - self.last_angle_deg);
+        let first = self.teeth == 0;
+
+        self.last_timestamp_us = timestamp_us;
+        self.last_angle_deg = angle_deg;
+        self.teeth = self.teeth.saturating_add(1);
+
+        if first || period == 0 || delta <= 0.0 {
+            return;
+        }
+
+        let mean = delta / period as f32;
+        if self.teeth > 2 && self.last_period_us > 0 {
+            // Las velocidades promedio corresponden a la mitad de cada periodo
+            let dt = 0.5 * (period + self.last_period_us) as f32;
+            self.accel_deg_per_us2 = (mean - self.mean_deg_per_us) / dt;
+        }
+        self.mean_deg_per_us = mean;
+        self.last_period_us = period;
+
+        self.deg_per_us = if self.config.acceleration_compensation {
+            // Extrapola de la mitad del periodo al instante del diente
+            (mean + self.accel_deg_per_us2 * 0.5 * period as f32).max(mean * 0.5)
+        } else {
+            mean
+        };
+
+        let rpm = self.rpm();
+        self.smoothed_rpm = if self.smoothed_rpm <= 0.0 {
+            rpm
+        } else {
+            self.smoothed_rpm + self.config.rpm_alpha * (rpm - self.smoothed_rpm)
+        };
+    }
+
+    /// true cuando ya hay una velocidad medida
+    pub fn is_valid(&self) -> bool {
+        self.deg_per_us > 0.0
+    }
+
+    /// Velocidad angular instantánea (grados/µs)
+    pub fn deg_per_us(&self) -> f32 {
+        self.deg_per_us
+    }
+
+    /// Aceleración angular estimada (grados/µs²), 0 sin compensación
+    pub fn acceleration_deg_per_us2(&self) -> f32 {
+        if self.config.acceleration_compensation { self.accel_deg_per_us2 } else { 0.0 }
+    }
+
+    /// RPM instantáneas
+    pub fn rpm(&self) -> f32 {
+        // grados/µs -> vueltas/min
+        self.deg_per_us * 60_000_000.0 / 360.0
+    }
+
+    /// RPM filtradas para consultar tablas
+    pub fn smoothed_rpm(&self) -> f32 {
+        self.smoothed_rpm
+    }
+
+    /// Marca de tiempo y ángulo del último diente
+    pub fn last_tooth(&self) -> (u32, f32) {
+        (self.last_timestamp_us, self.last_angle_deg)
+    }
+
+    /// Tiempo (µs) que tarda el motor en girar `delta_deg` desde el último diente
+    pub fn time_for_delta_deg(&self, delta_deg: f32) -> Option<f32> {
+        if !self.is_valid() {
+            return None;
+        }
+        let w = self.deg_per_us;
+        let a = self.acceleration_deg_per_us2();
+
+        if a.abs() > f32::EPSILON {
+            // ½·a·t² + w·t - Δθ = 0
+            let disc = w * w + 2.0 * a * delta_deg;
+            if disc >= 0.0 {
+                let t = (-w + sqrtf(disc)) / a;
+                if t >= 0.0 {
+                    return Some(t);
+                }
+            }
+            // Desaceleración que no llega al ángulo: se usa velocidad constante
+        }
+        Some(delta_deg / w)
+    }
+
+    /// Marca de tiempo (µs) en que el cigüeñal pasará por `angle_deg`.
+    /// El ángulo se toma como el siguiente paso después del último diente
+    /// (a lo más un ciclo adelante). None sin velocidad medida.
+    pub fn predict_time_of_angle(&self, angle_deg: f32) -> Option<u32> {
+        let delta = self.wrap(angle_deg - self.last_angle_deg);
+        let t = self.time_for_delta_deg(delta)?;
+        Some(self.last_timestamp_us.wrapping_add((t + 0.5) as u32))
+    }
+
+    /// Ángulo estimado en `timestamp_us` (posterior al último diente)
+    pub fn angle_at(&self, timestamp_us: u32) -> f32 {
+        let t = timestamp_us.wrapping_sub(self.last_timestamp_us) as f32;
+        let delta = self.deg_per_us * t + 0.5 * self.acceleration_deg_per_us2() * t * t;
+        self.wrap(self.last_angle_deg + delta.max(0.0))
+    }
+
+    /// Olvida la historia (ej. pérdida de sincronía)
+    pub fn reset(&mut self) {
+        *self = Self::new(self.config);
+    }
+
+    /// Normaliza a 0..cycle_deg
+    fn wrap(&self, angle: f32) -> f32 {
+        let cycle = self.config.cycle_deg;
+        let mut a = angle % cycle;
+        if a < 0.0 {
+            a += cycle;
+        }
+        a
+    }
+}
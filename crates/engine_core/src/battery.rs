@@ -0,0 +1,249 @@
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use ecu_traits::engine_io::AnalogInput;
+use crate::diagnostics::{DiagnosticLog, FaultCode, FaultKind, SensorId};
+use crate::sensor_fault::{FaultTransition, RangeMonitor};
+use crate::tables::Table2D;
+
+/// Voltaje nominal antes de la primera medición
+pub const NOMINAL_SUPPLY_VOLTS: f32 = 13.5;
+
+/// Voltaje de alimentación compartido entre tareas: lo publica el monitor
+/// de batería y lo leen los modelos de inyector y bobina (en otras
+/// prioridades, por eso es atómico).
+///
+/// # Ejemplo
+///
+/// ```
+/// use engine_core::battery::SupplyVoltage;
+///
+/// static SUPPLY: SupplyVoltage = SupplyVoltage::new(13.5);
+///
+/// SUPPLY.publish(12.1, false);
+/// assert_eq!(SUPPLY.volts(), 12.1);
+/// ```
+pub struct SupplyVoltage {
+    bits: AtomicU32,
+    limited: AtomicBool,
+}
+
+impl SupplyVoltage {
+    pub const fn new(volts: f32) -> Self {
+        Self { bits: AtomicU32::new(volts.to_bits()), limited: AtomicBool::new(false) }
+    }
+
+    /// Publica una medición nueva (y si las salidas deben limitarse)
+    pub fn publish(&self, volts: f32, outputs_limited: bool) {
+        self.bits.store(volts.to_bits(), Ordering::Relaxed);
+        self.limited.store(outputs_limited, Ordering::Relaxed);
+    }
+
+    pub fn volts(&self) -> f32 {
+        f32::from_bits(self.bits.load(Ordering::Relaxed))
+    }
+
+    /// true con sobrevoltaje: inyectores y bobinas no se deben activar
+    pub fn outputs_limited(&self) -> bool {
+        self.limited.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for SupplyVoltage {
+    fn default() -> Self {
+        Self::new(NOMINAL_SUPPLY_VOLTS)
+    }
+}
+
+/// Configuración del monitor de batería
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatteryConfig {
+    /// Relación del divisor de la placa (voltaje de batería / voltaje medido)
+    pub divider_ratio: f32,
+    /// Debajo de este voltaje se reporta batería baja
+    pub low_volts: f32,
+    /// Arriba de este voltaje se reporta sobrevoltaje (falla de regulador)
+    pub high_volts: f32,
+    /// Arriba de este voltaje se limitan las salidas de inmediato (sin debounce)
+    pub max_safe_volts: f32,
+    /// Las salidas se liberan hasta bajar de `max_safe_volts - limit_hysteresis_volts`
+    pub limit_hysteresis_volts: f32,
+    /// Tiempo que el voltaje debe quedarse debajo de la histéresis para
+    /// liberar las salidas (evita que el ruido del ADC las prenda y apague)
+    pub limit_hold_us: u32,
+    /// Tiempo para confirmar o quitar las fallas (los arranques bajan el voltaje un momento)
+    pub debounce_us: u32,
+}
+
+/// Estado de la alimentación
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatteryStatus {
+    Normal,
+    Low,
+    High,
+}
+
+/// Monitor de voltaje de batería.
+/// I: canal analógico ya filtrado (voltaje en el pin del divisor)
+pub struct BatteryMonitor<I: AnalogInput> {
+    input: I,
+    config: BatteryConfig,
+    monitor: RangeMonitor,
+    volts: f32,
+    limited: bool,
+    /// Desde cuándo el voltaje está debajo de la histéresis (con las salidas limitadas)
+    safe_since_us: Option<u32>,
+}
+
+impl<I: AnalogInput> BatteryMonitor<I> {
+    pub fn new(input: I, config: BatteryConfig) -> Self {
+        Self {
+            input,
+            config,
+            monitor: RangeMonitor::new(config.debounce_us),
+            volts: NOMINAL_SUPPLY_VOLTS,
+            limited: false,
+            safe_since_us: None,
+        }
+    }
+
+    /// Lee el voltaje, actualiza el diagnóstico y lo publica en `supply`.
+    /// Si la lectura falla se conserva el último voltaje publicado.
+    pub fn update<const N: usize>(
+        &mut self,
+        now_us: u32,
+        diag: &mut DiagnosticLog<N>,
+        supply: &SupplyVoltage,
+    ) -> BatteryStatus {
+        let c = self.config;
+        let observed = match self.input.read_volts() {
+            Ok(pin_volts) => {
+                self.volts = pin_volts * c.divider_ratio;
+                self.update_limit(now_us);
+                if self.volts < c.low_volts {
+                    Some(FaultKind::RangeLow)
+                } else if self.volts > c.high_volts {
+                    Some(FaultKind::RangeHigh)
+                } else {
+                    None
+                }
+            }
+            Err(_) => Some(FaultKind::ReadError),
+        };
+
+        match self.monitor.observe(observed, now_us) {
+            Some(FaultTransition::Set(kind)) => {
+                for previous in [FaultKind::RangeLow, FaultKind::RangeHigh, FaultKind::ReadError] {
+                    if previous != kind {
+                        diag.report(FaultCode::new(SensorId::Battery, previous), false, now_us);
+                    }
+                }
+                diag.report(FaultCode::new(SensorId::Battery, kind), true, now_us);
+            }
+            Some(FaultTransition::Cleared(kind)) => {
+                diag.report(FaultCode::new(SensorId::Battery, kind), false, now_us)
+            }
+            None => {}
+        }
+
+        supply.publish(self.volts, self.limited);
+        self.status()
+    }
+
+    /// Limita de inmediato arriba de `max_safe_volts`; libera solo después
+    /// de `limit_hold_us` continuos debajo de la histéresis
+    fn update_limit(&mut self, now_us: u32) {
+        let c = &self.config;
+        if self.volts > c.max_safe_volts {
+            self.limited = true;
+            self.safe_since_us = None;
+        } else if self.limited {
+            if self.volts < c.max_safe_volts - c.limit_hysteresis_volts {
+                let since = *self.safe_since_us.get_or_insert(now_us);
+                if now_us.wrapping_sub(since) >= c.limit_hold_us {
+                    self.limited = false;
+                    self.safe_since_us = None;
+                }
+            } else {
+                self.safe_since_us = None;
+            }
+        }
+    }
+
+    /// Estado confirmado (con debounce)
+    pub fn status(&self) -> BatteryStatus {
+        match self.monitor.fault() {
+            Some(FaultKind::RangeLow) => BatteryStatus::Low,
+            Some(FaultKind::RangeHigh) => BatteryStatus::High,
+            _ => BatteryStatus::Normal,
+        }
+    }
+
+    /// Último voltaje de batería medido
+    pub fn volts(&self) -> f32 {
+        self.volts
+    }
+
+    /// true mientras las salidas están limitadas por sobrevoltaje
+    pub fn outputs_limited(&self) -> bool {
+        self.limited
+    }
+
+    pub fn input_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+}
+
+/// Tiempo muerto del inyector contra voltaje de batería.
+/// El inyector tarda más en abrir con menos voltaje; el tiempo muerto se
+/// suma al pulso efectivo que calcula `SpeedDensity`.
+#[derive(Debug, Clone)]
+pub struct InjectorDeadTime<const N: usize> {
+    /// Voltaje (V, ascendente) -> tiempo muerto (µs)
+    pub curve: Table2D<N>,
+}
+
+impl<const N: usize> InjectorDeadTime<N> {
+    pub fn new(volts: [f32; N], dead_time_us: [f32; N]) -> Self {
+        Self { curve: Table2D::new(volts, dead_time_us) }
+    }
+
+    pub fn dead_time_us(&self, supply: &SupplyVoltage) -> u32 {
+        self.curve.interpolate(supply.volts()).max(0.0) as u32
+    }
+
+    /// Pulso a programar para un pulso efectivo. Sin combustible pedido o con
+    /// las salidas limitadas por sobrevoltaje el pulso es 0.
+    pub fn pulse_width_us(&self, effective_us: u32, supply: &SupplyVoltage) -> u32 {
+        if effective_us == 0 || supply.outputs_limited() {
+            return 0;
+        }
+        effective_us.saturating_add(self.dead_time_us(supply))
+    }
+}
+
+/// Dwell de la bobina contra voltaje de batería (con menos voltaje la
+/// bobina necesita más tiempo para cargar).
+#[derive(Debug, Clone)]
+pub struct DwellCurve<const N: usize> {
+    /// Voltaje (V, ascendente) -> dwell (µs)
+    pub curve: Table2D<N>,
+}
+
+impl<const N: usize> DwellCurve<N> {
+    pub fn new(volts: [f32; N], dwell_us: [f32; N]) -> Self {
+        Self { curve: Table2D::new(volts, dwell_us) }
+    }
+
+    /// Dwell a programar; 0 (sin chispa) con las salidas limitadas
+    pub fn dwell_us(&self, supply: &SupplyVoltage) -> u32 {
+        if supply.outputs_limited() {
+            return 0;
+        }
+        self.curve.interpolate(supply.volts()).max(0.0) as u32
+    }
+
+    /// Dwell en grados a la velocidad actual (para `AngleEvent::spark`)
+    pub fn dwell_deg(&self, supply: &SupplyVoltage, deg_per_us: f32) -> f32 {
+        self.dwell_us(supply) as f32 * deg_per_us
+    }
+}
@@ -0,0 +1,261 @@
+use crate::crank_decoder::{CrankDecoder, SyncState, ToothEvent};
+use crate::trigger_patterns::TriggerPattern;
+
+/// Configuración de la señal de leva (CMP).
+/// K: dientes de leva por ciclo de 720°
+#[derive(Debug, Clone)]
+pub struct CamConfig<const K: usize> {
+    /// Ángulo de ciclo (0..720°) en que aparece cada diente de leva.
+    /// Una leva de un diente es `[630.0]`; una 4+1 repite 4 dientes cada
+    /// 180° y agrega uno de sincronía solo en una de las dos vueltas.
+    pub teeth_cycle_deg: [f32; K],
+    /// Tolerancia angular para aceptar un flanco como un diente esperado
+    pub tolerance_deg: f32,
+    /// Ciclos inválidos seguidos antes de caer a operación de 360°
+    pub max_invalid_cycles: u8,
+}
+
+/// Estado de la fase del motor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CamState {
+    /// Todavía no se identifica la fase: solo se conoce el ángulo de 0..360°
+    NoPhase,
+    /// Fase conocida: ángulo de ciclo de 0..720°
+    Synced,
+    /// La señal de leva desapareció o es inválida: se opera a 360°
+    /// (chispa perdida / semi-secuencial) sin perder la sincronía de cigüeñal
+    Fallback,
+}
+
+/// Capa de sincronía de leva sobre el decodificador de cigüeñal
+pub struct CamSync<const K: usize> {
+    config: CamConfig<K>,
+    state: CamState,
+    // true = segunda vuelta del ciclo (360..720°)
+    second_half: bool,
+    last_crank_angle_deg: f32,
+    // Flancos válidos y errores dentro del ciclo en curso
+    edges_in_cycle: u16,
+    cycle_error: bool,
+    // El ciclo en el que se obtuvo la fase está incompleto: no se valida
+    partial_cycle: bool,
+    invalid_cycles: u8,
+    cam_errors: u32,
+}
+
+impl<const K: usize> CamSync<K> {
+    pub fn new(config: CamConfig<K>) -> Self {
+        Self {
+            config,
+            state: CamState::NoPhase,
+            second_half: false,
+            last_crank_angle_deg: 0.0,
+            edges_in_cycle: 0,
+            cycle_error: false,
+            partial_cycle: false,
+            invalid_cycles: 0,
+            cam_errors: 0,
+        }
+    }
+
+    /// Se llama con cada diente del decodificador de cigüeñal sincronizado.
+    /// Detecta el paso por 360° para alternar la vuelta y validar el ciclo.
+    pub fn on_crank_tooth(&mut self, event: &ToothEvent) {
+        if event.angle_deg < self.last_crank_angle_deg {
+            self.second_half = !self.second_half;
+            if !self.second_half {
+                self.validate_cycle();
+            }
+        }
+        self.last_crank_angle_deg = event.angle_deg;
+    }
+
+    /// Se llama con cada flanco de leva.
+    /// crank_angle_deg: ángulo del cigüeñal (0..360°) en el momento del flanco
+    pub fn on_cam_edge(&mut self, crank_angle_deg: f32) {
+        // Vuelta que implica cada diente compatible con este ángulo
+        let mut implied_half: Option<bool> = None;
+        let mut ambiguous = false;
+        let mut matched = false;
+
+        for &tooth in self.config.teeth_cycle_deg.iter() {
+            let tooth_crank = tooth % 360.0;
+            let diff = wrap_180(crank_angle_deg - tooth_crank);
+            if diff.abs() > self.config.tolerance_deg {
+                continue;
+            }
+            matched = true;
+
+            // Posición real en el ciclo (considera flancos cerca de 0/360°)
+            let position = (tooth + diff + 720.0) % 720.0;
+            let half = position >= 360.0;
+            match implied_half {
+                None => implied_half = Some(half),
+                Some(h) if h != half => ambiguous = true,
+                _ => {}
+            }
+        }
+
+        if !matched {
+            // Flanco donde no hay diente: ruido o leva desfasada
+            self.cycle_error = true;
+            self.cam_errors = self.cam_errors.wrapping_add(1);
+            return;
+        }
+        self.edges_in_cycle = self.edges_in_cycle.saturating_add(1);
+
+        if ambiguous {
+            // Diente que se repite en ambas vueltas: no aporta fase
+            return;
+        }
+
+        if let Some(half) = implied_half {
+            if self.state == CamState::Synced && half != self.second_half {
+                // La leva contradice la fase actual: se corrige con la leva
+                self.cycle_error = true;
+                self.cam_errors = self.cam_errors.wrapping_add(1);
+            }
+            if self.state != CamState::Synced {
+                self.state = CamState::Synced;
+                self.partial_cycle = true;
+                self.invalid_cycles = 0;
+            }
+            self.second_half = half;
+        }
+    }
+
+    /// Ángulo dentro del ciclo. Con fase conocida es 0..720°; sin fase
+    /// se regresa el ángulo del cigüeñal (0..360°).
+    pub fn cycle_angle_deg(&self, crank_angle_deg: f32) -> f32 {
+        if self.phase_known() && self.second_half {
+            crank_angle_deg + 360.0
+        } else {
+            crank_angle_deg
+        }
+    }
+
+    /// Longitud del ciclo utilizable: 720° con fase, 360° sin ella
+    pub fn cycle_deg(&self) -> f32 {
+        if self.phase_known() { 720.0 } else { 360.0 }
+    }
+
+    pub fn phase_known(&self) -> bool {
+        self.state == CamState::Synced
+    }
+
+    pub fn state(&self) -> CamState {
+        self.state
+    }
+
+    /// Flancos de leva fuera de lugar o ciclos con dientes faltantes
+    pub fn cam_errors(&self) -> u32 {
+        self.cam_errors
+    }
+
+    /// Olvida la fase (ej. se perdió la sincronía de cigüeñal)
+    pub fn reset(&mut self) {
+        self.state = CamState::NoPhase;
+        self.second_half = false;
+        self.edges_in_cycle = 0;
+        self.cycle_error = false;
+        self.partial_cycle = false;
+        self.invalid_cycles = 0;
+    }
+
+    /// Revisión al terminar cada ciclo de 720°: deben haber llegado K dientes
+    fn validate_cycle(&mut self) {
+        // Solo se valida con fase: antes de ella la frontera del ciclo es arbitraria
+        if self.partial_cycle || self.state != CamState::Synced {
+            self.partial_cycle = false;
+            self.edges_in_cycle = 0;
+            self.cycle_error = false;
+            return;
+        }
+
+        let valid = !self.cycle_error && self.edges_in_cycle as usize == K;
+
+        if valid {
+            self.invalid_cycles = 0;
+        } else {
+            if !self.cycle_error {
+                // Dientes faltantes (o sobrantes) sin error previo en el ciclo
+                self.cam_errors = self.cam_errors.wrapping_add(1);
+            }
+            self.invalid_cycles = self.invalid_cycles.saturating_add(1);
+            if self.invalid_cycles >= self.config.max_invalid_cycles {
+                self.state = CamState::Fallback;
+            }
+        }
+
+        self.edges_in_cycle = 0;
+        self.cycle_error = false;
+    }
+}
+
+/// RPM medidas con la señal de leva, respaldo cuando el cigüeñal no es plausible.
+/// K: dientes de leva por ciclo de 720°; se mide un ciclo completo para no
+/// depender de la separación entre dientes.
+pub struct CamRpm<const K: usize> {
+    timestamps: [u32; K],
+    index: usize,
+    edges: usize,
+    // Último flanco y el flanco de hace K dientes (un ciclo antes)
+    newest_us: u32,
+    cycle_start_us: u32,
+}
+
+impl<const K: usize> CamRpm<K> {
+    pub fn new() -> Self {
+        Self { timestamps: [0; K], index: 0, edges: 0, newest_us: 0, cycle_start_us: 0 }
+    }
+
+    /// Se llama con la marca de tiempo (µs) de cada flanco de leva
+    pub fn on_edge(&mut self, timestamp_us: u32) {
+        self.cycle_start_us = self.timestamps[self.index];
+        self.newest_us = timestamp_us;
+        self.timestamps[self.index] = timestamp_us;
+        self.index = (self.index + 1) % K;
+        self.edges = self.edges.saturating_add(1);
+    }
+
+    /// RPM del cigüeñal (un ciclo de leva = 2 vueltas). 0 hasta completar un ciclo.
+    pub fn rpm(&self) -> f32 {
+        if self.edges <= K {
+            return 0.0;
+        }
+        let cycle_us = self.newest_us.wrapping_sub(self.cycle_start_us);
+        if cycle_us == 0 { 0.0 } else { 120_000_000.0 / cycle_us as f32 }
+    }
+
+    pub fn reset(&mut self) {
+        self.edges = 0;
+        self.index = 0;
+    }
+}
+
+impl<const K: usize> Default for CamRpm<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// RPM plausibles: las del cigüeñal si está sincronizado, si no las de la leva.
+/// None si ninguna de las dos señales es utilizable.
+pub fn plausible_rpm<P: TriggerPattern, const K: usize>(
+    crank: &CrankDecoder<P>,
+    cam: &CamRpm<K>,
+) -> Option<f32> {
+    if crank.state() == SyncState::Synced {
+        return Some(crank.rpm());
+    }
+    let rpm = cam.rpm();
+    if rpm > 0.0 { Some(rpm) } else { None }
+}
+
+/// Normaliza una diferencia angular a -180..180°
+fn wrap_180(angle: f32) -> f32 {
+    let mut a = angle % 360.0;
+    if a > 180.0 { a -= 360.0; }
+    if a < -180.0 { a += 360.0; }
+    a
+}
@@ -0,0 +1,281 @@
+use ecu_traits::engine_io::CapturedEdge;
+use crate::trigger_patterns::TriggerPattern;
+
+// La rueda N-M vive con el resto de los patrones; se re-exporta aquí
+pub use crate::trigger_patterns::MissingToothWheel;
+
+/// Periodos que guarda el decodificador para las reglas de sincronía
+pub const PERIOD_HISTORY: usize = 12;
+
+/// Estado de sincronía del decodificador
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncState {
+    /// Sin flancos (motor detenido o recién inicializado)
+    NoSync,
+    /// Recibiendo dientes, buscando la referencia del patrón (ej. el hueco)
+    Syncing,
+    /// Posición angular conocida
+    Synced,
+    /// Se perdió la sincronía (hueco inesperado o hueco faltante), buscando de nuevo
+    Lost,
+}
+
+/// Información de un diente con el decodificador sincronizado
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToothEvent {
+    /// Marca de tiempo del flanco (µs)
+    pub timestamp_us: u32,
+    /// Índice del diente dentro del patrón (0 = diente de referencia,
+    /// en una rueda N-M el primero después del hueco)
+    pub tooth: u16,
+    /// Ángulo en este diente (0 = PMS del cilindro 1). Va de 0 a 360° en
+    /// patrones de cigüeñal y de 0 a 720° en patrones de leva
+    pub angle_deg: f32,
+    /// Tiempo desde el diente anterior (µs)
+    pub period_us: u32,
+}
+
+/// Contadores de diagnóstico del decodificador
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DecoderDiagnostics {
+    /// Flancos descartados por llegar antes de lo plausible (glitches)
+    pub rejected_edges: u32,
+    /// Flancos recibidos sin sincronía (no generan evento)
+    pub ignored_edges: u32,
+    /// Relaciones entre periodos que no coinciden con el patrón
+    pub ratio_errors: u32,
+    /// Veces que se perdió la sincronía
+    pub sync_losses: u32,
+}
+
+/// Decodificador de patrones de disparo independiente del hardware.
+/// Se alimenta con las marcas de tiempo de cada flanco de diente; la
+/// geometría y la regla de sincronía vienen del [`TriggerPattern`].
+///
+/// # Ejemplo
+///
+/// ```
+/// use engine_core::crank_decoder::{CrankDecoder, MissingToothWheel, SyncState};
+///
+/// let mut decoder = CrankDecoder::new(MissingToothWheel::new(36, 1), 0.0);
+///
+/// // 35 dientes a 1000 µs y luego el hueco de 2000 µs
+/// let mut t = 0u32;
+/// for _ in 0..35 {
+///     decoder.on_tooth(t);
+///     t += 1000;
+/// }
+/// t += 1000; // diente faltante
+/// let event = decoder.on_tooth(t).unwrap();
+///
+/// assert_eq!(decoder.state(), SyncState::Synced);
+/// assert_eq!(event.tooth, 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CrankDecoder<P: TriggerPattern = MissingToothWheel> {
+    pattern: P,
+    /// Ángulo del diente 0 del patrón respecto al PMS del cilindro 1
+    first_tooth_angle_deg: f32,
+    state: SyncState,
+    last_timestamp_us: u32,
+    // Periodos recientes en orden cronológico (el último al final)
+    periods: [u32; PERIOD_HISTORY],
+    valid_periods: usize,
+    // Flancos recibidos desde el último reinicio
+    edges: u32,
+    tooth: u16,
+    revolutions: u32,
+    // Fracción del periodo esperado debajo de la cual se rechaza un flanco (0 = sin filtro)
+    min_period_fraction: f32,
+    // Relación entre el hueco más corto y el más largo del patrón
+    min_gap_ratio: f32,
+    diagnostics: DecoderDiagnostics,
+}
+
+impl<P: TriggerPattern> CrankDecoder<P> {
+    /// pattern: Geometría del patrón
+    /// first_tooth_angle_deg: Ángulo (DPMS) del diente 0 del patrón
+    pub fn new(pattern: P, first_tooth_angle_deg: f32) -> Self {
+        let (mut min_gap, mut max_gap) = (f32::MAX, 0.0f32);
+        for tooth in 0..pattern.tooth_count() {
+            let gap = pattern.gap_deg(tooth);
+            min_gap = min_gap.min(gap);
+            max_gap = max_gap.max(gap);
+        }
+
+        Self {
+            pattern,
+            first_tooth_angle_deg,
+            state: SyncState::NoSync,
+            last_timestamp_us: 0,
+            periods: [0; PERIOD_HISTORY],
+            valid_periods: 0,
+            edges: 0,
+            tooth: 0,
+            revolutions: 0,
+            min_period_fraction: 0.0,
+            min_gap_ratio: if max_gap > 0.0 { min_gap / max_gap } else { 1.0 },
+            diagnostics: DecoderDiagnostics::default(),
+        }
+    }
+
+    /// Habilita el filtro de ruido: se rechazan los flancos que llegan antes de
+    /// `min_period_fraction` veces el periodo esperado (ej. 0.25).
+    /// Sin sincronía se espera el peor caso del patrón (el hueco más corto).
+    pub fn with_noise_filter(mut self, min_period_fraction: f32) -> Self {
+        self.min_period_fraction = min_period_fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Procesa un flanco capturado por hardware.
+    /// Los patrones de un solo flanco solo usan los de subida; los de bajada se ignoran.
+    pub fn on_edge(&mut self, edge: CapturedEdge) -> Option<ToothEvent> {
+        if !edge.rising && !self.pattern.uses_both_edges() {
+            return None;
+        }
+        self.on_tooth(edge.timestamp_us)
+    }
+
+    /// Procesa un flanco de diente.
+    /// timestamp_us: tiempo del flanco en µs (contador libre, puede dar la vuelta)
+    /// Regresa el evento del diente solo si el decodificador está sincronizado.
+    pub fn on_tooth(&mut self, timestamp_us: u32) -> Option<ToothEvent> {
+        let period = timestamp_us.wrapping_sub(self.last_timestamp_us);
+
+        if self.is_glitch(period) {
+            // El flanco se descarta sin tocar la marca de tiempo anterior
+            self.diagnostics.rejected_edges = self.diagnostics.rejected_edges.wrapping_add(1);
+            return None;
+        }
+
+        self.last_timestamp_us = timestamp_us;
+        self.edges = self.edges.saturating_add(1);
+
+        if self.state == SyncState::NoSync {
+            self.state = SyncState::Syncing;
+            self.ignore_edge();
+            return None;
+        }
+        self.push_period(period);
+
+        let teeth = self.pattern.tooth_count() as u16;
+        let periods = &self.periods[PERIOD_HISTORY - self.valid_periods..];
+
+        match self.state {
+            SyncState::Syncing | SyncState::Lost => {
+                let Some(tooth) = self.pattern.identify(periods) else {
+                    self.ignore_edge();
+                    return None;
+                };
+                self.state = SyncState::Synced;
+                self.tooth = tooth as u16;
+            }
+            SyncState::Synced => {
+                let expected = (self.tooth + 1) % teeth;
+
+                if !self.pattern.validate(expected as usize, periods) {
+                    // Hueco fuera de lugar, hueco que nunca llegó o relación inválida
+                    self.diagnostics.ratio_errors = self.diagnostics.ratio_errors.wrapping_add(1);
+                    self.lose_sync();
+                    return None;
+                }
+                if expected == 0 {
+                    self.revolutions = self.revolutions.wrapping_add(1);
+                }
+                self.tooth = expected;
+            }
+            SyncState::NoSync => return None,
+        }
+
+        Some(ToothEvent {
+            timestamp_us,
+            tooth: self.tooth,
+            angle_deg: self.tooth_angle_deg(self.tooth),
+            period_us: period,
+        })
+    }
+
+    /// Ángulo de un diente del patrón (0..cycle_deg)
+    pub fn tooth_angle_deg(&self, tooth: u16) -> f32 {
+        let cycle = self.pattern.cycle_deg();
+        let angle = (self.first_tooth_angle_deg + self.pattern.tooth_angle_deg(tooth as usize)) % cycle;
+        if angle < 0.0 { angle + cycle } else { angle }
+    }
+
+    /// Reinicia el decodificador (ej. motor detenido por timeout)
+    pub fn reset(&mut self) {
+        self.state = SyncState::NoSync;
+        self.edges = 0;
+        self.valid_periods = 0;
+        self.tooth = 0;
+    }
+
+    pub fn state(&self) -> SyncState {
+        self.state
+    }
+
+    pub fn pattern(&self) -> &P {
+        &self.pattern
+    }
+
+    /// Veces que se perdió la sincronía
+    pub fn sync_losses(&self) -> u32 {
+        self.diagnostics.sync_losses
+    }
+
+    /// Contadores de flancos rechazados, ignorados y errores
+    pub fn diagnostics(&self) -> DecoderDiagnostics {
+        self.diagnostics
+    }
+
+    /// RPM instantáneas a partir del último periodo (0 sin sincronía)
+    pub fn rpm(&self) -> f32 {
+        let period = self.periods[PERIOD_HISTORY - 1];
+        if self.state != SyncState::Synced || period == 0 {
+            return 0.0;
+        }
+        let gap_deg = self.pattern.gap_deg(self.tooth as usize);
+        // grados/µs -> RPM: (deg / 360) vueltas en period µs
+        gap_deg / 360.0 * 60_000_000.0 / period as f32
+    }
+
+    /// Ciclos completos del patrón con sincronía
+    pub fn revolutions(&self) -> u32 {
+        self.revolutions
+    }
+
+    fn push_period(&mut self, period: u32) {
+        self.periods.copy_within(1.., 0);
+        self.periods[PERIOD_HISTORY - 1] = period;
+        self.valid_periods = (self.valid_periods + 1).min(PERIOD_HISTORY);
+    }
+
+    /// Un flanco es glitch si llega antes de la fracción configurada del
+    /// periodo esperado para el siguiente diente
+    fn is_glitch(&self, period: u32) -> bool {
+        if self.min_period_fraction <= 0.0 || self.valid_periods == 0 {
+            return false;
+        }
+        let last_period = self.periods[PERIOD_HISTORY - 1] as f32;
+
+        let expected = if self.state == SyncState::Synced {
+            let n = self.pattern.tooth_count();
+            let current = self.tooth as usize;
+            let next = (current + 1) % n;
+            last_period * self.pattern.gap_deg(next) / self.pattern.gap_deg(current)
+        } else {
+            last_period * self.min_gap_ratio
+        };
+
+        (period as f32) < expected * self.min_period_fraction
+    }
+
+    fn ignore_edge(&mut self) {
+        self.diagnostics.ignored_edges = self.diagnostics.ignored_edges.wrapping_add(1);
+    }
+
+    fn lose_sync(&mut self) {
+        self.state = SyncState::Lost;
+        self.diagnostics.sync_losses = self.diagnostics.sync_losses.wrapping_add(1);
+    }
+}
@@ -0,0 +1,155 @@
+/// Sensor que origina una falla
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorId {
+    Tps,
+    Map,
+    Iat,
+    Cts,
+    /// Voltaje de alimentación
+    Battery,
+}
+
+/// Tipo de falla eléctrica de un sensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    /// Voltaje debajo del rango (corto a tierra, o circuito abierto en sensores con pull-down)
+    RangeLow,
+    /// Voltaje arriba del rango (circuito abierto con pull-up, o corto a 5 V)
+    RangeHigh,
+    /// El canal no entregó lectura
+    ReadError,
+    /// Dos señales redundantes no coinciden (ej. pistas de TPS)
+    Disagreement,
+}
+
+/// Código de falla tipado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultCode {
+    pub sensor: SensorId,
+    pub kind: FaultKind,
+}
+
+impl FaultCode {
+    pub const fn new(sensor: SensorId, kind: FaultKind) -> Self {
+        Self { sensor, kind }
+    }
+
+    /// Número del código OBD-II equivalente (ej. 118 para P0118, 2135 para P2135)
+    pub fn obd_code(&self) -> u16 {
+        // (circuito, entrada baja, entrada alta)
+        let (circuit, low, high) = match self.sensor {
+            SensorId::Map => (105, 107, 108),
+            SensorId::Iat => (110, 112, 113),
+            SensorId::Cts => (115, 117, 118),
+            SensorId::Tps => (120, 122, 123),
+            SensorId::Battery => (560, 562, 563),
+        };
+        match self.kind {
+            FaultKind::RangeLow => low,
+            FaultKind::RangeHigh => high,
+            FaultKind::ReadError => circuit,
+            FaultKind::Disagreement => match self.sensor {
+                // Correlación de voltaje de las pistas A/B
+                SensorId::Tps => 2135,
+                _ => circuit,
+            },
+        }
+    }
+}
+
+/// Historial de un código de falla
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultRecord {
+    pub code: FaultCode,
+    pub active: bool,
+    /// Primera vez que se activó (µs)
+    pub first_seen_us: u32,
+    /// Última vez que se activó (µs)
+    pub last_seen_us: u32,
+    /// Veces que se activó
+    pub occurrences: u32,
+}
+
+/// Registro de fallas para diagnóstico (lo lee la herramienta de calibración).
+/// N: códigos distintos que se pueden guardar
+///
+/// Los códigos quedan guardados aunque la falla se quite, hasta `clear_inactive`.
+pub struct DiagnosticLog<const N: usize> {
+    records: [Option<FaultRecord>; N],
+    overflowed: u32,
+}
+
+impl<const N: usize> DiagnosticLog<N> {
+    pub fn new() -> Self {
+        Self { records: [None; N], overflowed: 0 }
+    }
+
+    /// Reporta que la falla se activó (`active`) o se quitó
+    pub fn report(&mut self, code: FaultCode, active: bool, now_us: u32) {
+        if let Some(record) = self.records.iter_mut().flatten().find(|r| r.code == code) {
+            if active && !record.active {
+                record.occurrences = record.occurrences.wrapping_add(1);
+                record.last_seen_us = now_us;
+            }
+            record.active = active;
+            return;
+        }
+        if !active {
+            return;
+        }
+        match self.records.iter_mut().find(|r| r.is_none()) {
+            Some(free) => {
+                *free = Some(FaultRecord {
+                    code,
+                    active: true,
+                    first_seen_us: now_us,
+                    last_seen_us: now_us,
+                    occurrences: 1,
+                })
+            }
+            None => self.overflowed = self.overflowed.wrapping_add(1),
+        }
+    }
+
+    pub fn is_active(&self, code: FaultCode) -> bool {
+        self.records.iter().flatten().any(|r| r.code == code && r.active)
+    }
+
+    /// true si hay alguna falla activa (ej. para la luz de check engine)
+    pub fn any_active(&self) -> bool {
+        self.records.iter().flatten().any(|r| r.active)
+    }
+
+    /// Fallas activas de un sensor
+    pub fn sensor_fault(&self, sensor: SensorId) -> Option<FaultCode> {
+        self.records
+            .iter()
+            .flatten()
+            .find(|r| r.code.sensor == sensor && r.active)
+            .map(|r| r.code)
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &FaultRecord> {
+        self.records.iter().flatten()
+    }
+
+    /// Borra los códigos que ya no están activos
+    pub fn clear_inactive(&mut self) {
+        for slot in self.records.iter_mut() {
+            if matches!(slot, Some(r) if !r.active) {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Códigos que no se pudieron guardar por falta de espacio
+    pub fn overflowed(&self) -> u32 {
+        self.overflowed
+    }
+}
+
+impl<const N: usize> Default for DiagnosticLog<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
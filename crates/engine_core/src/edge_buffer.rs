@@ -0,0 +1,125 @@
+use ecu_traits::engine_io::{CapturedEdge, EdgeCapture};
+
+/// Cola circular de flancos capturados.
+/// N: capacidad en flancos
+///
+/// La interrupción de captura la llena con `fill_from` y el decodificador la
+/// consume con `drain`, así la lógica de decodificación no depende del BSP.
+///
+/// # Ejemplo
+///
+/// ```
+/// use ecu_traits::engine_io::CapturedEdge;
+/// use engine_core::edge_buffer::EdgeBuffer;
+///
+/// let mut buffer: EdgeBuffer<4> = EdgeBuffer::new();
+/// buffer.push(CapturedEdge { timestamp_us: 100, rising: true });
+/// buffer.push(CapturedEdge { timestamp_us: 250, rising: false });
+///
+/// let times: Vec<u32> = buffer.drain().map(|e| e.timestamp_us).collect();
+/// assert_eq!(times, [100, 250]);
+/// assert!(buffer.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct EdgeBuffer<const N: usize> {
+    edges: [CapturedEdge; N],
+    // Índice del flanco más antiguo
+    head: usize,
+    len: usize,
+    overruns: u32,
+}
+
+impl<const N: usize> EdgeBuffer<N> {
+    pub fn new() -> Self {
+        Self {
+            edges: [CapturedEdge { timestamp_us: 0, rising: false }; N],
+            head: 0,
+            len: 0,
+            overruns: 0,
+        }
+    }
+
+    /// Agrega un flanco. Si la cola está llena se descarta y se cuenta como pérdida.
+    pub fn push(&mut self, edge: CapturedEdge) -> bool {
+        if self.len == N {
+            self.overruns = self.overruns.wrapping_add(1);
+            return false;
+        }
+        self.edges[(self.head + self.len) % N] = edge;
+        self.len += 1;
+        true
+    }
+
+    /// Saca el flanco más antiguo
+    pub fn pop(&mut self) -> Option<CapturedEdge> {
+        if self.len == 0 {
+            return None;
+        }
+        let edge = self.edges[self.head];
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(edge)
+    }
+
+    /// Lee del hardware todos los flancos disponibles.
+    /// Las capturas perdidas por el hardware también cuentan como pérdidas.
+    /// Regresa cuántos flancos se agregaron.
+    pub fn fill_from<C: EdgeCapture>(&mut self, capture: &mut C) -> usize {
+        let mut added = 0;
+        // Límite para no quedarse en la interrupción si el hardware reporta errores sin fin
+        for _ in 0..N + 1 {
+            match capture.read_edge() {
+                Ok(Some(edge)) => {
+                    if self.push(edge) {
+                        added += 1;
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => self.overruns = self.overruns.wrapping_add(1),
+            }
+        }
+        added
+    }
+
+    /// Consume los flancos en orden de llegada
+    pub fn drain(&mut self) -> Drain<'_, N> {
+        Drain { buffer: self }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Flancos perdidos (cola llena o sobre-captura del hardware)
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for EdgeBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iterador que vacía la cola
+pub struct Drain<'a, const N: usize> {
+    buffer: &'a mut EdgeBuffer<N>,
+}
+
+impl<const N: usize> Iterator for Drain<'_, N> {
+    type Item = CapturedEdge;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.pop()
+    }
+}
@@ -0,0 +1,201 @@
+/// Configuración de la máquina de estados del motor
+#[derive(Debug, Clone)]
+pub struct EngineStateConfig {
+    /// Con sincronía y arriba de estas RPM el motor está en marcha
+    pub cranking_rpm: f32,
+    /// En marcha se vuelve a arranque solo debajo de `cranking_rpm - cranking_hysteresis_rpm`
+    pub cranking_hysteresis_rpm: f32,
+    /// Tiempo sin dientes después del cual el motor se da por apagado (µs)
+    pub stall_timeout_us: u32,
+    /// TPS (%) desde el que se limpia el ahogo durante el arranque (corte de combustible)
+    pub flood_clear_tps: f32,
+    /// TPS (%) hasta el que se considera acelerador cerrado para el corte en desaceleración
+    pub overrun_max_tps: f32,
+    /// RPM arriba de las que entra el corte en desaceleración
+    pub overrun_enter_rpm: f32,
+    /// RPM debajo de las que sale del corte (antes de llegar a ralentí)
+    pub overrun_exit_rpm: f32,
+}
+
+/// Estado del ciclo de vida del motor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineState {
+    /// Llave apagada, o encendida sin que el motor haya girado
+    Stopped,
+    /// Girando con el motor de arranque (sin sincronía o debajo de las RPM de marcha)
+    Cranking,
+    /// Arranque con el acelerador a fondo: se corta la inyección para limpiar el ahogo
+    FloodClear,
+    Running,
+    /// En marcha con el acelerador cerrado a RPM altas: corte en desaceleración
+    Overrun,
+    /// El motor estaba girando y dejó de recibir dientes con la llave encendida
+    Stalled,
+}
+
+impl EngineState {
+    /// Arranque, incluyendo la limpieza de ahogo
+    pub fn is_cranking(&self) -> bool {
+        matches!(self, EngineState::Cranking | EngineState::FloodClear)
+    }
+
+    /// En marcha, incluyendo el corte en desaceleración
+    pub fn is_running(&self) -> bool {
+        matches!(self, EngineState::Running | EngineState::Overrun)
+    }
+
+    /// Estados en los que no se inyecta
+    pub fn cuts_fuel(&self) -> bool {
+        !matches!(self, EngineState::Cranking | EngineState::Running)
+    }
+}
+
+/// Cambio de estado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EngineTransition {
+    pub from: EngineState,
+    pub to: EngineState,
+    pub timestamp_us: u32,
+}
+
+impl EngineTransition {
+    /// El motor arrancó (de arranque a marcha): inicio del enriquecimiento post-arranque
+    pub fn is_start(&self) -> bool {
+        self.from.is_cranking() && self.to.is_running()
+    }
+
+    /// El motor se apagó solo
+    pub fn is_stall(&self) -> bool {
+        self.to == EngineState::Stalled
+    }
+}
+
+/// Entradas de la máquina de estados en cada actualización
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EngineInputs {
+    pub key_on: bool,
+    /// Decodificador de cigüeñal sincronizado
+    pub synced: bool,
+    pub rpm: f32,
+    pub tps: f32,
+}
+
+/// Máquina de estados del motor: detenido, arranque, marcha, corte en
+/// desaceleración y apagado.
+///
+/// Los dientes se reportan con `on_tooth` (desde la interrupción del
+/// decodificador) y `update` se llama periódicamente con el resto de las
+/// entradas. Cada cambio de estado se regresa como `EngineTransition` para
+/// que los demás módulos reaccionen (ej. enriquecimiento post-arranque).
+pub struct EngineStateMachine {
+    config: EngineStateConfig,
+    state: EngineState,
+    state_since_us: u32,
+    last_tooth_us: Option<u32>,
+    starts: u32,
+    stalls: u32,
+}
+
+impl EngineStateMachine {
+    pub fn new(config: EngineStateConfig) -> Self {
+        Self {
+            config,
+            state: EngineState::Stopped,
+            state_since_us: 0,
+            last_tooth_us: None,
+            starts: 0,
+            stalls: 0,
+        }
+    }
+
+    /// Se llama con cada diente de cigüeñal (sincronizado o no)
+    pub fn on_tooth(&mut self, timestamp_us: u32) {
+        self.last_tooth_us = Some(timestamp_us);
+    }
+
+    /// Evalúa las transiciones. Regresa el cambio de estado, si hubo.
+    pub fn update(&mut self, inputs: EngineInputs, now_us: u32) -> Option<EngineTransition> {
+        let turning = match self.last_tooth_us {
+            Some(t) => now_us.wrapping_sub(t) < self.config.stall_timeout_us,
+            None => false,
+        };
+        if !turning {
+            self.last_tooth_us = None;
+        }
+
+        let next = self.next_state(inputs, turning);
+        if next == self.state {
+            return None;
+        }
+
+        let transition = EngineTransition { from: self.state, to: next, timestamp_us: now_us };
+        if transition.is_start() {
+            self.starts = self.starts.wrapping_add(1);
+        }
+        if transition.is_stall() {
+            self.stalls = self.stalls.wrapping_add(1);
+        }
+        self.state = next;
+        self.state_since_us = now_us;
+        Some(transition)
+    }
+
+    fn next_state(&self, inputs: EngineInputs, turning: bool) -> EngineState {
+        let c = &self.config;
+
+        if !inputs.key_on {
+            return EngineState::Stopped;
+        }
+        if !turning {
+            return match self.state {
+                EngineState::Stopped => EngineState::Stopped,
+                _ => EngineState::Stalled,
+            };
+        }
+
+        // Sin sincronía no hay RPM confiables: en marcha se conserva el estado
+        // (una pérdida momentánea no es un arranque nuevo ni una limpieza de
+        // ahogo) y desde el arranque no se puede pasar a marcha
+        if self.state.is_running() && !inputs.synced {
+            return self.state;
+        }
+        let running = if self.state.is_running() {
+            inputs.rpm >= c.cranking_rpm - c.cranking_hysteresis_rpm
+        } else {
+            inputs.synced && inputs.rpm >= c.cranking_rpm
+        };
+
+        if running {
+            let throttle_closed = inputs.tps <= c.overrun_max_tps;
+            let overrun = if self.state == EngineState::Overrun {
+                throttle_closed && inputs.rpm >= c.overrun_exit_rpm
+            } else {
+                throttle_closed && inputs.rpm >= c.overrun_enter_rpm
+            };
+            if overrun { EngineState::Overrun } else { EngineState::Running }
+        } else if inputs.tps >= c.flood_clear_tps {
+            EngineState::FloodClear
+        } else {
+            EngineState::Cranking
+        }
+    }
+
+    pub fn state(&self) -> EngineState {
+        self.state
+    }
+
+    /// Tiempo en el estado actual (µs)
+    pub fn time_in_state_us(&self, now_us: u32) -> u32 {
+        now_us.wrapping_sub(self.state_since_us)
+    }
+
+    /// Arranques exitosos (transiciones de arranque a marcha)
+    pub fn starts(&self) -> u32 {
+        self.starts
+    }
+
+    /// Veces que el motor se apagó solo
+    pub fn stalls(&self) -> u32 {
+        self.stalls
+    }
+}
@@ -0,0 +1,256 @@
+use ecu_traits::engine_io::AnalogInput;
+
+/// Ventana máxima del filtro de mediana
+pub const MAX_MEDIAN_WINDOW: usize = 7;
+
+/// Mediana de las últimas muestras: elimina picos aislados sin retrasar
+/// los escalones más que media ventana.
+#[derive(Debug, Clone)]
+pub struct MedianFilter {
+    window: [f32; MAX_MEDIAN_WINDOW],
+    size: usize,
+    len: usize,
+    next: usize,
+}
+
+impl MedianFilter {
+    /// size: muestras de la ventana (1..=MAX_MEDIAN_WINDOW; 1 no filtra)
+    pub fn new(size: usize) -> Self {
+        Self {
+            window: [0.0; MAX_MEDIAN_WINDOW],
+            size: size.clamp(1, MAX_MEDIAN_WINDOW),
+            len: 0,
+            next: 0,
+        }
+    }
+
+    pub fn apply(&mut self, sample: f32) -> f32 {
+        self.window[self.next] = sample;
+        self.next = (self.next + 1) % self.size;
+        self.len = (self.len + 1).min(self.size);
+
+        let mut sorted = [0.0; MAX_MEDIAN_WINDOW];
+        let sorted = &mut sorted[..self.len];
+        // Ordenamiento por inserción: la ventana es chica y no arrastra el sort de core
+        for (i, &sample) in self.window[..self.len].iter().enumerate() {
+            let mut j = i;
+            while j > 0 && sorted[j - 1] > sample {
+                sorted[j] = sorted[j - 1];
+                j -= 1;
+            }
+            sorted[j] = sample;
+        }
+
+        let mid = self.len / 2;
+        if self.len.is_multiple_of(2) { (sorted[mid - 1] + sorted[mid]) / 2.0 } else { sorted[mid] }
+    }
+
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+}
+
+/// Filtro de primer orden (paso bajo) con constante de tiempo en µs.
+/// Ante un escalón llega al 63% en `tau_us`.
+#[derive(Debug, Clone)]
+pub struct LagFilter {
+    tau_us: u32,
+    state: Option<f32>,
+}
+
+impl LagFilter {
+    pub fn new(tau_us: u32) -> Self {
+        Self { tau_us, state: None }
+    }
+
+    /// dt_us: tiempo desde la muestra anterior
+    pub fn apply(&mut self, sample: f32, dt_us: u32) -> f32 {
+        let out = match self.state {
+            // La primera muestra inicializa el filtro (sin rampa desde cero)
+            None => sample,
+            Some(prev) => {
+                let dt = dt_us as f32;
+                let alpha = dt / (self.tau_us as f32 + dt);
+                prev + alpha * (sample - prev)
+            }
+        };
+        self.state = Some(out);
+        out
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Limita la velocidad de cambio de la señal (unidades por segundo)
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    max_per_s: f32,
+    state: Option<f32>,
+}
+
+impl RateLimiter {
+    pub fn new(max_per_s: f32) -> Self {
+        Self { max_per_s, state: None }
+    }
+
+    pub fn apply(&mut self, sample: f32, dt_us: u32) -> f32 {
+        let out = match self.state {
+            None => sample,
+            Some(prev) => {
+                let max_step = self.max_per_s * dt_us as f32 / 1_000_000.0;
+                prev + (sample - prev).clamp(-max_step, max_step)
+            }
+        };
+        self.state = Some(out);
+        out
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Cadena de filtros de un canal analógico (se elige en la configuración)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FilterConfig {
+    /// Lecturas del ADC que se promedian por muestra (1 = sin sobremuestreo)
+    pub oversample: u8,
+    /// Ventana de la mediana (1 = sin mediana)
+    pub median_window: usize,
+    /// Constante de tiempo del filtro de primer orden (0 = sin filtro)
+    pub lag_tau_us: u32,
+    /// Velocidad máxima de cambio en V/s (0 = sin límite)
+    pub rate_limit_per_s: f32,
+    /// Periodo con el que se lee el canal (µs)
+    pub sample_period_us: u32,
+}
+
+impl FilterConfig {
+    /// Sin filtrado: una lectura por muestra
+    pub const fn none(sample_period_us: u32) -> Self {
+        Self { oversample: 1, median_window: 1, lag_tau_us: 0, rate_limit_per_s: 0.0, sample_period_us }
+    }
+}
+
+/// Filtros aplicados en orden: mediana, primer orden y límite de cambio.
+/// El sobremuestreo lo hace `FilteredInput` antes de la cadena.
+#[derive(Debug, Clone)]
+pub struct FilterChain {
+    config: FilterConfig,
+    median: MedianFilter,
+    lag: LagFilter,
+    rate: RateLimiter,
+    output: f32,
+}
+
+impl FilterChain {
+    pub fn new(config: FilterConfig) -> Self {
+        Self {
+            config,
+            median: MedianFilter::new(config.median_window),
+            lag: LagFilter::new(config.lag_tau_us),
+            rate: RateLimiter::new(config.rate_limit_per_s),
+            output: 0.0,
+        }
+    }
+
+    pub fn apply(&mut self, sample: f32, dt_us: u32) -> f32 {
+        let mut value = sample;
+        if self.config.median_window > 1 {
+            value = self.median.apply(value);
+        }
+        if self.config.lag_tau_us > 0 {
+            value = self.lag.apply(value, dt_us);
+        }
+        if self.config.rate_limit_per_s > 0.0 {
+            value = self.rate.apply(value, dt_us);
+        }
+        self.output = value;
+        value
+    }
+
+    /// Último valor filtrado
+    pub fn output(&self) -> f32 {
+        self.output
+    }
+
+    pub fn config(&self) -> &FilterConfig {
+        &self.config
+    }
+
+    /// Olvida la historia (ej. al recuperarse de una falla del sensor)
+    pub fn reset(&mut self) {
+        self.median.reset();
+        self.lag.reset();
+        self.rate.reset();
+    }
+}
+
+/// Canal analógico filtrado: se usa en lugar del canal crudo dentro de
+/// `CalibratedSensor`, así el filtro trabaja sobre voltaje antes de convertir.
+/// El voltaje antes de la cadena queda en `raw_volts` para que el
+/// diagnóstico de rango no espere al filtro (ver `MonitoredSensor`).
+///
+/// # Ejemplo
+///
+/// ```
+/// use ecu_traits::engine_io::AnalogInput;
+/// use ecu_traits::mock::MockAnalogInput;
+/// use engine_core::filters::{FilterConfig, FilteredInput};
+///
+/// // Un pico de 5 V entre lecturas de 1 V
+/// let input = MockAnalogInput::new([1.0, 1.0, 5.0, 1.0]);
+/// let config = FilterConfig { median_window: 3, ..FilterConfig::none(10_000) };
+/// let mut filtered = FilteredInput::new(input, config);
+///
+/// for _ in 0..4 {
+///     assert_eq!(filtered.read_volts().unwrap(), 1.0);
+/// }
+/// ```
+pub struct FilteredInput<I: AnalogInput> {
+    input: I,
+    chain: FilterChain,
+    raw_volts: Option<f32>,
+}
+
+impl<I: AnalogInput> FilteredInput<I> {
+    pub fn new(input: I, config: FilterConfig) -> Self {
+        Self { input, chain: FilterChain::new(config), raw_volts: None }
+    }
+
+    pub fn chain(&self) -> &FilterChain {
+        &self.chain
+    }
+
+    pub fn chain_mut(&mut self) -> &mut FilterChain {
+        &mut self.chain
+    }
+
+    pub fn input_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Promedio del sobremuestreo de la última lectura, antes de la cadena de filtros
+    pub fn raw_volts(&self) -> Option<f32> {
+        self.raw_volts
+    }
+}
+
+impl<I: AnalogInput> AnalogInput for FilteredInput<I> {
+    type Error = I::Error;
+
+    fn read_volts(&mut self) -> Result<f32, Self::Error> {
+        let config = *self.chain.config();
+        let reads = config.oversample.max(1);
+        let mut sum = 0.0;
+        for _ in 0..reads {
+            sum += self.input.read_volts()?;
+        }
+        let raw = sum / reads as f32;
+        self.raw_volts = Some(raw);
+        Ok(self.chain.apply(raw, config.sample_period_us))
+    }
+}
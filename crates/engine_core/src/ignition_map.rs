@@ -0,0 +1,189 @@
+/// Modo de encendido del motor.
+/// Define cómo se reparten los cilindros entre las salidas de bobina de la Board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgnitionMode {
+    /// Chispa perdida: dos cilindros gemelos (separados 360°) comparten una salida.
+    /// La bobina dispara cada 360°, por lo que solo necesita sincronía de cigüeñal.
+    WastedSpark,
+    /// Bobina por cilindro (secuencial). Cada salida dispara cada 720°,
+    /// por lo que necesita sincronía de leva (CMP).
+    CoilOnPlug,
+    /// Una sola bobina con distribuidor: todos los cilindros usan la salida 0.
+    Distributor,
+}
+
+/// Errores de validación de la configuración de encendido
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgnitionConfigError {
+    /// El orden de encendido no es una permutación de 1..=N
+    InvalidFiringOrder,
+    /// Chispa perdida requiere un número par de cilindros
+    OddCylinderCount,
+    /// Algún cilindro apunta a una salida que la Board no tiene
+    OutputOutOfRange,
+    /// Algún PMS está fuera de 0..720°
+    TdcOutOfRange,
+}
+
+/// Un evento de chispa dentro del ciclo del modo configurado
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SparkEvent {
+    /// Cilindro (base 1) cuyo PMS de compresión genera el evento
+    pub cylinder: u8,
+    /// Índice (base 0) de la salida de bobina de la Board
+    pub output: u8,
+    /// Ángulo del PMS dentro del ciclo (0..360 o 0..720 según el modo)
+    pub tdc_deg: f32,
+}
+
+/// Configuración de la capa de mapeo de encendido.
+/// N: Número de cilindros
+///
+/// # Ejemplo
+///
+/// ```
+/// use engine_core::ignition_map::{IgnitionConfig, IgnitionMode};
+///
+/// // 4 cilindros en línea, orden 1-3-4-2, chispa perdida
+/// let config = IgnitionConfig::even_fire(IgnitionMode::WastedSpark, [1, 3, 4, 2]);
+///
+/// // Los cilindros 1 y 4 comparten la salida 0
+/// assert_eq!(config.output_for_cylinder(1), Some(0));
+/// assert_eq!(config.output_for_cylinder(4), Some(0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct IgnitionConfig<const N: usize> {
+    pub mode: IgnitionMode,
+    /// Orden de encendido con números de cilindro base 1 (ej. [1, 3, 4, 2])
+    pub firing_order: [u8; N],
+    /// Ángulo del PMS de compresión de cada cilindro (índice = cilindro - 1), 0..720°
+    pub tdc_offsets: [f32; N],
+    /// Salida de bobina asignada a cada cilindro (índice = cilindro - 1)
+    pub coil_outputs: [u8; N],
+}
+
+impl<const N: usize> IgnitionConfig<N> {
+    /// Constructor para motores de encendido parejo (720 / N grados entre chispas).
+    /// Calcula los PMS a partir del orden de encendido y asigna las salidas
+    /// según el modo:
+    /// - CoilOnPlug: salida = cilindro - 1
+    /// - WastedSpark: los gemelos comparten la salida de su posición en la primera mitad del orden
+    /// - Distributor: todo a la salida 0
+    pub fn even_fire(mode: IgnitionMode, firing_order: [u8; N]) -> Self {
+        let spacing = 720.0 / (N as f32);
+        let mut tdc_offsets = [0.0; N];
+        let mut coil_outputs = [0u8; N];
+
+        for (position, &cylinder) in firing_order.iter().enumerate() {
+            let idx = (cylinder as usize).wrapping_sub(1);
+            // Si el orden es inválido lo dejamos pasar, validate() lo reporta
+            if idx >= N { continue; }
+
+            tdc_offsets[idx] = spacing * (position as f32);
+            coil_outputs[idx] = match mode {
+                IgnitionMode::CoilOnPlug => idx as u8,
+                IgnitionMode::WastedSpark => (position % (N / 2).max(1)) as u8,
+                IgnitionMode::Distributor => 0,
+            };
+        }
+
+        Self { mode, firing_order, tdc_offsets, coil_outputs }
+    }
+
+    /// Verifica que la configuración tenga sentido para una Board con `available_outputs` bobinas
+    pub fn validate(&self, available_outputs: usize) -> Result<(), IgnitionConfigError> {
+        // El orden debe contener cada cilindro exactamente una vez
+        let mut seen = [false; N];
+        for &cylinder in self.firing_order.iter() {
+            let idx = (cylinder as usize).wrapping_sub(1);
+            if idx >= N || seen[idx] {
+                return Err(IgnitionConfigError::InvalidFiringOrder);
+            }
+            seen[idx] = true;
+        }
+
+        if self.mode == IgnitionMode::WastedSpark && !N.is_multiple_of(2) {
+            return Err(IgnitionConfigError::OddCylinderCount);
+        }
+
+        if self.tdc_offsets.iter().any(|&tdc| !(0.0..720.0).contains(&tdc)) {
+            return Err(IgnitionConfigError::TdcOutOfRange);
+        }
+
+        if self.coil_outputs.iter().any(|&out| out as usize >= available_outputs) {
+            return Err(IgnitionConfigError::OutputOutOfRange);
+        }
+
+        Ok(())
+    }
+
+    /// Longitud del ciclo de disparo del modo: 720° para secuencial, 360° para el resto
+    pub fn cycle_deg(&self) -> f32 {
+        match self.mode {
+            IgnitionMode::CoilOnPlug => 720.0,
+            IgnitionMode::WastedSpark | IgnitionMode::Distributor => 360.0,
+        }
+    }
+
+    /// Indica si el modo necesita conocer la fase del motor (señal de leva)
+    pub fn requires_cam_sync(&self) -> bool {
+        self.mode == IgnitionMode::CoilOnPlug
+    }
+
+    /// Salida de bobina asignada a un cilindro (base 1)
+    pub fn output_for_cylinder(&self, cylinder: u8) -> Option<u8> {
+        let idx = (cylinder as usize).wrapping_sub(1);
+        self.coil_outputs.get(idx).copied()
+    }
+
+    /// Itera los eventos de chispa de un ciclo en orden de encendido.
+    /// En los modos de 360° los PMS se reducen módulo 360 y se descartan los
+    /// duplicados (el gemelo dispara con el mismo evento).
+    pub fn events(&self) -> SparkEvents<'_, N> {
+        SparkEvents { config: self, position: 0 }
+    }
+}
+
+/// Iterador de eventos de chispa, ver [`IgnitionConfig::events`]
+pub struct SparkEvents<'a, const N: usize> {
+    config: &'a IgnitionConfig<N>,
+    position: usize,
+}
+
+impl<const N: usize> Iterator for SparkEvents<'_, N> {
+    type Item = SparkEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cycle = self.config.cycle_deg();
+
+        while self.position < N {
+            let position = self.position;
+            self.position += 1;
+
+            let event = self.event_at(position, cycle)?;
+
+            // En 360° el gemelo ya salió antes en el orden: lo saltamos
+            let duplicated = (0..position)
+                .filter_map(|prev| self.event_at(prev, cycle))
+                .any(|prev| prev.output == event.output && prev.tdc_deg == event.tdc_deg);
+
+            if !duplicated {
+                return Some(event);
+            }
+        }
+        None
+    }
+}
+
+impl<const N: usize> SparkEvents<'_, N> {
+    fn event_at(&self, position: usize, cycle: f32) -> Option<SparkEvent> {
+        let cylinder = self.config.firing_order[position];
+        let idx = (cylinder as usize).checked_sub(1)?;
+
+        Some(SparkEvent {
+            cylinder,
+            output: *self.config.coil_outputs.get(idx)?,
+            tdc_deg: self.config.tdc_offsets.get(idx)? % cycle,
+        })
+    }
+}
@@ -0,0 +1,194 @@
+use crate::spark_advance::SparkCorrection;
+use crate::tables::{Table2D, Table3D};
+
+/// Configuración del control de cascabeleo (knock).
+/// T: puntos de la curva de umbral vs RPM
+#[derive(Debug, Clone)]
+pub struct KnockConfig<const T: usize> {
+    /// Intensidad a partir de la cual se considera knock, vs RPM
+    pub threshold: Table2D<T>,
+    /// Retardo que se agrega en cada evento con knock (grados)
+    pub retard_step_deg: f32,
+    /// Retardo máximo total por cilindro (grados)
+    pub max_retard_deg: f32,
+    /// Avance que se recupera por cada evento limpio (grados)
+    pub recovery_step_deg: f32,
+    /// Eventos limpios antes de empezar a recuperar
+    pub recovery_delay_events: u16,
+}
+
+/// Control de knock por cilindro.
+/// C: número de cilindros
+/// T: puntos de la curva de umbral
+pub struct KnockController<const C: usize, const T: usize> {
+    config: KnockConfig<T>,
+    // Retardo dinámico por cilindro
+    retard: [f32; C],
+    // Eventos seguidos sin knock por cilindro
+    quiet_events: [u16; C],
+    // Eventos con knock por cilindro (diagnóstico)
+    knock_count: [u32; C],
+    // Retardo base aprendido en el punto de operación actual
+    learned_deg: f32,
+}
+
+impl<const C: usize, const T: usize> KnockController<C, T> {
+    pub fn new(config: KnockConfig<T>) -> Self {
+        Self {
+            config,
+            retard: [0.0; C],
+            quiet_events: [0; C],
+            knock_count: [0; C],
+            learned_deg: 0.0,
+        }
+    }
+
+    /// Procesa la intensidad medida después de una combustión.
+    /// cylinder: cilindro (base 0)
+    /// intensity: intensidad normalizada del knock
+    /// rpm: RPM actuales (para el umbral)
+    /// Regresa true si el evento se consideró knock.
+    pub fn on_combustion(&mut self, cylinder: usize, intensity: f32, rpm: f32) -> bool {
+        if cylinder >= C { return false; }
+
+        let threshold = self.config.threshold.interpolate(rpm);
+        let knock = intensity > threshold;
+
+        if knock {
+            // Retardo escalonado, sin pasar del máximo total
+            let limit = (self.config.max_retard_deg - self.learned_deg).max(0.0);
+            self.retard[cylinder] = (self.retard[cylinder] + self.config.retard_step_deg).min(limit);
+            self.quiet_events[cylinder] = 0;
+            self.knock_count[cylinder] = self.knock_count[cylinder].wrapping_add(1);
+        } else if self.quiet_events[cylinder] < self.config.recovery_delay_events {
+            self.quiet_events[cylinder] += 1;
+        } else {
+            // Recuperación gradual del avance
+            self.retard[cylinder] = (self.retard[cylinder] - self.config.recovery_step_deg).max(0.0);
+        }
+
+        knock
+    }
+
+    /// Fija el retardo aprendido para el punto de operación actual (ver [`KnockLearning`])
+    pub fn set_learned_retard(&mut self, learned_deg: f32) {
+        self.learned_deg = learned_deg.clamp(0.0, self.config.max_retard_deg);
+    }
+
+    /// Mueve grados del retardo dinámico al aprendido (negativo: el aprendido
+    /// devuelve avance). Solo se mueve lo que todos los cilindros comparten,
+    /// así el retardo efectivo de cada uno no cambia al aprender.
+    fn shift_to_learned(&mut self, deg: f32) {
+        let deg = deg.min(self.common_retard_deg());
+        let before = self.learned_deg;
+        self.learned_deg = (before + deg).clamp(0.0, self.config.max_retard_deg);
+        let moved = self.learned_deg - before;
+        if moved > 0.0 {
+            for retard in self.retard.iter_mut() {
+                *retard -= moved;
+            }
+        }
+    }
+
+    /// Retardo dinámico (sin el aprendido) del cilindro
+    pub fn dynamic_retard_deg(&self, cylinder: usize) -> f32 {
+        self.retard.get(cylinder).copied().unwrap_or(0.0)
+    }
+
+    /// Retardo dinámico promedio de todos los cilindros
+    pub fn average_retard_deg(&self) -> f32 {
+        if C == 0 { return 0.0; }
+        self.retard.iter().sum::<f32>() / C as f32
+    }
+
+    /// Retardo dinámico que tienen todos los cilindros (el menor)
+    pub fn common_retard_deg(&self) -> f32 {
+        if C == 0 { return 0.0; }
+        self.retard.iter().copied().fold(f32::MAX, f32::min)
+    }
+
+    /// Eventos con knock detectados en el cilindro
+    pub fn knock_count(&self, cylinder: usize) -> u32 {
+        self.knock_count.get(cylinder).copied().unwrap_or(0)
+    }
+}
+
+impl<const C: usize, const T: usize> SparkCorrection for KnockController<C, T> {
+    fn retard_deg(&self, cylinder: usize) -> f32 {
+        (self.learned_deg + self.dynamic_retard_deg(cylinder)).min(self.config.max_retard_deg)
+    }
+}
+
+/// Tabla de aprendizaje adaptivo de retardo por knock (RPM x Carga).
+/// El contenido se puede guardar en memoria no volátil y restaurar con `from_table`.
+/// N: columnas (RPM)
+/// M: filas (Carga)
+pub struct KnockLearning<const N: usize, const M: usize> {
+    table: Table3D<N, M>,
+    /// Fracción del retardo dinámico que se mueve a la celda en cada llamada
+    pub learn_rate: f32,
+    /// Grados que se devuelven por llamada cuando no hay retardo dinámico
+    pub decay_deg: f32,
+    /// Retardo máximo que puede guardar una celda
+    pub max_learned_deg: f32,
+}
+
+impl<const N: usize, const M: usize> KnockLearning<N, M> {
+    /// Crea una tabla de aprendizaje vacía
+    pub fn new(rpm_axis: [f32; N], load_axis: [f32; M], learn_rate: f32, decay_deg: f32, max_learned_deg: f32) -> Self {
+        Self::from_table(Table3D::new(rpm_axis, load_axis, [[0.0; N]; M]), learn_rate, decay_deg, max_learned_deg)
+    }
+
+    /// Restaura una tabla previamente aprendida
+    pub fn from_table(table: Table3D<N, M>, learn_rate: f32, decay_deg: f32, max_learned_deg: f32) -> Self {
+        Self { table, learn_rate, decay_deg, max_learned_deg }
+    }
+
+    /// Retardo aprendido interpolado en el punto de operación
+    pub fn learned_retard_deg(&self, rpm: f32, load: f32) -> f32 {
+        self.table.interpolate(rpm, load)
+    }
+
+    /// Actualiza la celda más cercana al punto de operación con el retardo
+    /// dinámico que comparten todos los cilindros (el knock de uno solo no
+    /// castiga a los demás). El cambio del valor interpolado, el mismo que
+    /// entrega `learned_retard_deg`, se le quita al retardo dinámico del
+    /// controlador: el retardo efectivo de cada cilindro no cambia al aprender.
+    pub fn learn<const C: usize, const T: usize>(
+        &mut self,
+        rpm: f32,
+        load: f32,
+        controller: &mut KnockController<C, T>,
+    ) {
+        let before = self.learned_retard_deg(rpm, load);
+        let col = nearest_index(&self.table.x_axis, rpm);
+        let row = nearest_index(&self.table.y_axis, load);
+        let cell = &mut self.table.data[row][col];
+
+        let common_retard_deg = controller.common_retard_deg();
+        if common_retard_deg > 0.0 {
+            *cell += self.learn_rate * common_retard_deg;
+        } else if (0..C).all(|cyl| controller.dynamic_retard_deg(cyl) == 0.0) {
+            // Sin knock: se devuelve avance poco a poco
+            *cell -= self.decay_deg;
+        }
+        *cell = cell.clamp(0.0, self.max_learned_deg);
+        controller.shift_to_learned(self.learned_retard_deg(rpm, load) - before);
+    }
+
+    /// Tabla aprendida, para guardarla
+    pub fn table(&self) -> &Table3D<N, M> {
+        &self.table
+    }
+}
+
+/// Índice del breakpoint más cercano al valor
+fn nearest_index(axis: &[f32], value: f32) -> usize {
+    let mut best = 0;
+    for (i, &point) in axis.iter().enumerate() {
+        if (point - value).abs() < (axis[best] - value).abs() {
+            best = i;
+        }
+    }
+    best
+}
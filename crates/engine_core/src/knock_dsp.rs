@@ -0,0 +1,179 @@
+use libm::{cosf, sinf, sqrtf};
+use core::f32::consts::PI;
+
+/// Método de filtrado para extraer la energía en la frecuencia de knock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnockFilterKind {
+    /// Filtro pasa-banda IIR de segundo orden, se mide el RMS de la salida
+    BandPass,
+    /// Goertzel: magnitud de un solo bin de la DFT
+    Goertzel,
+}
+
+/// Configuración del procesamiento de la señal del sensor de knock
+#[derive(Debug, Clone)]
+pub struct KnockDspConfig {
+    /// Frecuencia de muestreo del ADC durante la ventana (Hz)
+    pub sample_rate_hz: f32,
+    /// Frecuencia de resonancia del motor (Hz), típicamente 5-15 kHz
+    /// Aproximación: 900 / (PI * radio del cilindro en metros)
+    pub knock_freq_hz: f32,
+    /// Factor de calidad del pasa-banda (ancho de banda = f / Q)
+    pub q: f32,
+    pub filter: KnockFilterKind,
+    /// Constante de aprendizaje del ruido de fondo (0.0 a 1.0)
+    pub noise_alpha: f32,
+    /// Las ventanas con intensidad mayor a esta no actualizan el ruido de fondo,
+    /// para que el knock no "enseñe" al estimador
+    pub noise_reject_ratio: f32,
+}
+
+/// Filtro IIR de segundo orden (forma directa II transpuesta)
+#[derive(Debug, Clone, Copy)]
+pub struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    /// Pasa-banda con ganancia 0 dB en la frecuencia central (RBJ Audio EQ Cookbook)
+    pub fn band_pass(center_hz: f32, q: f32, sample_rate_hz: f32) -> Self {
+        let w0 = 2.0 * PI * center_hz / sample_rate_hz;
+        let alpha = sinf(w0) / (2.0 * q);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: alpha / a0,
+            b1: 0.0,
+            b2: -alpha / a0,
+            a1: (-2.0 * cosf(w0)) / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    /// Procesa una muestra
+    pub fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+
+    /// Limpia el estado interno (al inicio de cada ventana)
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+/// Magnitud (amplitud pico estimada) de un tono con el algoritmo de Goertzel
+pub fn goertzel_amplitude(samples: &[f32], freq_hz: f32, sample_rate_hz: f32) -> f32 {
+    if samples.is_empty() { return 0.0; }
+
+    let w = 2.0 * PI * freq_hz / sample_rate_hz;
+    let coeff = 2.0 * cosf(w);
+    let (mut s1, mut s2) = (0.0f32, 0.0f32);
+
+    for &x in samples {
+        let s0 = x + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+
+    let power = s1 * s1 + s2 * s2 - coeff * s1 * s2;
+    2.0 * sqrtf(power.max(0.0)) / samples.len() as f32
+}
+
+/// Procesador de ventanas de knock.
+/// C: número de cilindros (cada uno tiene su propio ruido de fondo)
+/// W: tamaño máximo de ventana en muestras
+pub struct KnockProcessor<const C: usize, const W: usize> {
+    config: KnockDspConfig,
+    filter: Biquad,
+    // Nivel de ruido de fondo por cilindro (misma unidad que la energía medida)
+    background: [f32; C],
+    // Ventanas procesadas por cilindro (para el arranque del estimador)
+    windows: [u16; C],
+    // Buffer de trabajo con la señal sin offset DC
+    work: [f32; W],
+}
+
+impl<const C: usize, const W: usize> KnockProcessor<C, W> {
+    pub fn new(config: KnockDspConfig) -> Self {
+        let filter = Biquad::band_pass(config.knock_freq_hz, config.q, config.sample_rate_hz);
+        Self {
+            config,
+            filter,
+            background: [0.0; C],
+            windows: [0; C],
+            work: [0.0; W],
+        }
+    }
+
+    /// Procesa las muestras crudas del ADC capturadas en la ventana angular
+    /// de un cilindro y regresa la intensidad normalizada contra su ruido de fondo
+    /// (1.0 = nivel de ruido normal).
+    pub fn process_window(&mut self, cylinder: usize, samples: &[u16]) -> f32 {
+        if cylinder >= C { return 0.0; }
+
+        let energy = self.window_energy(samples);
+        let background = self.background[cylinder];
+        let intensity = if background > 0.0 { energy / background } else { 1.0 };
+
+        // Al arrancar se usa el promedio acumulado (alpha = 1/n) hasta llegar
+        // a la constante configurada; una sola ventana es una mala referencia
+        let n = self.windows[cylinder].saturating_add(1);
+        self.windows[cylinder] = n;
+        let alpha = self.config.noise_alpha.clamp(0.0, 1.0);
+        let warming_up = 1.0 / (n as f32) > alpha;
+
+        if warming_up {
+            self.background[cylinder] += (energy - background) / n as f32;
+        } else if intensity < self.config.noise_reject_ratio {
+            self.background[cylinder] += alpha * (energy - background);
+        }
+
+        intensity
+    }
+
+    /// Ruido de fondo estimado del cilindro
+    pub fn background(&self, cylinder: usize) -> f32 {
+        self.background.get(cylinder).copied().unwrap_or(0.0)
+    }
+
+    /// Energía en la banda de knock (amplitud) de una ventana
+    fn window_energy(&mut self, samples: &[u16]) -> f32 {
+        let len = samples.len().min(W);
+        if len == 0 { return 0.0; }
+
+        // Quitamos el offset DC (el sensor está polarizado a media escala)
+        let mean = samples[..len].iter().map(|&s| s as f32).sum::<f32>() / len as f32;
+        for (dst, &src) in self.work.iter_mut().zip(samples[..len].iter()) {
+            *dst = src as f32 - mean;
+        }
+        let signal = &self.work[..len];
+
+        match self.config.filter {
+            KnockFilterKind::Goertzel => {
+                goertzel_amplitude(signal, self.config.knock_freq_hz, self.config.sample_rate_hz)
+            }
+            KnockFilterKind::BandPass => {
+                self.filter.reset();
+                let mut sum_sq = 0.0;
+                for &x in signal {
+                    let y = self.filter.process(x);
+                    sum_sq += y * y;
+                }
+                // RMS escalado a amplitud pico para ser comparable con Goertzel
+                sqrtf(2.0 * sum_sq / len as f32)
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::rev_limiter::{CutDecision, CutMode, CutPattern, CutPatternKind};
+
+/// Configuración del two-step (launch control) y del flat-shift
+#[derive(Debug, Clone)]
+pub struct LaunchConfig {
+    /// RPM sostenidas en la salida (clutch pisado, vehículo detenido)
+    pub launch_rpm: f32,
+    /// Arriba de esta velocidad el clutch se interpreta como cambio de marcha (km/h)
+    pub max_launch_speed_kph: f32,
+    /// Retardo de chispa mientras se sostiene el launch (grados)
+    pub launch_retard_deg: f32,
+    /// El flat-shift sostiene las RPM previas al cambio menos este valor
+    pub flat_shift_offset_rpm: f32,
+    /// Retardo de chispa durante el flat-shift (grados)
+    pub flat_shift_retard_deg: f32,
+    /// Debajo de estas RPM el flat-shift no se activa (cambio normal)
+    pub min_flat_shift_rpm: f32,
+    /// Fracción de corte al alcanzar el objetivo (0.0 a 1.0)
+    pub min_cut_fraction: f32,
+    /// RPM sobre el objetivo en las que el corte llega al 100%
+    pub cut_ramp_rpm: f32,
+    pub cut_mode: CutMode,
+    pub pattern: CutPatternKind,
+}
+
+/// Modo activo del control de salida
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchState {
+    Off,
+    /// Two-step: clutch pisado con el vehículo detenido
+    Launch,
+    /// Cambio sin levantar el pie: clutch pisado en movimiento
+    FlatShift,
+}
+
+/// Estado para logs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LaunchStatus {
+    pub state: LaunchState,
+    /// RPM objetivo que se está sosteniendo
+    pub target_rpm: f32,
+    pub retard_deg: f32,
+    pub cut_fraction: f32,
+}
+
+/// Launch control y flat-shift.
+/// Usa los mismos patrones de corte que el limitador de RPM.
+pub struct LaunchControl {
+    config: LaunchConfig,
+    pattern: CutPattern,
+    status: LaunchStatus,
+    /// Clutch pisado en la llamada anterior (para detectar el flanco)
+    clutch_held: bool,
+}
+
+impl LaunchControl {
+    pub fn new(config: LaunchConfig, cylinders: u8) -> Self {
+        let pattern = CutPattern::new(config.pattern, cylinders);
+        Self {
+            config,
+            pattern,
+            status: LaunchStatus {
+                state: LaunchState::Off,
+                target_rpm: 0.0,
+                retard_deg: 0.0,
+                cut_fraction: 0.0,
+            },
+            clutch_held: false,
+        }
+    }
+
+    /// Actualiza el modo con las entradas del vehículo.
+    /// clutch_engaged: switch de clutch activo (pedal pisado)
+    /// speed_kph: velocidad del vehículo
+    /// rpm: RPM actuales
+    pub fn update(&mut self, clutch_engaged: bool, speed_kph: f32, rpm: f32) -> LaunchStatus {
+        // El modo se decide en el flanco de pisar el clutch y se mantiene hasta
+        // soltarlo: un cambio que empezó debajo de las RPM mínimas no se vuelve
+        // flat-shift aunque las RPM suban con el pedal pisado
+        let pressed = clutch_engaged && !self.clutch_held;
+        self.clutch_held = clutch_engaged;
+        let state = if !clutch_engaged {
+            LaunchState::Off
+        } else if !pressed {
+            self.status.state
+        } else if speed_kph < self.config.max_launch_speed_kph {
+            LaunchState::Launch
+        } else if rpm >= self.config.min_flat_shift_rpm {
+            // Se captura el objetivo con las RPM previas al cambio
+            self.status.target_rpm = rpm - self.config.flat_shift_offset_rpm;
+            LaunchState::FlatShift
+        } else {
+            LaunchState::Off
+        };
+
+        let (target_rpm, retard) = match state {
+            LaunchState::Off => (0.0, 0.0),
+            LaunchState::Launch => (self.config.launch_rpm, self.config.launch_retard_deg),
+            LaunchState::FlatShift => (self.status.target_rpm, self.config.flat_shift_retard_deg),
+        };
+
+        // Retardo y corte solo cuando se alcanza el objetivo
+        let (retard_deg, cut_fraction) = if state != LaunchState::Off && rpm >= target_rpm {
+            let ramp = self.config.cut_ramp_rpm.max(1.0);
+            let factor = ((rpm - target_rpm) / ramp).clamp(0.0, 1.0);
+            let min_cut = self.config.min_cut_fraction.clamp(0.0, 1.0);
+            (retard, min_cut + (1.0 - min_cut) * factor)
+        } else {
+            (0.0, 0.0)
+        };
+
+        self.status = LaunchStatus { state, target_rpm, retard_deg, cut_fraction };
+        self.status
+    }
+
+    /// Decide el corte del siguiente evento de combustión (en orden de encendido)
+    pub fn next_cut(&mut self) -> CutDecision {
+        if self.pattern.should_cut(self.status.cut_fraction) {
+            CutDecision::from_mode(self.config.cut_mode)
+        } else {
+            CutDecision::NONE
+        }
+    }
+
+    /// Estado actual para logs
+    pub fn status(&self) -> LaunchStatus {
+        self.status
+    }
+}
@@ -1,8 +1,31 @@
 #![no_std]
 
-// Permitimos std solo para correr tests unitarios en PC
-#[cfg(test)]
+// Permitimos std solo para correr tests unitarios y herramientas en PC
+#[cfg(any(test, feature = "std"))]
 extern crate std;
 
 pub mod tables; // <--- Aquí vivirá la matemática
-pub mod fuel_model;
\ No newline at end of file
+pub mod fuel_model;
+pub mod ignition_map;
+pub mod rev_limiter;
+pub mod launch_control;
+pub mod spark_advance;
+pub mod knock;
+pub mod knock_dsp;
+pub mod crank_decoder;
+pub mod trigger_patterns;
+pub mod cam_sync;
+pub mod angle_predictor;
+pub mod scheduler;
+pub mod edge_buffer;
+pub mod trigger_log;
+pub mod signal_gen;
+pub mod engine_state;
+pub mod analog;
+pub mod diagnostics;
+pub mod sensor_fault;
+pub mod filters;
+pub mod map_sampler;
+pub mod o2;
+pub mod battery;
+pub mod tps;
@@ -0,0 +1,226 @@
+use crate::angle_predictor::AnglePredictor;
+use crate::filters::LagFilter;
+use crate::fuel_model::SpeedDensity;
+use crate::scheduler::{
+    AngleEvent, AngleScheduler, CompareAction, EventEnd, ScheduleError, ScheduleOutcome, TimerBackend,
+};
+
+/// Cómo se reduce la ventana a un solo valor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapSampleMode {
+    /// Promedio de las muestras de la ventana
+    Average,
+    /// Mínimo de la ventana (el vacío de la carrera de admisión)
+    Minimum,
+}
+
+/// Configuración del muestreo de MAP sincronizado con el cigüeñal.
+/// C: ventanas por ciclo (una por cilindro)
+#[derive(Debug, Clone)]
+pub struct MapSamplerConfig<const C: usize> {
+    /// Canal del scheduler reservado para la ventana. No debe manejar ninguna
+    /// salida: en la placa es un canal por software (`timer::SOFTWARE_CHANNELS`)
+    pub channel: u8,
+    /// Canal del timer para las conversiones dentro de la ventana (también sin salida)
+    pub sample_channel: u8,
+    /// Tiempo entre conversiones dentro de la ventana (µs)
+    pub sample_interval_us: u32,
+    /// Inicio de la ventana de cada cilindro, en orden de encendido (dominio del predictor)
+    pub window_start_deg: [f32; C],
+    /// Duración de la ventana (°)
+    pub window_deg: f32,
+    pub mode: MapSampleMode,
+    /// Constante de tiempo del respaldo por tiempo, usado antes de la sincronía (µs)
+    pub fallback_tau_us: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    cylinder: usize,
+    sum: f32,
+    min: f32,
+    count: u32,
+}
+
+/// Muestreo de MAP sincronizado con el ángulo de cigüeñal.
+///
+/// En motores chicos el MAP pulsa con cada carrera de admisión y un
+/// muestreo por tiempo produce aliasing. Aquí la ventana de cada cilindro
+/// se programa en el `AngleScheduler` como un evento más: el timer abre la
+/// ventana con `Activate` y la cierra con `Deactivate`. Las conversiones
+/// del ADC las disparan los propios eventos: una al abrir, una al cerrar y
+/// una cada `sample_interval_us` en `sample_channel` mientras está abierta.
+/// Esas lecturas deben ser crudas (sin el filtro del canal periódico) y se
+/// reducen al mínimo o al promedio.
+///
+/// Antes de la sincronía (o si se pierde) la carga sale de un filtro de
+/// primer orden sobre las lecturas periódicas de `add_sample`.
+pub struct MapSampler<const C: usize> {
+    config: MapSamplerConfig<C>,
+    next_cylinder: usize,
+    scheduled: bool,
+    window: Option<Window>,
+    cylinder_kpa: [Option<f32>; C],
+    last_window_kpa: Option<f32>,
+    fallback: LagFilter,
+    fallback_kpa: Option<f32>,
+    last_sample_us: u32,
+    empty_windows: u32,
+}
+
+impl<const C: usize> MapSampler<C> {
+    pub fn new(config: MapSamplerConfig<C>) -> Self {
+        let fallback = LagFilter::new(config.fallback_tau_us);
+        Self {
+            config,
+            next_cylinder: 0,
+            scheduled: false,
+            window: None,
+            cylinder_kpa: [None; C],
+            last_window_kpa: None,
+            fallback,
+            fallback_kpa: None,
+            last_sample_us: 0,
+            empty_windows: 0,
+        }
+    }
+
+    /// Evento de la siguiente ventana
+    pub fn next_window(&self) -> AngleEvent {
+        let start = self.config.window_start_deg[self.next_cylinder];
+        AngleEvent {
+            channel: self.config.channel,
+            start_deg: start,
+            end: EventEnd::AtAngle(start + self.config.window_deg),
+        }
+    }
+
+    /// Programa la siguiente ventana si no hay una pendiente.
+    /// Se llama en cada diente, después de `scheduler.on_tooth`.
+    pub fn schedule<const S: usize, B: TimerBackend>(
+        &mut self,
+        scheduler: &mut AngleScheduler<S>,
+        predictor: &AnglePredictor,
+        backend: &mut B,
+    ) -> Result<Option<ScheduleOutcome>, ScheduleError> {
+        if self.scheduled {
+            return Ok(None);
+        }
+        let outcome = scheduler.schedule(self.next_window(), predictor, backend)?;
+        self.scheduled = true;
+        Ok(Some(outcome))
+    }
+
+    /// Acción del timer en el canal de la ventana (desde la interrupción,
+    /// junto con `scheduler.on_compare`). kpa: conversión cruda hecha en
+    /// ese momento.
+    pub fn on_window<B: TimerBackend>(&mut self, action: CompareAction, kpa: f32, now_us: u32, backend: &mut B) {
+        match action {
+            CompareAction::Activate => {
+                self.window = Some(Window { cylinder: self.next_cylinder, sum: 0.0, min: f32::MAX, count: 0 });
+                self.add_window_sample(kpa);
+                backend.arm(
+                    self.config.sample_channel,
+                    now_us.wrapping_add(self.config.sample_interval_us),
+                    CompareAction::Activate,
+                );
+            }
+            CompareAction::Deactivate => {
+                backend.cancel(self.config.sample_channel);
+                self.add_window_sample(kpa);
+                match self.window.take() {
+                    Some(window) => self.close(window),
+                    // Se perdió la apertura: la ventana no tiene valor
+                    None => self.empty_windows = self.empty_windows.wrapping_add(1),
+                }
+                self.next_cylinder = (self.next_cylinder + 1) % C;
+                self.scheduled = false;
+            }
+        }
+    }
+
+    /// Compare del canal de conversiones (desde la interrupción).
+    /// kpa: conversión cruda hecha en ese momento.
+    pub fn on_sample<B: TimerBackend>(&mut self, kpa: f32, now_us: u32, backend: &mut B) {
+        if self.window.is_none() {
+            return;
+        }
+        self.add_window_sample(kpa);
+        backend.arm(
+            self.config.sample_channel,
+            now_us.wrapping_add(self.config.sample_interval_us),
+            CompareAction::Activate,
+        );
+    }
+
+    fn add_window_sample(&mut self, kpa: f32) {
+        if let Some(window) = self.window.as_mut() {
+            window.sum += kpa;
+            window.min = window.min.min(kpa);
+            window.count += 1;
+        }
+    }
+
+    /// Siempre tiene al menos la conversión del cierre
+    fn close(&mut self, window: Window) {
+        let kpa = match self.config.mode {
+            MapSampleMode::Average => window.sum / window.count as f32,
+            MapSampleMode::Minimum => window.min,
+        };
+        self.cylinder_kpa[window.cylinder] = Some(kpa);
+        self.last_window_kpa = Some(kpa);
+    }
+
+    /// Lectura periódica de MAP (kPa, ya filtrada) para el respaldo por tiempo
+    pub fn add_sample(&mut self, kpa: f32, now_us: u32) {
+        let dt = now_us.wrapping_sub(self.last_sample_us);
+        self.last_sample_us = now_us;
+        self.fallback_kpa = Some(self.fallback.apply(kpa, dt));
+    }
+
+    /// Muestras en la ventana abierta (None si no hay ventana)
+    pub fn window_samples(&self) -> Option<u32> {
+        self.window.map(|w| w.count)
+    }
+
+    /// Pérdida de sincronía: se descartan las ventanas y la carga vuelve al
+    /// respaldo por tiempo (el scheduler se cancela por separado; una
+    /// conversión pendiente sin ventana se ignora)
+    pub fn sync_lost(&mut self) {
+        self.window = None;
+        self.scheduled = false;
+        self.next_cylinder = 0;
+        self.cylinder_kpa = [None; C];
+        self.last_window_kpa = None;
+    }
+
+    /// MAP para la carga: la última ventana con sincronía, o el respaldo por tiempo
+    pub fn load_kpa(&self) -> Option<f32> {
+        self.last_window_kpa.or(self.fallback_kpa)
+    }
+
+    /// true si la carga sale de las ventanas sincronizadas
+    pub fn is_synchronous(&self) -> bool {
+        self.last_window_kpa.is_some()
+    }
+
+    /// Última ventana de un cilindro
+    pub fn cylinder_kpa(&self, cylinder: usize) -> Option<f32> {
+        self.cylinder_kpa.get(cylinder).copied().flatten()
+    }
+
+    /// Respaldo por tiempo
+    pub fn fallback_kpa(&self) -> Option<f32> {
+        self.fallback_kpa
+    }
+
+    /// Ventanas que cerraron sin haberse abierto
+    pub fn empty_windows(&self) -> u32 {
+        self.empty_windows
+    }
+
+    /// Masa de aire del cilindro con `load_kpa` como carga
+    pub fn air_mass_g(&self, model: &SpeedDensity, iat_c: f32, ve_percent: f32) -> Option<f32> {
+        self.load_kpa().map(|kpa| model.calculate_air_mass(kpa, iat_c, ve_percent))
+    }
+}
@@ -0,0 +1,209 @@
+use crate::analog::{Converter, LinearConverter};
+
+/// Relación estequiométrica de la gasolina
+pub const STOICH_AFR_GASOLINE: f32 = 14.7;
+
+/// Configuración de la salida analógica de un controlador de banda ancha
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WidebandConfig {
+    /// Voltaje -> lambda (ej. 0 V = 0.68, 5 V = 1.36 en la mayoría de los controladores)
+    pub lambda: LinearConverter,
+    /// Relación estequiométrica del combustible, para reportar AFR
+    pub stoich_afr: f32,
+    /// Fuera de este rango la salida no es válida (controlador apagado o en error)
+    pub valid_min_volts: f32,
+    pub valid_max_volts: f32,
+    /// Tiempo de calentamiento del sensor desde que arranca el motor (µs)
+    pub warmup_us: u32,
+}
+
+/// Lectura de oxígeno validada
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct O2Reading {
+    pub lambda: f32,
+    pub afr: f32,
+}
+
+/// Sonda de banda ancha leída por la salida analógica del controlador.
+///
+/// Las lecturas solo se entregan con el sensor listo: pasó el tiempo de
+/// calentamiento desde `start` y el voltaje está dentro del rango válido.
+///
+/// # Ejemplo
+///
+/// ```
+/// use engine_core::analog::LinearConverter;
+/// use engine_core::o2::{Wideband, WidebandConfig, STOICH_AFR_GASOLINE};
+///
+/// let mut wideband = Wideband::new(WidebandConfig {
+///     lambda: LinearConverter::new(0.0, 0.68, 5.0, 1.36),
+///     stoich_afr: STOICH_AFR_GASOLINE,
+///     valid_min_volts: 0.05,
+///     valid_max_volts: 4.95,
+///     warmup_us: 10_000_000,
+/// });
+/// wideband.start(0);
+///
+/// // Calentando
+/// assert_eq!(wideband.update(2.5, 1_000_000), None);
+///
+/// let reading = wideband.update(2.5, 10_000_000).unwrap();
+/// assert!((reading.lambda - 1.02).abs() < 1e-4);
+/// ```
+pub struct Wideband {
+    config: WidebandConfig,
+    started_us: Option<u32>,
+    reading: Option<O2Reading>,
+}
+
+impl Wideband {
+    pub fn new(config: WidebandConfig) -> Self {
+        Self { config, started_us: None, reading: None }
+    }
+
+    /// Inicio del calentamiento (arranque del motor)
+    pub fn start(&mut self, now_us: u32) {
+        self.started_us = Some(now_us);
+    }
+
+    /// Motor apagado: el sensor deja de estar listo
+    pub fn stop(&mut self) {
+        self.started_us = None;
+        self.reading = None;
+    }
+
+    /// true si ya pasó el calentamiento
+    pub fn is_warm(&self, now_us: u32) -> bool {
+        self.started_us.is_some_and(|t| now_us.wrapping_sub(t) >= self.config.warmup_us)
+    }
+
+    /// Procesa un voltaje; regresa la lectura si el sensor está listo
+    pub fn update(&mut self, volts: f32, now_us: u32) -> Option<O2Reading> {
+        let valid = volts >= self.config.valid_min_volts && volts <= self.config.valid_max_volts;
+        self.reading = if valid && self.is_warm(now_us) {
+            let lambda = self.config.lambda.convert(volts);
+            Some(O2Reading { lambda, afr: lambda * self.config.stoich_afr })
+        } else {
+            None
+        };
+        self.reading
+    }
+
+    /// Última lectura válida (None si el sensor no está listo)
+    pub fn reading(&self) -> Option<O2Reading> {
+        self.reading
+    }
+}
+
+/// Configuración de una sonda de banda angosta (0-1 V, conmutación)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NarrowbandConfig {
+    /// Umbral rico/pobre (típico 0.45 V)
+    pub threshold_volts: f32,
+    /// Histéresis alrededor del umbral
+    pub hysteresis_volts: f32,
+    /// Fuera de este rango la lectura no es válida (circuito abierto o en corto)
+    pub valid_min_volts: f32,
+    pub valid_max_volts: f32,
+    pub warmup_us: u32,
+    /// Sin conmutar durante este tiempo la sonda se considera inactiva (µs)
+    pub switch_timeout_us: u32,
+}
+
+/// Estado de la mezcla visto por la sonda
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MixtureState {
+    Rich,
+    Lean,
+}
+
+/// Sonda de banda angosta: detecta rico/pobre y sus conmutaciones.
+///
+/// Una sonda fría entrega cerca de 0.45 V sin conmutar, así que además del
+/// calentamiento se exige que la sonda esté conmutando para darla por lista.
+pub struct Narrowband {
+    config: NarrowbandConfig,
+    started_us: Option<u32>,
+    state: Option<MixtureState>,
+    last_switch_us: Option<u32>,
+    switch_period_us: Option<u32>,
+    switches: u32,
+    valid: bool,
+}
+
+impl Narrowband {
+    pub fn new(config: NarrowbandConfig) -> Self {
+        Self {
+            config,
+            started_us: None,
+            state: None,
+            last_switch_us: None,
+            switch_period_us: None,
+            switches: 0,
+            valid: false,
+        }
+    }
+
+    /// Inicio del calentamiento (arranque del motor)
+    pub fn start(&mut self, now_us: u32) {
+        self.started_us = Some(now_us);
+    }
+
+    /// Motor apagado: se olvida la historia de conmutación
+    pub fn stop(&mut self) {
+        *self = Self::new(self.config);
+    }
+
+    /// Procesa un voltaje; regresa el estado de la mezcla si la lectura es válida
+    pub fn update(&mut self, volts: f32, now_us: u32) -> Option<MixtureState> {
+        let c = &self.config;
+        self.valid = volts >= c.valid_min_volts && volts <= c.valid_max_volts;
+        if !self.valid {
+            return None;
+        }
+
+        let half = c.hysteresis_volts / 2.0;
+        let next = match self.state {
+            Some(MixtureState::Rich) if volts < c.threshold_volts - half => MixtureState::Lean,
+            Some(MixtureState::Lean) if volts > c.threshold_volts + half => MixtureState::Rich,
+            Some(state) => state,
+            None if volts > c.threshold_volts => MixtureState::Rich,
+            None => MixtureState::Lean,
+        };
+
+        if self.state.is_some_and(|s| s != next) {
+            if let Some(last) = self.last_switch_us {
+                self.switch_period_us = Some(now_us.wrapping_sub(last));
+            }
+            self.last_switch_us = Some(now_us);
+            self.switches = self.switches.wrapping_add(1);
+        }
+        self.state = Some(next);
+        self.state
+    }
+
+    /// true si la sonda conmutó dentro del tiempo límite
+    pub fn is_switching(&self, now_us: u32) -> bool {
+        self.last_switch_us.is_some_and(|t| now_us.wrapping_sub(t) < self.config.switch_timeout_us)
+    }
+
+    /// Lista para lazo cerrado: caliente, con lectura válida y conmutando
+    pub fn is_ready(&self, now_us: u32) -> bool {
+        let warm = self.started_us.is_some_and(|t| now_us.wrapping_sub(t) >= self.config.warmup_us);
+        warm && self.valid && self.is_switching(now_us)
+    }
+
+    pub fn state(&self) -> Option<MixtureState> {
+        self.state
+    }
+
+    /// Tiempo entre las dos últimas conmutaciones (µs)
+    pub fn switch_period_us(&self) -> Option<u32> {
+        self.switch_period_us
+    }
+
+    /// Conmutaciones desde el arranque
+    pub fn switches(&self) -> u32 {
+        self.switches
+    }
+}
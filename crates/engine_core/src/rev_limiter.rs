@@ -0,0 +1,243 @@
+use crate::tables::Table2D;
+
+/// Qué se corta cuando el limitador está activo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CutMode {
+    Fuel,
+    Spark,
+    FuelAndSpark,
+}
+
+/// Cómo se reparten los eventos cortados entre cilindros
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CutPatternKind {
+    /// Se cortan k eventos seguidos por ciclo y el inicio rota cada ciclo
+    Rotating,
+    /// Se decide cada evento con un generador pseudo-aleatorio (LFSR de 16 bits)
+    Random,
+}
+
+/// Decisión de corte para el siguiente evento de combustión
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CutDecision {
+    pub fuel: bool,
+    pub spark: bool,
+}
+
+impl CutDecision {
+    pub const NONE: CutDecision = CutDecision { fuel: false, spark: false };
+
+    pub fn from_mode(mode: CutMode) -> Self {
+        match mode {
+            CutMode::Fuel => CutDecision { fuel: true, spark: false },
+            CutMode::Spark => CutDecision { fuel: false, spark: true },
+            CutMode::FuelAndSpark => CutDecision { fuel: true, spark: true },
+        }
+    }
+
+    pub fn any(&self) -> bool {
+        self.fuel || self.spark
+    }
+}
+
+/// Generador del patrón de corte parcial.
+/// Se consulta una vez por evento de combustión (en orden de encendido)
+/// para que el corte quede repartido entre todos los cilindros.
+#[derive(Debug, Clone)]
+pub struct CutPattern {
+    kind: CutPatternKind,
+    cylinders: u8,
+    // Posición del evento dentro del ciclo actual
+    position: u8,
+    // Primer evento cortado del ciclo (patrón rotativo)
+    start: u8,
+    // Estado del LFSR (patrón aleatorio), nunca debe ser 0
+    lfsr: u16,
+}
+
+impl CutPattern {
+    pub fn new(kind: CutPatternKind, cylinders: u8) -> Self {
+        Self {
+            kind,
+            cylinders: cylinders.max(1),
+            position: 0,
+            start: 0,
+            lfsr: 0xACE1,
+        }
+    }
+
+    /// Decide si el siguiente evento se corta.
+    /// fraction: porción de eventos a cortar (0.0 = ninguno, 1.0 = todos)
+    pub fn should_cut(&mut self, fraction: f32) -> bool {
+        if fraction <= 0.0 {
+            self.advance(0);
+            return false;
+        }
+        if fraction >= 1.0 {
+            self.advance(self.cylinders);
+            return true;
+        }
+
+        match self.kind {
+            CutPatternKind::Rotating => {
+                // k eventos cortados por ciclo, redondeado al más cercano (mínimo 1)
+                let n = self.cylinders;
+                let k = ((fraction * n as f32) + 0.5) as u8;
+                let k = k.clamp(1, n);
+
+                let offset = (self.position + n - self.start) % n;
+                let cut = offset < k;
+                self.advance(k);
+                cut
+            }
+            CutPatternKind::Random => {
+                let value = self.next_random();
+                self.advance(0);
+                (value as f32 / 65536.0) < fraction
+            }
+        }
+    }
+
+    /// Avanza al siguiente evento. Al cerrar el ciclo se rota el inicio k posiciones.
+    fn advance(&mut self, k: u8) {
+        self.position += 1;
+        if self.position >= self.cylinders {
+            self.position = 0;
+            self.start = (self.start + k) % self.cylinders;
+        }
+    }
+
+    /// LFSR de Galois de 16 bits (polinomio 0xB400), periodo 65535
+    fn next_random(&mut self) -> u16 {
+        let lsb = self.lfsr & 1;
+        self.lfsr >>= 1;
+        if lsb != 0 {
+            self.lfsr ^= 0xB400;
+        }
+        self.lfsr
+    }
+}
+
+/// Etapa del limitador
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimiterState {
+    Inactive,
+    /// Límite suave: retardo progresivo de chispa, sin corte
+    Soft,
+    /// Límite duro: corte de combustible y/o chispa
+    Hard,
+}
+
+/// Configuración del limitador de RPM.
+/// N: puntos de la curva de límite vs temperatura de refrigerante
+#[derive(Debug, Clone)]
+pub struct RevLimiterConfig<const N: usize> {
+    /// Límite duro (RPM) vs temperatura de refrigerante (°C).
+    /// Permite un límite más bajo con el motor frío.
+    pub hard_limit_rpm: Table2D<N>,
+    /// El límite suave empieza esta cantidad de RPM debajo del duro
+    pub soft_window_rpm: f32,
+    /// Retardo alcanzado al llegar al límite duro (grados)
+    pub max_retard_deg: f32,
+    /// Fracción de corte al entrar al límite duro (0.0 a 1.0)
+    pub min_cut_fraction: f32,
+    /// RPM sobre el límite duro en las que el corte llega al 100%
+    pub cut_ramp_rpm: f32,
+    /// Histéresis de salida de cada etapa (RPM)
+    pub hysteresis_rpm: f32,
+    pub cut_mode: CutMode,
+    pub pattern: CutPatternKind,
+}
+
+/// Estado del limitador para logs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RevLimiterStatus {
+    pub state: LimiterState,
+    /// Límite duro vigente (ya corregido por temperatura)
+    pub hard_limit_rpm: f32,
+    /// Retardo de chispa aplicado (grados)
+    pub retard_deg: f32,
+    /// Porción de eventos cortados
+    pub cut_fraction: f32,
+    /// Eventos cortados desde el arranque
+    pub cut_events: u32,
+}
+
+/// Limitador de RPM de dos etapas
+pub struct RevLimiter<const N: usize> {
+    config: RevLimiterConfig<N>,
+    pattern: CutPattern,
+    status: RevLimiterStatus,
+}
+
+impl<const N: usize> RevLimiter<N> {
+    pub fn new(config: RevLimiterConfig<N>, cylinders: u8) -> Self {
+        let pattern = CutPattern::new(config.pattern, cylinders);
+        Self {
+            config,
+            pattern,
+            status: RevLimiterStatus {
+                state: LimiterState::Inactive,
+                hard_limit_rpm: 0.0,
+                retard_deg: 0.0,
+                cut_fraction: 0.0,
+                cut_events: 0,
+            },
+        }
+    }
+
+    /// Actualiza la etapa del limitador. Se llama en cada ciclo de cálculo.
+    /// rpm: RPM actuales
+    /// clt_c: Temperatura de refrigerante (°C)
+    pub fn update(&mut self, rpm: f32, clt_c: f32) -> RevLimiterStatus {
+        let hard = self.config.hard_limit_rpm.interpolate(clt_c);
+        let soft = hard - self.config.soft_window_rpm;
+        let hyst = self.config.hysteresis_rpm;
+
+        // Transiciones con histéresis: se entra al cruzar el límite
+        // y se sale hasta bajar "hyst" RPM debajo de él
+        let state = match self.status.state {
+            _ if rpm >= hard => LimiterState::Hard,
+            LimiterState::Hard if rpm >= hard - hyst => LimiterState::Hard,
+            _ if rpm >= soft => LimiterState::Soft,
+            LimiterState::Hard | LimiterState::Soft if rpm >= soft - hyst => LimiterState::Soft,
+            _ => LimiterState::Inactive,
+        };
+
+        let (retard_deg, cut_fraction) = match state {
+            LimiterState::Inactive => (0.0, 0.0),
+            LimiterState::Soft => {
+                let window = self.config.soft_window_rpm.max(1.0);
+                let factor = ((rpm - soft) / window).clamp(0.0, 1.0);
+                (self.config.max_retard_deg * factor, 0.0)
+            }
+            LimiterState::Hard => {
+                let ramp = self.config.cut_ramp_rpm.max(1.0);
+                let factor = ((rpm - hard) / ramp).clamp(0.0, 1.0);
+                let min_cut = self.config.min_cut_fraction.clamp(0.0, 1.0);
+                (self.config.max_retard_deg, min_cut + (1.0 - min_cut) * factor)
+            }
+        };
+
+        self.status.state = state;
+        self.status.hard_limit_rpm = hard;
+        self.status.retard_deg = retard_deg;
+        self.status.cut_fraction = cut_fraction;
+        self.status
+    }
+
+    /// Decide el corte del siguiente evento de combustión (en orden de encendido)
+    pub fn next_cut(&mut self) -> CutDecision {
+        if self.pattern.should_cut(self.status.cut_fraction) {
+            self.status.cut_events = self.status.cut_events.wrapping_add(1);
+            CutDecision::from_mode(self.config.cut_mode)
+        } else {
+            CutDecision::NONE
+        }
+    }
+
+    /// Estado actual para logs
+    pub fn status(&self) -> RevLimiterStatus {
+        self.status
+    }
+}
@@ -0,0 +1,282 @@
+use crate::angle_predictor::AnglePredictor;
+// El timer de salidas vive en ecu_traits; se re-exporta para los usuarios del scheduler
+pub use ecu_traits::timer::{CompareAction, TimerBackend};
+
+/// Fin de un evento angular
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventEnd {
+    /// Duración fija en µs (pulso de inyección)
+    AfterUs(u32),
+    /// Ángulo de fin (ej. chispa); puede quedar del otro lado de 0°
+    AtAngle(f32),
+}
+
+/// Evento de salida programado en el dominio angular
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AngleEvent {
+    pub channel: u8,
+    /// Ángulo de inicio en el dominio del predictor (0..360 o 0..720°)
+    pub start_deg: f32,
+    pub end: EventEnd,
+}
+
+impl AngleEvent {
+    /// Abrir el inyector en `start_deg` y cerrarlo después de `pulse_us`
+    pub fn injection(channel: u8, start_deg: f32, pulse_us: u32) -> Self {
+        Self { channel, start_deg, end: EventEnd::AfterUs(pulse_us) }
+    }
+
+    /// Iniciar el dwell en `dwell_start_deg` y disparar en `fire_deg`
+    pub fn spark(channel: u8, dwell_start_deg: f32, fire_deg: f32) -> Self {
+        Self { channel, start_deg: dwell_start_deg, end: EventEnd::AtAngle(fire_deg) }
+    }
+}
+
+/// Resultado de programar un evento
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleOutcome {
+    /// Se programó completo
+    OnTime,
+    /// El inicio ya había pasado: la salida se activa de inmediato y el evento se acorta
+    Truncated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// Todavía no hay velocidad medida
+    NoSpeed,
+    /// No quedan lugares libres en el scheduler
+    NoFreeSlot,
+    /// El canal ya tiene un evento en curso
+    ChannelBusy,
+    /// El evento ya terminó antes de poder programarse: se descarta
+    Late,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlotState {
+    /// Inicio programado en el timer
+    Waiting,
+    /// Salida activa, fin programado en el timer
+    Active,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    event: AngleEvent,
+    state: SlotState,
+    start_us: u32,
+    end_us: u32,
+}
+
+/// Scheduler de eventos en el dominio angular.
+/// S: número máximo de eventos simultáneos
+///
+/// Convierte ángulos a marcas de tiempo con el predictor y las vuelve a
+/// calcular en cada diente, así los eventos que cruzan varios dientes (o
+/// el hueco de la rueda) se corrigen con la velocidad más reciente. El
+/// timer llama a `on_compare` al ejecutar cada acción para programar el fin.
+///
+/// Un ángulo que quedó hasta `late_window_deg` detrás del último diente se
+/// toma como tarde (ya pasó) y no como un evento del siguiente ciclo.
+pub struct AngleScheduler<const S: usize> {
+    slots: [Option<Slot>; S],
+    late_window_deg: f32,
+    late_drops: u32,
+    truncated: u32,
+}
+
+impl<const S: usize> AngleScheduler<S> {
+    pub fn new(late_window_deg: f32) -> Self {
+        Self {
+            slots: [None; S],
+            late_window_deg,
+            late_drops: 0,
+            truncated: 0,
+        }
+    }
+
+    /// Programa un evento de una sola vez (se vuelve a pedir cada ciclo)
+    pub fn schedule<B: TimerBackend>(
+        &mut self,
+        event: AngleEvent,
+        predictor: &AnglePredictor,
+        backend: &mut B,
+    ) -> Result<ScheduleOutcome, ScheduleError> {
+        if !predictor.is_valid() {
+            return Err(ScheduleError::NoSpeed);
+        }
+        if self.slots.iter().flatten().any(|s| s.event.channel == event.channel) {
+            return Err(ScheduleError::ChannelBusy);
+        }
+        let free = self.slots.iter().position(|s| s.is_none()).ok_or(ScheduleError::NoFreeSlot)?;
+
+        let (start_us, end_us) = self.event_times(&event, predictor);
+        let now = backend.now_us();
+
+        if is_past(end_us, now) {
+            self.late_drops = self.late_drops.wrapping_add(1);
+            return Err(ScheduleError::Late);
+        }
+
+        let outcome = if is_past(start_us, now) {
+            self.truncated = self.truncated.wrapping_add(1);
+            backend.arm(event.channel, now, CompareAction::Activate);
+            ScheduleOutcome::Truncated
+        } else {
+            backend.arm(event.channel, start_us, CompareAction::Activate);
+            ScheduleOutcome::OnTime
+        };
+
+        self.slots[free] = Some(Slot { event, state: SlotState::Waiting, start_us, end_us });
+        Ok(outcome)
+    }
+
+    /// Se llama en cada diente (después de actualizar el predictor) para
+    /// corregir los tiempos de los eventos pendientes
+    pub fn on_tooth<B: TimerBackend>(&mut self, predictor: &AnglePredictor, backend: &mut B) {
+        if !predictor.is_valid() {
+            return;
+        }
+        let now = backend.now_us();
+
+        for i in 0..S {
+            let Some(mut slot) = self.slots[i] else { continue };
+            let (start_us, end_us) = self.event_times(&slot.event, predictor);
+
+            match slot.state {
+                SlotState::Waiting => {
+                    slot.start_us = start_us;
+                    slot.end_us = end_us;
+                    let at = if is_past(start_us, now) { now } else { start_us };
+                    backend.arm(slot.event.channel, at, CompareAction::Activate);
+                }
+                SlotState::Active => {
+                    // Una duración fija ya quedó definida al activar; un fin angular se corrige.
+                    // El inicio ya pasó, así que el fin se mide desde el diente y no
+                    // desde el inicio (que puede haber quedado fuera de la ventana de atraso)
+                    if let EventEnd::AtAngle(end_deg) = slot.event.end {
+                        let end_us = self.angle_time(end_deg, predictor);
+                        slot.end_us = end_us;
+                        let at = if is_past(end_us, now) { now } else { end_us };
+                        backend.arm(slot.event.channel, at, CompareAction::Deactivate);
+                    }
+                }
+            }
+            self.slots[i] = Some(slot);
+        }
+    }
+
+    /// Se llama desde la interrupción del timer cuando se ejecutó la acción del canal
+    pub fn on_compare<B: TimerBackend>(&mut self, channel: u8, backend: &mut B) {
+        let Some(index) = self.slot_index(channel) else { return };
+        let Some(mut slot) = self.slots[index] else { return };
+
+        match slot.state {
+            SlotState::Waiting => {
+                let now = backend.now_us();
+                if let EventEnd::AfterUs(_) = slot.event.end {
+                    // Si se activó tarde el pulso se acorta, el cierre no se mueve
+                    if is_past(slot.end_us, now) {
+                        slot.end_us = now;
+                    }
+                }
+                slot.state = SlotState::Active;
+                backend.arm(channel, slot.end_us, CompareAction::Deactivate);
+                self.slots[index] = Some(slot);
+            }
+            SlotState::Active => {
+                self.slots[index] = None;
+            }
+        }
+    }
+
+    /// Cancela todo (ej. pérdida de sincronía). Las salidas activas se desactivan.
+    pub fn cancel_all<B: TimerBackend>(&mut self, backend: &mut B) {
+        for slot in self.slots.iter_mut() {
+            if let Some(s) = slot.take() {
+                backend.cancel(s.event.channel);
+                if s.state == SlotState::Active {
+                    let now = backend.now_us();
+                    backend.arm(s.event.channel, now, CompareAction::Deactivate);
+                }
+            }
+        }
+    }
+
+    /// Eventos en curso (esperando inicio o activos)
+    pub fn pending(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+
+    /// Eventos descartados por llegar tarde
+    pub fn late_drops(&self) -> u32 {
+        self.late_drops
+    }
+
+    /// Eventos acortados por iniciar tarde
+    pub fn truncated(&self) -> u32 {
+        self.truncated
+    }
+
+    fn slot_index(&self, channel: u8) -> Option<usize> {
+        self.slots.iter().position(|s| matches!(s, Some(s) if s.event.channel == channel))
+    }
+
+    /// Ángulo visto desde el último diente; lo que quedó dentro de la ventana
+    /// de atraso es negativo (ya pasó) en lugar de un ciclo adelante
+    fn delta_from_tooth(&self, angle_deg: f32, predictor: &AnglePredictor) -> f32 {
+        let cycle = predictor.cycle_deg();
+        let (_, last_deg) = predictor.last_tooth();
+        let delta = wrap(angle_deg - last_deg, cycle);
+        if delta > cycle - self.late_window_deg { delta - cycle } else { delta }
+    }
+
+    /// Marca de tiempo de un ángulo visto desde el último diente
+    fn angle_time(&self, angle_deg: f32, predictor: &AnglePredictor) -> u32 {
+        let (last_us, _) = predictor.last_tooth();
+        last_us.wrapping_add_signed(time_for(predictor, self.delta_from_tooth(angle_deg, predictor)))
+    }
+
+    /// Marcas de tiempo de inicio y fin del evento vistas desde el último diente
+    fn event_times(&self, event: &AngleEvent, predictor: &AnglePredictor) -> (u32, u32) {
+        let cycle = predictor.cycle_deg();
+        let (last_us, _) = predictor.last_tooth();
+
+        let start_delta = self.delta_from_tooth(event.start_deg, predictor);
+        let start_us = last_us.wrapping_add_signed(time_for(predictor, start_delta));
+
+        let end_us = match event.end {
+            EventEnd::AfterUs(pulse) => start_us.wrapping_add(pulse),
+            EventEnd::AtAngle(end_deg) => {
+                let span = wrap(end_deg - event.start_deg, cycle);
+                last_us.wrapping_add_signed(time_for(predictor, start_delta + span))
+            }
+        };
+        (start_us, end_us)
+    }
+}
+
+/// µs (con signo) desde el último diente hasta `delta_deg`
+fn time_for(predictor: &AnglePredictor, delta_deg: f32) -> i32 {
+    let t = if delta_deg < 0.0 {
+        delta_deg / predictor.deg_per_us()
+    } else {
+        predictor.time_for_delta_deg(delta_deg).unwrap_or(0.0)
+    };
+    if t < 0.0 { (t - 0.5) as i32 } else { (t + 0.5) as i32 }
+}
+
+/// true si `at_us` es anterior o igual a `now_us` (aritmética con vuelta)
+fn is_past(at_us: u32, now_us: u32) -> bool {
+    (at_us.wrapping_sub(now_us) as i32) <= 0
+}
+
+/// Normaliza a 0..cycle
+fn wrap(angle: f32, cycle: f32) -> f32 {
+    let mut a = angle % cycle;
+    if a < 0.0 {
+        a += cycle;
+    }
+    a
+}
@@ -0,0 +1,249 @@
+use ecu_traits::engine_io::{AnalogInput, AnalogSensor};
+use crate::analog::{CalibratedSensor, Converter};
+use crate::diagnostics::{DiagnosticLog, FaultCode, FaultKind, SensorId};
+use crate::filters::FilteredInput;
+
+/// Rango eléctrico válido de un sensor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeCheckConfig {
+    /// Debajo de este voltaje la lectura es falla de rango bajo
+    pub min_volts: f32,
+    /// Arriba de este voltaje la lectura es falla de rango alto
+    pub max_volts: f32,
+    /// Tiempo que una condición debe mantenerse para activar o quitar la falla (µs)
+    pub debounce_us: u32,
+}
+
+impl RangeCheckConfig {
+    pub const fn new(min_volts: f32, max_volts: f32, debounce_us: u32) -> Self {
+        Self { min_volts, max_volts, debounce_us }
+    }
+
+    /// Clasifica un voltaje
+    pub fn classify(&self, volts: f32) -> Option<FaultKind> {
+        if volts < self.min_volts {
+            Some(FaultKind::RangeLow)
+        } else if volts > self.max_volts {
+            Some(FaultKind::RangeHigh)
+        } else {
+            None
+        }
+    }
+}
+
+/// Valor que se usa mientras el sensor está en falla
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fallback {
+    /// Valor fijo (ej. 80 °C para CTS)
+    Fixed(f32),
+    /// Última lectura válida; `default` si nunca hubo una
+    LastGood { default: f32 },
+    /// Valor calculado por otro modelo (ej. MAP estimado con TPS/RPM) que se
+    /// pasa en cada `update`; `default` si no se tiene
+    Modelled { default: f32 },
+}
+
+/// Cambio en el estado de falla confirmado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultTransition {
+    Set(FaultKind),
+    Cleared(FaultKind),
+}
+
+/// Filtro de tiempo (debounce) de las fallas de un sensor
+#[derive(Debug, Clone)]
+pub struct RangeMonitor {
+    debounce_us: u32,
+    fault: Option<FaultKind>,
+    /// Condición observada distinta de la confirmada, y desde cuándo
+    pending: Option<(Option<FaultKind>, u32)>,
+}
+
+impl RangeMonitor {
+    pub fn new(debounce_us: u32) -> Self {
+        Self { debounce_us, fault: None, pending: None }
+    }
+
+    /// Falla confirmada
+    pub fn fault(&self) -> Option<FaultKind> {
+        self.fault
+    }
+
+    /// true si la condición observada todavía no se confirma
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Procesa la condición observada en una lectura
+    pub fn observe(&mut self, observed: Option<FaultKind>, now_us: u32) -> Option<FaultTransition> {
+        if observed == self.fault {
+            self.pending = None;
+            return None;
+        }
+
+        let since = match self.pending {
+            Some((condition, since)) if condition == observed => since,
+            _ => {
+                self.pending = Some((observed, now_us));
+                now_us
+            }
+        };
+        if now_us.wrapping_sub(since) < self.debounce_us {
+            return None;
+        }
+
+        self.pending = None;
+        let previous = core::mem::replace(&mut self.fault, observed);
+        match (previous, observed) {
+            (_, Some(kind)) => Some(FaultTransition::Set(kind)),
+            (Some(kind), None) => Some(FaultTransition::Cleared(kind)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Sensor calibrado con diagnóstico de rango y valor de respaldo.
+///
+/// Un cable roto lleva el voltaje al tope y sin diagnóstico se convierte en
+/// un valor absurdo (ej. CTS en -40 °C, que sobre-enriquece la mezcla).
+/// Aquí cada lectura se compara con el rango eléctrico; mientras una
+/// condición nueva no se confirma se mantiene la última lectura válida, y
+/// con la falla confirmada se entrega el valor de respaldo y se reporta el
+/// código al registro de diagnóstico.
+///
+/// El canal siempre es un `FilteredInput` (`FilterConfig::none` si no se
+/// filtra) y el rango se revisa sobre su voltaje crudo: un filtro lento
+/// tardaría segundos en llevar el voltaje fuera de rango mientras la
+/// conversión ya entrega valores absurdos. El filtro solo se reinicia al
+/// confirmarse o quitarse la falla; un pico aislado no toca su historia.
+///
+/// # Ejemplo
+///
+/// ```
+/// use ecu_traits::mock::MockAnalogInput;
+/// use engine_core::analog::{CalibratedSensor, Thermistor, GM_THERMISTOR};
+/// use engine_core::diagnostics::{DiagnosticLog, FaultCode, FaultKind, SensorId};
+/// use engine_core::filters::{FilterConfig, FilteredInput};
+/// use engine_core::sensor_fault::{Fallback, MonitoredSensor, RangeCheckConfig};
+///
+/// let input = FilteredInput::new(MockAnalogInput::new([1.0, 5.0]), FilterConfig::none(10_000));
+/// let thermistor = Thermistor::new(GM_THERMISTOR, 2490.0, 5.0);
+/// let mut cts = MonitoredSensor::new(
+///     CalibratedSensor::new(input, thermistor),
+///     SensorId::Cts,
+///     RangeCheckConfig::new(0.1, 4.9, 0),
+///     Fallback::Fixed(80.0),
+/// );
+/// let mut diag: DiagnosticLog<4> = DiagnosticLog::new();
+///
+/// assert!(cts.update(0, None, &mut diag) > 50.0);
+///
+/// // Cable roto: 5 V
+/// assert_eq!(cts.update(1000, None, &mut diag), 80.0);
+/// assert!(diag.is_active(FaultCode::new(SensorId::Cts, FaultKind::RangeHigh)));
+/// ```
+pub struct MonitoredSensor<I: AnalogInput, C: Converter> {
+    sensor: CalibratedSensor<FilteredInput<I>, C>,
+    id: SensorId,
+    range: RangeCheckConfig,
+    monitor: RangeMonitor,
+    fallback: Fallback,
+    last_good: Option<f32>,
+    value: f32,
+}
+
+impl<I: AnalogInput, C: Converter> MonitoredSensor<I, C> {
+    pub fn new(sensor: CalibratedSensor<FilteredInput<I>, C>, id: SensorId, range: RangeCheckConfig, fallback: Fallback) -> Self {
+        let value = match fallback {
+            Fallback::Fixed(v) | Fallback::LastGood { default: v } | Fallback::Modelled { default: v } => v,
+        };
+        Self {
+            sensor,
+            id,
+            range,
+            monitor: RangeMonitor::new(range.debounce_us),
+            fallback,
+            last_good: None,
+            value,
+        }
+    }
+
+    /// Lee el sensor, actualiza el diagnóstico y regresa el valor a usar.
+    /// modelled: valor estimado para `Fallback::Modelled` (si se tiene)
+    pub fn update<const N: usize>(
+        &mut self,
+        now_us: u32,
+        modelled: Option<f32>,
+        diag: &mut DiagnosticLog<N>,
+    ) -> f32 {
+        let (observed, reading) = match self.sensor.read() {
+            Ok(value) => {
+                let volts = self.sensor.input().raw_volts().unwrap_or(self.sensor.last_volts());
+                (self.range.classify(volts), Some(value))
+            }
+            Err(_) => (Some(FaultKind::ReadError), None),
+        };
+
+        let transition = self.monitor.observe(observed, now_us);
+        if transition.is_some() {
+            // Lo filtrado durante la falla (o antes de ella) no es del sensor:
+            // la siguiente lectura arranca desde el voltaje crudo
+            self.sensor.input_mut().chain_mut().reset();
+        }
+        match transition {
+            Some(FaultTransition::Set(kind)) => {
+                // Una falla que cambia de tipo quita el código anterior
+                for previous in [FaultKind::RangeLow, FaultKind::RangeHigh, FaultKind::ReadError] {
+                    if previous != kind {
+                        diag.report(FaultCode::new(self.id, previous), false, now_us);
+                    }
+                }
+                diag.report(FaultCode::new(self.id, kind), true, now_us);
+            }
+            Some(FaultTransition::Cleared(kind)) => diag.report(FaultCode::new(self.id, kind), false, now_us),
+            None => {}
+        }
+
+        self.value = match (self.monitor.fault(), observed, reading) {
+            (None, None, Some(value)) => {
+                self.last_good = Some(value);
+                value
+            }
+            // Condición nueva sin confirmar: se sostiene la última lectura válida
+            (None, _, _) => self.last_good.unwrap_or_else(|| self.fallback_value(modelled)),
+            // En falla (aunque la lectura ya sea válida, hasta que se confirme)
+            (Some(_), _, _) => self.fallback_value(modelled),
+        };
+        self.value
+    }
+
+    fn fallback_value(&self, modelled: Option<f32>) -> f32 {
+        match self.fallback {
+            Fallback::Fixed(value) => value,
+            Fallback::LastGood { default } => self.last_good.unwrap_or(default),
+            Fallback::Modelled { default } => modelled.unwrap_or(default),
+        }
+    }
+
+    /// Último valor entregado por `update`
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Falla confirmada
+    pub fn fault(&self) -> Option<FaultCode> {
+        self.monitor.fault().map(|kind| FaultCode::new(self.id, kind))
+    }
+
+    pub fn id(&self) -> SensorId {
+        self.id
+    }
+
+    pub fn sensor(&self) -> &CalibratedSensor<FilteredInput<I>, C> {
+        &self.sensor
+    }
+
+    pub fn sensor_mut(&mut self) -> &mut CalibratedSensor<FilteredInput<I>, C> {
+        &mut self.sensor
+    }
+}
@@ -0,0 +1,360 @@
+use ecu_traits::engine_io::CapturedEdge;
+use crate::trigger_patterns::TriggerPattern;
+
+/// Debajo de estas RPM el generador avanza como si girara a esta velocidad
+/// (permite rampas que arrancan en 0)
+const MIN_RPM: f32 = 10.0;
+
+/// Señal a la que pertenece un flanco generado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerSignal {
+    Crank,
+    Cam,
+}
+
+/// Flanco de la línea de tiempo generada
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratedEdge {
+    pub timestamp_us: u32,
+    pub signal: TriggerSignal,
+    pub rising: bool,
+    /// Diente real del patrón (cigüeñal) o índice del diente de leva.
+    /// None en los flancos de ruido.
+    pub tooth: Option<u16>,
+}
+
+impl GeneratedEdge {
+    /// El flanco como lo entregaría la captura por hardware
+    pub fn captured(&self) -> CapturedEdge {
+        CapturedEdge { timestamp_us: self.timestamp_us, rising: self.rising }
+    }
+}
+
+/// Defectos que se agregan a la señal. 0 = desactivado.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SignalNoise {
+    /// Variación aleatoria (±µs) de cada marca de tiempo. Si es mayor que
+    /// la separación entre flancos puede desordenarlos.
+    pub jitter_us: u32,
+    /// Cada cuántos dientes de cigüeñal se agrega un pulso falso (rebote)
+    /// al 10% del periodo siguiente
+    pub glitch_every: u32,
+    /// Cada cuántos dientes de cigüeñal se pierde uno
+    pub drop_every: u32,
+    /// Semilla del generador pseudoaleatorio del jitter
+    pub seed: u32,
+}
+
+/// Configuración del generador
+/// K: dientes de leva por ciclo de 720° (0 = sin señal de leva)
+#[derive(Debug, Clone)]
+pub struct SignalGenConfig<const K: usize> {
+    /// Marca de tiempo del inicio de la generación
+    pub start_us: u32,
+    /// Ángulo de ciclo (0..720°) en el que arranca el motor
+    pub start_angle_deg: f32,
+    /// RPM iniciales
+    pub rpm: f32,
+    /// Ángulos de ciclo (ascendentes) de los dientes de leva, igual que en `CamConfig`
+    pub cam_teeth_deg: [f32; K],
+    /// Ancho de cada diente de leva en grados de cigüeñal
+    pub cam_tooth_width_deg: f32,
+    pub noise: SignalNoise,
+}
+
+/// Generador sintético de las señales de cigüeñal y leva.
+///
+/// Produce la línea de tiempo de flancos de cualquier `TriggerPattern` con
+/// un perfil de RPM (constantes o rampas encadenadas) y los defectos de
+/// `SignalNoise`. Con patrones de un solo flanco cada diente es un pulso que
+/// sube en el ángulo del diente y baja a la mitad del hueco siguiente; con
+/// patrones de ambos flancos los dientes pares son subidas y los impares bajadas.
+///
+/// # Ejemplo
+///
+/// ```
+/// use engine_core::crank_decoder::{CrankDecoder, MissingToothWheel, SyncState};
+/// use engine_core::signal_gen::{SignalGenConfig, SignalGenerator, SignalNoise, TriggerSignal};
+///
+/// let wheel = MissingToothWheel::new(36, 1);
+/// let config = SignalGenConfig {
+///     start_us: 0,
+///     start_angle_deg: 0.0,
+///     rpm: 1000.0,
+///     cam_teeth_deg: [],
+///     cam_tooth_width_deg: 0.0,
+///     noise: SignalNoise::default(),
+/// };
+/// let mut generator = SignalGenerator::new(wheel, config);
+/// let mut decoder = CrankDecoder::new(wheel, 0.0);
+///
+/// for edge in generator.by_ref().take(200) {
+///     if edge.signal == TriggerSignal::Crank {
+///         decoder.on_edge(edge.captured());
+///     }
+/// }
+/// assert_eq!(decoder.state(), SyncState::Synced);
+/// ```
+pub struct SignalGenerator<P: TriggerPattern, const K: usize> {
+    pattern: P,
+    config: SignalGenConfig<K>,
+    // Tiempo desde el inicio (µs) y ángulo absoluto (grados, sin normalizar)
+    time_us: f64,
+    angle_deg: f64,
+    // Rampa en curso: RPM al inicio, RPM objetivo y duración
+    ramp_from: f32,
+    ramp_to: f32,
+    ramp_start_us: f64,
+    ramp_us: f64,
+    // Siguiente flanco de cigüeñal: índice dentro del ciclo del patrón e inicio del ciclo
+    crank_edge: usize,
+    crank_base_deg: f64,
+    // Siguiente flanco de leva
+    cam_edge: usize,
+    cam_base_deg: f64,
+    // Pulso de ruido pendiente (ángulo absoluto, flanco de subida)
+    glitch: Option<(f64, bool)>,
+    glitch_from_deg: f64,
+    teeth: u32,
+    // El diente actual se perdió: su bajada tampoco sale
+    dropping: bool,
+    rng: u32,
+}
+
+impl<P: TriggerPattern, const K: usize> SignalGenerator<P, K> {
+    pub fn new(pattern: P, config: SignalGenConfig<K>) -> Self {
+        let start = config.start_angle_deg as f64;
+        let rpm = config.rpm;
+        let rng = if config.noise.seed == 0 { 0x1234_5678 } else { config.noise.seed };
+
+        let mut generator = Self {
+            pattern,
+            config,
+            time_us: 0.0,
+            angle_deg: start,
+            ramp_from: rpm,
+            ramp_to: rpm,
+            ramp_start_us: 0.0,
+            ramp_us: 0.0,
+            crank_edge: 0,
+            crank_base_deg: 0.0,
+            cam_edge: 0,
+            cam_base_deg: 0.0,
+            glitch: None,
+            glitch_from_deg: 0.0,
+            teeth: 0,
+            dropping: false,
+            rng,
+        };
+
+        // Salta los flancos anteriores al ángulo de arranque
+        let crank_cycle = generator.pattern.cycle_deg() as f64;
+        while generator.crank_base_deg + crank_cycle <= start {
+            generator.crank_base_deg += crank_cycle;
+        }
+        while generator.next_crank_deg() < start {
+            generator.advance_crank();
+        }
+        while generator.cam_base_deg + 720.0 <= start {
+            generator.cam_base_deg += 720.0;
+        }
+        if K > 0 {
+            while generator.next_cam_deg() < start {
+                generator.advance_cam();
+            }
+        }
+        generator
+    }
+
+    /// Cambia las RPM linealmente hasta `rpm` en `duration_us` (0 = cambio inmediato)
+    pub fn ramp_to(&mut self, rpm: f32, duration_us: u32) {
+        self.ramp_from = self.rpm();
+        self.ramp_to = rpm;
+        self.ramp_start_us = self.time_us;
+        self.ramp_us = duration_us as f64;
+    }
+
+    /// RPM en el instante del último flanco generado
+    pub fn rpm(&self) -> f32 {
+        self.rpm_at(self.time_us)
+    }
+
+    /// Ángulo de ciclo (0..720°) del último flanco generado
+    pub fn angle_deg(&self) -> f32 {
+        (self.angle_deg % 720.0) as f32
+    }
+
+    /// Marca de tiempo del último flanco generado (sin jitter)
+    pub fn now_us(&self) -> u32 {
+        self.config.start_us.wrapping_add((self.time_us + 0.5) as u64 as u32)
+    }
+
+    pub fn pattern(&self) -> &P {
+        &self.pattern
+    }
+
+    /// Siguiente flanco de la línea de tiempo (cigüeñal, leva y ruido en orden)
+    pub fn next_edge(&mut self) -> GeneratedEdge {
+        loop {
+            let crank_deg = self.next_crank_deg();
+            let cam_deg = if K > 0 { self.next_cam_deg() } else { f64::INFINITY };
+
+            if let Some((glitch_deg, rising)) = self.glitch {
+                if glitch_deg <= crank_deg && glitch_deg <= cam_deg {
+                    self.move_to(glitch_deg);
+                    // El pulso de ruido dura la mitad de lo que tardó en aparecer
+                    self.glitch = if rising {
+                        Some((glitch_deg + (glitch_deg - self.glitch_from_deg) * 0.5, false))
+                    } else {
+                        None
+                    };
+                    return self.emit(TriggerSignal::Crank, rising, None);
+                }
+            }
+
+            if cam_deg < crank_deg {
+                let tooth = self.cam_edge / 2;
+                let rising = self.cam_edge.is_multiple_of(2);
+                self.move_to(cam_deg);
+                self.advance_cam();
+                return self.emit(TriggerSignal::Cam, rising, Some(tooth as u16));
+            }
+
+            let (tooth, rising) = self.crank_tooth();
+            let edge_deg = crank_deg;
+            self.advance_crank();
+            self.move_to(edge_deg);
+
+            // Con ambos flancos cada flanco es un diente
+            let both = self.pattern.uses_both_edges();
+            if rising || both {
+                self.teeth = self.teeth.wrapping_add(1);
+                let noise = self.config.noise;
+                self.dropping = noise.drop_every > 0 && self.teeth.is_multiple_of(noise.drop_every);
+                if noise.glitch_every > 0 && self.teeth.is_multiple_of(noise.glitch_every) {
+                    // Rebote al 10% del hueco hasta el siguiente flanco
+                    let next = self.next_crank_deg();
+                    self.glitch_from_deg = edge_deg;
+                    self.glitch = Some((edge_deg + (next - edge_deg) * 0.1, true));
+                }
+            }
+            if self.dropping {
+                continue;
+            }
+            return self.emit(TriggerSignal::Crank, rising, Some(tooth as u16));
+        }
+    }
+
+    /// Diente del patrón y polaridad del siguiente flanco de cigüeñal
+    fn crank_tooth(&self) -> (usize, bool) {
+        if self.pattern.uses_both_edges() {
+            (self.crank_edge, self.crank_edge.is_multiple_of(2))
+        } else {
+            (self.crank_edge / 2, self.crank_edge.is_multiple_of(2))
+        }
+    }
+
+    /// Flancos de cigüeñal por ciclo del patrón
+    fn crank_edges(&self) -> usize {
+        if self.pattern.uses_both_edges() {
+            self.pattern.tooth_count()
+        } else {
+            self.pattern.tooth_count() * 2
+        }
+    }
+
+    fn next_crank_deg(&self) -> f64 {
+        let angle = if self.pattern.uses_both_edges() {
+            self.pattern.tooth_angle_deg(self.crank_edge) as f64
+        } else {
+            let tooth = self.crank_edge / 2;
+            let rise = self.pattern.tooth_angle_deg(tooth) as f64;
+            if self.crank_edge.is_multiple_of(2) {
+                rise
+            } else {
+                let next = (tooth + 1) % self.pattern.tooth_count();
+                rise + self.pattern.gap_deg(next) as f64 * 0.5
+            }
+        };
+        self.crank_base_deg + angle
+    }
+
+    fn advance_crank(&mut self) {
+        self.crank_edge += 1;
+        if self.crank_edge == self.crank_edges() {
+            self.crank_edge = 0;
+            self.crank_base_deg += self.pattern.cycle_deg() as f64;
+        }
+    }
+
+    fn next_cam_deg(&self) -> f64 {
+        let tooth = self.config.cam_teeth_deg[self.cam_edge / 2] as f64;
+        let offset = if self.cam_edge.is_multiple_of(2) { 0.0 } else { self.config.cam_tooth_width_deg as f64 };
+        self.cam_base_deg + tooth + offset
+    }
+
+    fn advance_cam(&mut self) {
+        self.cam_edge += 1;
+        if self.cam_edge == K * 2 {
+            self.cam_edge = 0;
+            self.cam_base_deg += 720.0;
+        }
+    }
+
+    /// Avanza el tiempo hasta el ángulo absoluto `target_deg`
+    fn move_to(&mut self, target_deg: f64) {
+        let delta = target_deg - self.angle_deg;
+        if delta > 0.0 {
+            // Punto medio: velocidad a la mitad del intervalo estimado
+            let dt = delta / deg_per_us(self.rpm_at(self.time_us));
+            let dt = delta / deg_per_us(self.rpm_at(self.time_us + dt * 0.5));
+            self.time_us += dt;
+        }
+        self.angle_deg = target_deg;
+    }
+
+    fn rpm_at(&self, time_us: f64) -> f32 {
+        let elapsed = time_us - self.ramp_start_us;
+        let rpm = if elapsed >= self.ramp_us {
+            self.ramp_to
+        } else {
+            let k = (elapsed / self.ramp_us) as f32;
+            self.ramp_from + (self.ramp_to - self.ramp_from) * k
+        };
+        rpm.max(MIN_RPM)
+    }
+
+    fn emit(&mut self, signal: TriggerSignal, rising: bool, tooth: Option<u16>) -> GeneratedEdge {
+        let mut timestamp_us = self.now_us();
+        let jitter = self.config.noise.jitter_us;
+        if jitter > 0 {
+            let offset = (self.random() % (2 * jitter + 1)) as i32 - jitter as i32;
+            timestamp_us = timestamp_us.wrapping_add_signed(offset);
+        }
+        GeneratedEdge { timestamp_us, signal, rising, tooth }
+    }
+
+    /// xorshift32
+    fn random(&mut self) -> u32 {
+        let mut x = self.rng;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.rng = x;
+        x
+    }
+}
+
+impl<P: TriggerPattern, const K: usize> Iterator for SignalGenerator<P, K> {
+    type Item = GeneratedEdge;
+
+    /// La línea de tiempo no termina
+    fn next(&mut self) -> Option<GeneratedEdge> {
+        Some(self.next_edge())
+    }
+}
+
+/// RPM -> grados/µs
+fn deg_per_us(rpm: f32) -> f64 {
+    rpm as f64 * 360.0 / 60_000_000.0
+}
@@ -0,0 +1,49 @@
+use crate::launch_control::LaunchControl;
+use crate::rev_limiter::RevLimiter;
+
+/// Una corrección del avance de chispa.
+/// Cada módulo que retarda la chispa (limitador, launch, knock...) la implementa
+/// y el avance final se arma sumando todas las correcciones sobre el avance base.
+pub trait SparkCorrection {
+    /// Retardo en grados para el cilindro (base 0). Positivo = menos avance.
+    fn retard_deg(&self, cylinder: usize) -> f32;
+}
+
+/// Calcula el avance final de un cilindro.
+/// base_deg: Avance de la tabla (grados APMS)
+/// min_advance_deg: Límite inferior (ej. -10° para no quemar el escape)
+///
+/// # Ejemplo
+///
+/// ```
+/// use engine_core::spark_advance::{corrected_advance, SparkCorrection};
+///
+/// struct Fijo(f32);
+/// impl SparkCorrection for Fijo {
+///     fn retard_deg(&self, _cylinder: usize) -> f32 { self.0 }
+/// }
+///
+/// let avance = corrected_advance(30.0, 0, &[&Fijo(5.0), &Fijo(3.0)], -10.0);
+/// assert_eq!(avance, 22.0);
+/// ```
+pub fn corrected_advance(
+    base_deg: f32,
+    cylinder: usize,
+    corrections: &[&dyn SparkCorrection],
+    min_advance_deg: f32,
+) -> f32 {
+    let total_retard: f32 = corrections.iter().map(|c| c.retard_deg(cylinder)).sum();
+    (base_deg - total_retard).max(min_advance_deg)
+}
+
+impl<const N: usize> SparkCorrection for RevLimiter<N> {
+    fn retard_deg(&self, _cylinder: usize) -> f32 {
+        self.status().retard_deg
+    }
+}
+
+impl SparkCorrection for LaunchControl {
+    fn retard_deg(&self, _cylinder: usize) -> f32 {
+        self.status().retard_deg
+    }
+}
@@ -41,10 +41,10 @@ impl<const N: usize, const M: usize> Table3D<N,M> {
     pub fn interpolate(&self, x_val: f32, y_val: f32) -> f32 {
         
         // encontramos los indices para X
-        let (x0_idx, x1_idx, x_factor) = self.find_axis_indices(&self.x_axis, x_val);
+        let (x0_idx, x1_idx, x_factor) = find_axis_indices(&self.x_axis, x_val);
 
         // encontramos los indices para Y
-        let (y0_idx, y1_idx, y_factor) = self.find_axis_indices(&self.y_axis, y_val);
+        let (y0_idx, y1_idx, y_factor) = find_axis_indices(&self.y_axis, y_val);
 
         // Se debe obtener los vecinos de la celda en la que nos encontramos (x,y)
         let q11 = self.data[y0_idx][x0_idx];
@@ -60,31 +60,64 @@ impl<const N: usize, const M: usize> Table3D<N,M> {
         
         final_val
     }
+}
+
+/// Una tabla 2D genérica (Curva).
+/// X: Eje horizontal (ej. Temperatura de refrigerante)
+/// Y: Datos (ej. RPM límite)
+/// N: Número de puntos
+///
+/// # Ejemplo
+///
+/// ```
+/// use engine_core::tables::Table2D;
+///
+/// let curva = Table2D::new([0.0, 80.0], [4000.0, 7000.0]);
+/// assert_eq!(curva.interpolate(40.0), 5500.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Table2D<const N: usize> {
+    pub x_axis: [f32; N], // Breakpoints
+    pub data: [f32; N],   // Valor en cada breakpoint
+}
+
+impl<const N: usize> Table2D<N> {
 
-    /// Funcion para buscar los indices de las celdas
-    /// Retorna: (indice_bajo, indice_alto, factor_de_peso)
-    fn find_axis_indices(&self, axis: &[f32], value:f32) -> (usize, usize, f32) {
-
-        // Validamos no salir de la tabla, si no, usamos el ultimo valor
-        if value <= axis[0] { return (0,0,0.0);}
-        if value >= axis[axis.len() - 1] {return (axis.len() - 1, axis.len() - 1, 0.0);}
-
-        // busqueda lineal
-        // TODO: evaluar busqueda bianria
-        let mut idx = 0;
-        for i in 0..axis.len()-1 {
-            if value >= axis[i] && value < axis[i+1] {
-                idx = i;
-                break;
-            }
+    pub fn new(x_axis: [f32; N], data: [f32; N]) -> Self {
+        Self { x_axis, data }
+    }
+
+    /// Interpolacion lineal, fuera de rango se usa el extremo
+    pub fn interpolate(&self, x_val: f32) -> f32 {
+        let (x0_idx, x1_idx, factor) = find_axis_indices(&self.x_axis, x_val);
+
+        self.data[x0_idx] * (1.0 - factor) + self.data[x1_idx] * factor
+    }
+}
+
+/// Funcion para buscar los indices de las celdas
+/// Retorna: (indice_bajo, indice_alto, factor_de_peso)
+fn find_axis_indices(axis: &[f32], value:f32) -> (usize, usize, f32) {
+
+    // Validamos no salir de la tabla, si no, usamos el ultimo valor
+    if value <= axis[0] { return (0,0,0.0);}
+    if value >= axis[axis.len() - 1] {return (axis.len() - 1, axis.len() - 1, 0.0);}
+
+    // busqueda lineal
+    // TODO: evaluar busqueda bianria
+    let mut idx = 0;
+    for i in 0..axis.len()-1 {
+        if value >= axis[i] && value < axis[i+1] {
+            idx = i;
+            break;
         }
-        
-        let x0 = axis[idx];
-        let x1 = axis[idx + 1];
-        
-        // Factor: ¿Qué tan cerca estamos de x1? (0.0 = en x0, 1.0 = en x1)
-        let factor: f32 = (value - x0) / (x1 - x0);
-        
-        (idx, idx + 1, factor)
     }
-}
\ No newline at end of file
+    
+    let x0 = axis[idx];
+    let x1 = axis[idx + 1];
+    
+    // Factor: ¿Qué tan cerca estamos de x1? (0.0 = en x0, 1.0 = en x1)
+    let factor: f32 = (value - x0) / (x1 - x0);
+    
+    (idx, idx + 1, factor)
+}
@@ -0,0 +1,297 @@
+use crate::analog::{Converter, LinearConverter};
+use crate::diagnostics::{DiagnosticLog, FaultCode, FaultKind, SensorId};
+use crate::sensor_fault::{FaultTransition, RangeMonitor};
+
+/// Voltajes de mariposa cerrada y a fondo de una pista.
+/// Con pendiente inversa (pista opuesta) `closed_volts` es mayor que `wot_volts`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TpsCalibration {
+    pub closed_volts: f32,
+    pub wot_volts: f32,
+}
+
+impl TpsCalibration {
+    pub const fn new(closed_volts: f32, wot_volts: f32) -> Self {
+        Self { closed_volts, wot_volts }
+    }
+
+    /// Conversión lineal 0..100% (para `CalibratedSensor`)
+    pub fn converter(&self) -> LinearConverter {
+        LinearConverter::new(self.closed_volts, 0.0, self.wot_volts, 100.0)
+    }
+
+    /// Apertura (%) limitada a 0..100
+    pub fn percent(&self, volts: f32) -> f32 {
+        self.converter().convert(volts).clamp(0.0, 100.0)
+    }
+
+    fn rising(&self) -> bool {
+        self.wot_volts > self.closed_volts
+    }
+}
+
+/// Límites de lo que se acepta aprender
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TpsLimits {
+    /// Rango aceptado para el voltaje de cerrado
+    pub closed_min_volts: f32,
+    pub closed_max_volts: f32,
+    /// Rango aceptado para el voltaje a fondo
+    pub wot_min_volts: f32,
+    pub wot_max_volts: f32,
+    /// Diferencia mínima entre cerrado y a fondo
+    pub min_span_volts: f32,
+    /// Variación máxima de la lectura para considerarla estable en el seguimiento del cerrado
+    pub closed_stable_volts: f32,
+    /// Tiempo que la lectura debe quedarse estable antes de aprenderla (µs)
+    pub closed_stable_us: u32,
+    /// Lo más que se mueve el cerrado aprendido en cada actualización
+    pub closed_step_volts: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TpsCalError {
+    /// `finish_calibration` sin `begin_calibration`
+    NotCalibrating,
+    ClosedOutOfRange,
+    WotOutOfRange,
+    /// El recorrido no cubrió de cerrado a fondo
+    SpanTooSmall,
+}
+
+/// Una pista de TPS con aprendizaje de sus extremos.
+///
+/// Hay dos formas de aprender:
+/// - Comando de calibración: `begin_calibration`, se recorre el acelerador
+///   de cerrado a fondo y `finish_calibration` valida y aplica los extremos.
+/// - Seguimiento continuo del cerrado: una lectura más allá del cerrado
+///   aprendido (y dentro de los límites) que se mantiene estable
+///   `closed_stable_us` lo corrige poco a poco (`closed_step_volts` por
+///   actualización), así el desgaste del sensor no deja el ralentí en 1-2%
+///   y un pico de ruido no mueve la calibración.
+pub struct TpsTrack {
+    calibration: TpsCalibration,
+    limits: TpsLimits,
+    sweep: Option<(f32, f32)>,
+    /// Lectura candidata del seguimiento y desde cuándo está estable
+    candidate: Option<(f32, u32)>,
+}
+
+impl TpsTrack {
+    pub fn new(calibration: TpsCalibration, limits: TpsLimits) -> Self {
+        Self { calibration, limits, sweep: None, candidate: None }
+    }
+
+    /// Procesa un voltaje y regresa la apertura (%)
+    pub fn update(&mut self, volts: f32, now_us: u32) -> f32 {
+        if let Some((min, max)) = self.sweep.as_mut() {
+            *min = min.min(volts);
+            *max = max.max(volts);
+        } else {
+            self.track_closed(volts, now_us);
+        }
+        self.calibration.percent(volts)
+    }
+
+    fn track_closed(&mut self, volts: f32, now_us: u32) {
+        let l = self.limits;
+        let c = &mut self.calibration;
+        let beyond = if c.rising() { volts < c.closed_volts } else { volts > c.closed_volts };
+        if !beyond || volts < l.closed_min_volts || volts > l.closed_max_volts {
+            self.candidate = None;
+            return;
+        }
+
+        let since = match self.candidate {
+            Some((reference, since)) if (volts - reference).abs() <= l.closed_stable_volts => since,
+            _ => {
+                self.candidate = Some((volts, now_us));
+                now_us
+            }
+        };
+        if now_us.wrapping_sub(since) >= l.closed_stable_us {
+            let step = (volts - c.closed_volts).clamp(-l.closed_step_volts, l.closed_step_volts);
+            c.closed_volts += step;
+        }
+    }
+
+    /// Inicia el comando de calibración
+    pub fn begin_calibration(&mut self) {
+        self.sweep = Some((f32::MAX, f32::MIN));
+        self.candidate = None;
+    }
+
+    pub fn is_calibrating(&self) -> bool {
+        self.sweep.is_some()
+    }
+
+    /// Termina el comando: valida los extremos recorridos y los aplica.
+    /// Con error se conserva la calibración anterior.
+    pub fn finish_calibration(&mut self) -> Result<TpsCalibration, TpsCalError> {
+        let (min, max) = self.sweep.take().ok_or(TpsCalError::NotCalibrating)?;
+        let (closed, wot) = if self.calibration.rising() { (min, max) } else { (max, min) };
+
+        let l = &self.limits;
+        if !(l.closed_min_volts..=l.closed_max_volts).contains(&closed) {
+            return Err(TpsCalError::ClosedOutOfRange);
+        }
+        if !(l.wot_min_volts..=l.wot_max_volts).contains(&wot) {
+            return Err(TpsCalError::WotOutOfRange);
+        }
+        if (wot - closed).abs() < l.min_span_volts {
+            return Err(TpsCalError::SpanTooSmall);
+        }
+        self.calibration = TpsCalibration::new(closed, wot);
+        Ok(self.calibration)
+    }
+
+    pub fn calibration(&self) -> TpsCalibration {
+        self.calibration
+    }
+}
+
+/// Configuración de la plausibilidad entre las dos pistas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DualTpsConfig {
+    /// Diferencia máxima entre pistas (puntos de %)
+    pub disagreement_pct: f32,
+    /// Tiempo que debe mantenerse la diferencia para activar la falla (µs)
+    pub debounce_us: u32,
+    /// Apertura máxima en modo limitado (%)
+    pub limp_max_pct: f32,
+}
+
+/// Modo de operación del acelerador
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TpsMode {
+    Normal,
+    /// Las pistas no coinciden: se usa la menor y se limita la apertura
+    Limited,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TpsReading {
+    pub percent: f32,
+    pub mode: TpsMode,
+}
+
+/// TPS de doble pista (dos señales redundantes, con pendientes opuestas o
+/// desplazadas). Si las pistas difieren más del umbral durante el tiempo de
+/// debounce se reporta la falla de correlación y se entra en modo limitado,
+/// que queda retenido hasta `clear_fault` (ciclo de llave).
+///
+/// # Ejemplo
+///
+/// ```
+/// use engine_core::diagnostics::DiagnosticLog;
+/// use engine_core::tps::{DualTps, DualTpsConfig, TpsCalibration, TpsLimits, TpsMode, TpsTrack};
+///
+/// let limits = TpsLimits {
+///     closed_min_volts: 0.2,
+///     closed_max_volts: 4.8,
+///     wot_min_volts: 0.2,
+///     wot_max_volts: 4.8,
+///     min_span_volts: 2.0,
+///     closed_stable_volts: 0.02,
+///     closed_stable_us: 1_000_000,
+///     closed_step_volts: 0.002,
+/// };
+/// // Pista A sube de 0.5 a 4.5 V, la B baja de 4.5 a 0.5 V
+/// let a = TpsTrack::new(TpsCalibration::new(0.5, 4.5), limits);
+/// let b = TpsTrack::new(TpsCalibration::new(4.5, 0.5), limits);
+/// let config = DualTpsConfig { disagreement_pct: 10.0, debounce_us: 0, limp_max_pct: 20.0 };
+/// let mut tps = DualTps::new(a, b, config);
+/// let mut diag: DiagnosticLog<4> = DiagnosticLog::new();
+///
+/// let reading = tps.update(2.5, 2.5, 0, &mut diag);
+/// assert_eq!(reading.percent, 50.0);
+///
+/// // La pista B se desconecta (0 V = 100%)
+/// let reading = tps.update(2.5, 0.0, 1000, &mut diag);
+/// assert_eq!(reading.mode, TpsMode::Limited);
+/// assert_eq!(reading.percent, 20.0);
+/// ```
+pub struct DualTps {
+    a: TpsTrack,
+    b: TpsTrack,
+    config: DualTpsConfig,
+    monitor: RangeMonitor,
+    reading: TpsReading,
+}
+
+impl DualTps {
+    pub fn new(a: TpsTrack, b: TpsTrack, config: DualTpsConfig) -> Self {
+        Self {
+            a,
+            b,
+            config,
+            monitor: RangeMonitor::new(config.debounce_us),
+            reading: TpsReading { percent: 0.0, mode: TpsMode::Normal },
+        }
+    }
+
+    /// Procesa los voltajes de las dos pistas
+    pub fn update<const N: usize>(
+        &mut self,
+        volts_a: f32,
+        volts_b: f32,
+        now_us: u32,
+        diag: &mut DiagnosticLog<N>,
+    ) -> TpsReading {
+        let pct_a = self.a.update(volts_a, now_us);
+        let pct_b = self.b.update(volts_b, now_us);
+
+        // La falla queda retenida: ya no se vuelve a evaluar
+        if self.monitor.fault().is_none() {
+            let disagree = (pct_a - pct_b).abs() > self.config.disagreement_pct;
+            let observed = if disagree { Some(FaultKind::Disagreement) } else { None };
+            if let Some(FaultTransition::Set(kind)) = self.monitor.observe(observed, now_us) {
+                diag.report(FaultCode::new(SensorId::Tps, kind), true, now_us);
+            }
+        }
+
+        self.reading = if self.monitor.fault().is_some() {
+            TpsReading { percent: pct_a.min(pct_b).min(self.config.limp_max_pct), mode: TpsMode::Limited }
+        } else {
+            TpsReading { percent: (pct_a + pct_b) / 2.0, mode: TpsMode::Normal }
+        };
+        self.reading
+    }
+
+    pub fn reading(&self) -> TpsReading {
+        self.reading
+    }
+
+    /// Sale del modo limitado (ciclo de llave o herramienta de diagnóstico)
+    pub fn clear_fault<const N: usize>(&mut self, now_us: u32, diag: &mut DiagnosticLog<N>) {
+        self.monitor = RangeMonitor::new(self.config.debounce_us);
+        diag.report(FaultCode::new(SensorId::Tps, FaultKind::Disagreement), false, now_us);
+    }
+
+    /// Comando de calibración en las dos pistas a la vez
+    pub fn begin_calibration(&mut self) {
+        self.a.begin_calibration();
+        self.b.begin_calibration();
+    }
+
+    /// Termina la calibración de las dos pistas; si alguna falla ninguna cambia
+    pub fn finish_calibration(&mut self) -> Result<(TpsCalibration, TpsCalibration), TpsCalError> {
+        let (old_a, old_b) = (self.a.calibration, self.b.calibration);
+        let result = self.a.finish_calibration().and_then(|a| Ok((a, self.b.finish_calibration()?)));
+        if result.is_err() {
+            self.a.sweep = None;
+            self.b.sweep = None;
+            self.a.calibration = old_a;
+            self.b.calibration = old_b;
+        }
+        result
+    }
+
+    pub fn track_a(&self) -> &TpsTrack {
+        &self.a
+    }
+
+    pub fn track_b(&self) -> &TpsTrack {
+        &self.b
+    }
+}
@@ -0,0 +1,421 @@
+use core::fmt::{self, Write};
+
+use ecu_traits::engine_io::CapturedEdge;
+use crate::crank_decoder::{SyncState, ToothEvent};
+
+/// Qué registra el logger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogMode {
+    Off,
+    /// Solo flancos de cigüeñal (tooth logger)
+    Tooth,
+    /// Cigüeñal y leva en la misma línea de tiempo (composite logger)
+    Composite,
+}
+
+/// Tipo de entrada del log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogEvent {
+    CrankEdge,
+    CamEdge,
+    SyncGained,
+    SyncLost,
+}
+
+/// Una entrada del log de disparo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogEntry {
+    pub timestamp_us: u32,
+    pub event: LogEvent,
+    /// Polaridad del flanco (en eventos de sincronía es la del flanco que los causó)
+    pub rising: bool,
+    /// Nivel de la señal de leva en ese momento
+    pub cam_level: bool,
+    pub sync: SyncState,
+    /// Diente reportado por el decodificador (solo con sincronía)
+    pub tooth: Option<u16>,
+}
+
+/// Bytes por entrada en los frames del protocolo de calibración
+pub const ENTRY_BYTES: usize = 8;
+
+/// Comandos del logger en el protocolo de calibración (primer byte de la petición)
+pub const CMD_START: u8 = 0x01;
+pub const CMD_STOP: u8 = 0x02;
+pub const CMD_STATUS: u8 = 0x03;
+pub const CMD_READ: u8 = 0x04;
+
+/// Bytes de la respuesta a `CMD_STATUS`
+pub const STATUS_BYTES: usize = 8;
+
+/// Error al atender un comando del protocolo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogCommandError {
+    /// Petición vacía, incompleta o con un comando desconocido
+    BadRequest,
+    /// El buffer de respuesta no alcanza
+    ResponseTooSmall,
+}
+
+/// Captura acotada de la señal de disparo para diagnosticar arranques.
+/// N: entradas del buffer
+///
+/// La captura es de un solo disparo: se llena y se detiene para que la
+/// herramienta de calibración la lea completa con `read_frame` (o con
+/// `handle_command` desde el protocolo). Un flanco y la marca de sincronía
+/// que causó se guardan juntos: si no caben los dos la captura termina ahí
+/// y se cuentan como descartados, así no quedan huecos ni marcas perdidas.
+pub struct TriggerLogger<const N: usize> {
+    mode: LogMode,
+    entries: [LogEntry; N],
+    len: usize,
+    dropped: u32,
+    cam_level: bool,
+    last_sync: SyncState,
+}
+
+impl<const N: usize> TriggerLogger<N> {
+    pub fn new() -> Self {
+        Self {
+            mode: LogMode::Off,
+            entries: [EMPTY_ENTRY; N],
+            len: 0,
+            dropped: 0,
+            cam_level: false,
+            last_sync: SyncState::NoSync,
+        }
+    }
+
+    /// Borra la captura anterior y empieza a registrar
+    pub fn start(&mut self, mode: LogMode) {
+        self.mode = mode;
+        self.len = 0;
+        self.dropped = 0;
+    }
+
+    pub fn stop(&mut self) {
+        self.mode = LogMode::Off;
+    }
+
+    /// true mientras la captura está activa y tiene espacio
+    pub fn is_recording(&self) -> bool {
+        self.mode != LogMode::Off && !self.is_full()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N || self.dropped > 0
+    }
+
+    /// Entradas que llegaron con la captura llena
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    pub fn mode(&self) -> LogMode {
+        self.mode
+    }
+
+    /// Registra un flanco de cigüeñal y el resultado del decodificador.
+    /// Los cambios de sincronía se registran como entradas propias.
+    pub fn on_crank_edge(&mut self, edge: CapturedEdge, sync: SyncState, event: Option<&ToothEvent>) {
+        let was_synced = self.last_sync == SyncState::Synced;
+        let synced = sync == SyncState::Synced;
+        self.last_sync = sync;
+        if self.mode == LogMode::Off {
+            return;
+        }
+
+        let marker = match (was_synced, synced) {
+            (false, true) => Some(LogEvent::SyncGained),
+            (true, false) => Some(LogEvent::SyncLost),
+            _ => None,
+        };
+        let needed = 1 + marker.is_some() as usize;
+        if !self.reserve(needed) {
+            return;
+        }
+
+        let tooth = event.map(|e| e.tooth);
+        self.push(edge, LogEvent::CrankEdge, sync, tooth);
+        if let Some(kind) = marker {
+            self.push(edge, kind, sync, tooth);
+        }
+    }
+
+    /// Registra un flanco de leva (solo en modo composite)
+    pub fn on_cam_edge(&mut self, edge: CapturedEdge) {
+        self.cam_level = edge.rising;
+        if self.mode == LogMode::Composite && self.reserve(1) {
+            self.push(edge, LogEvent::CamEdge, self.last_sync, None);
+        }
+    }
+
+    /// true si caben `needed` entradas; si no, se cuentan como descartadas
+    fn reserve(&mut self, needed: usize) -> bool {
+        if !self.is_full() && self.len + needed <= N {
+            return true;
+        }
+        self.dropped = self.dropped.saturating_add(needed as u32);
+        false
+    }
+
+    pub fn entries(&self) -> &[LogEntry] {
+        &self.entries[..self.len]
+    }
+
+    /// Copia en `buf` las entradas codificadas a partir de `first_entry`
+    /// (lectura paginada desde el protocolo de calibración).
+    /// Regresa los bytes escritos; siempre son entradas completas.
+    pub fn read_frame(&self, first_entry: usize, buf: &mut [u8]) -> usize {
+        let mut written = 0;
+        for entry in self.entries().iter().skip(first_entry) {
+            if written + ENTRY_BYTES > buf.len() {
+                break;
+            }
+            buf[written..written + ENTRY_BYTES].copy_from_slice(&encode_entry(entry));
+            written += ENTRY_BYTES;
+        }
+        written
+    }
+
+    /// Atiende un comando del protocolo de calibración. Regresa los bytes
+    /// escritos en `response`.
+    /// - `CMD_START` modo (1 = tooth, 2 = composite): inicia una captura
+    /// - `CMD_STOP`: detiene la captura
+    /// - `CMD_STATUS`: modo, lleno, entradas (u16), capacidad (u16) y descartadas (u16)
+    /// - `CMD_READ` primera entrada (u16): entradas codificadas (ver `read_frame`)
+    pub fn handle_command(&mut self, request: &[u8], response: &mut [u8]) -> Result<usize, LogCommandError> {
+        match request {
+            [CMD_START, mode] => {
+                let mode = match mode {
+                    1 => LogMode::Tooth,
+                    2 => LogMode::Composite,
+                    _ => return Err(LogCommandError::BadRequest),
+                };
+                self.start(mode);
+                Ok(0)
+            }
+            [CMD_STOP] => {
+                self.stop();
+                Ok(0)
+            }
+            [CMD_STATUS] => {
+                let out = response.get_mut(..STATUS_BYTES).ok_or(LogCommandError::ResponseTooSmall)?;
+                out[0] = match self.mode {
+                    LogMode::Off => 0,
+                    LogMode::Tooth => 1,
+                    LogMode::Composite => 2,
+                };
+                out[1] = self.is_full() as u8;
+                out[2..4].copy_from_slice(&(self.len as u16).to_le_bytes());
+                out[4..6].copy_from_slice(&(N.min(u16::MAX as usize) as u16).to_le_bytes());
+                out[6..8].copy_from_slice(&(self.dropped.min(u16::MAX as u32) as u16).to_le_bytes());
+                Ok(STATUS_BYTES)
+            }
+            [CMD_READ, lo, hi] => {
+                if response.len() < ENTRY_BYTES {
+                    return Err(LogCommandError::ResponseTooSmall);
+                }
+                Ok(self.read_frame(u16::from_le_bytes([*lo, *hi]) as usize, response))
+            }
+            _ => Err(LogCommandError::BadRequest),
+        }
+    }
+
+    /// Exporta la captura como CSV
+    pub fn write_csv<W: Write>(&self, out: &mut W) -> fmt::Result {
+        write_csv(self.entries(), out)
+    }
+
+    fn push(&mut self, edge: CapturedEdge, event: LogEvent, sync: SyncState, tooth: Option<u16>) {
+        self.entries[self.len] = LogEntry {
+            timestamp_us: edge.timestamp_us,
+            event,
+            rising: edge.rising,
+            cam_level: self.cam_level,
+            sync,
+            tooth,
+        };
+        self.len += 1;
+    }
+}
+
+impl<const N: usize> Default for TriggerLogger<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const EMPTY_ENTRY: LogEntry = LogEntry {
+    timestamp_us: 0,
+    event: LogEvent::CrankEdge,
+    rising: false,
+    cam_level: false,
+    sync: SyncState::NoSync,
+    tooth: None,
+};
+
+/// Codifica una entrada para el protocolo (little endian):
+/// - bytes 0..4: marca de tiempo (µs)
+/// - bytes 4..6: diente (0xFFFF = sin diente)
+/// - byte 6: bits 0-1 evento, bit 2 flanco de subida, bit 3 nivel de leva, bits 4-5 sincronía
+/// - byte 7: reservado
+pub fn encode_entry(entry: &LogEntry) -> [u8; ENTRY_BYTES] {
+    let mut out = [0u8; ENTRY_BYTES];
+    out[0..4].copy_from_slice(&entry.timestamp_us.to_le_bytes());
+    out[4..6].copy_from_slice(&entry.tooth.unwrap_or(u16::MAX).to_le_bytes());
+
+    let event = match entry.event {
+        LogEvent::CrankEdge => 0,
+        LogEvent::CamEdge => 1,
+        LogEvent::SyncGained => 2,
+        LogEvent::SyncLost => 3,
+    };
+    let sync = match entry.sync {
+        SyncState::NoSync => 0,
+        SyncState::Syncing => 1,
+        SyncState::Synced => 2,
+        SyncState::Lost => 3,
+    };
+    out[6] = event | (entry.rising as u8) << 2 | (entry.cam_level as u8) << 3 | sync << 4;
+    out
+}
+
+/// Decodifica una entrada del protocolo (lado de la PC)
+pub fn decode_entry(bytes: &[u8; ENTRY_BYTES]) -> LogEntry {
+    let tooth = u16::from_le_bytes([bytes[4], bytes[5]]);
+    let flags = bytes[6];
+
+    LogEntry {
+        timestamp_us: u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        event: match flags & 0b11 {
+            0 => LogEvent::CrankEdge,
+            1 => LogEvent::CamEdge,
+            2 => LogEvent::SyncGained,
+            _ => LogEvent::SyncLost,
+        },
+        rising: flags & (1 << 2) != 0,
+        cam_level: flags & (1 << 3) != 0,
+        sync: match (flags >> 4) & 0b11 {
+            0 => SyncState::NoSync,
+            1 => SyncState::Syncing,
+            2 => SyncState::Synced,
+            _ => SyncState::Lost,
+        },
+        tooth: if tooth == u16::MAX { None } else { Some(tooth) },
+    }
+}
+
+/// Escribe las entradas como CSV (una fila por entrada).
+/// delta_us es el tiempo desde el flanco anterior de la misma señal.
+pub fn write_csv<W: Write>(entries: &[LogEntry], out: &mut W) -> fmt::Result {
+    writeln!(out, "timestamp_us,delta_us,event,rising,cam,sync,tooth")?;
+
+    let mut last_crank: Option<u32> = None;
+    let mut last_cam: Option<u32> = None;
+    for entry in entries {
+        let mut none = None;
+        let last = match entry.event {
+            LogEvent::CrankEdge => &mut last_crank,
+            LogEvent::CamEdge => &mut last_cam,
+            _ => &mut none,
+        };
+        let delta = last.map(|t| entry.timestamp_us.wrapping_sub(t)).unwrap_or(0);
+        *last = Some(entry.timestamp_us);
+
+        write!(
+            out,
+            "{},{},{},{},{},{},",
+            entry.timestamp_us,
+            delta,
+            event_name(entry.event),
+            entry.rising as u8,
+            entry.cam_level as u8,
+            sync_name(entry.sync),
+        )?;
+        match entry.tooth {
+            Some(tooth) => writeln!(out, "{}", tooth)?,
+            None => writeln!(out)?,
+        }
+    }
+    Ok(())
+}
+
+/// Dibuja los periodos de cigüeñal como barras de texto para verlos en la PC.
+/// Un periodo mucho más largo que el anterior se marca como hueco y uno
+/// mucho más corto como ruido; los cambios de sincronía y la leva se anotan.
+/// width: caracteres de la barra más larga
+pub fn render_periods<W: Write>(entries: &[LogEntry], out: &mut W, width: usize) -> fmt::Result {
+    // Escala con el periodo más largo de la captura
+    let mut max_period = 1;
+    let mut last: Option<u32> = None;
+    for entry in entries.iter().filter(|e| e.event == LogEvent::CrankEdge) {
+        if let Some(t) = last {
+            max_period = max_period.max(entry.timestamp_us.wrapping_sub(t));
+        }
+        last = Some(entry.timestamp_us);
+    }
+
+    let mut last: Option<u32> = None;
+    // Último periodo normal (sin hueco ni ruido) como referencia
+    let mut reference: Option<u32> = None;
+    for entry in entries {
+        match entry.event {
+            LogEvent::CrankEdge => {}
+            LogEvent::CamEdge => {
+                writeln!(out, "{:>10} leva {}", entry.timestamp_us, if entry.rising { "^" } else { "v" })?;
+                continue;
+            }
+            LogEvent::SyncGained => {
+                writeln!(out, "{:>10} == sincronía ==", entry.timestamp_us)?;
+                continue;
+            }
+            LogEvent::SyncLost => {
+                writeln!(out, "{:>10} == sincronía perdida ==", entry.timestamp_us)?;
+                continue;
+            }
+        }
+
+        let Some(t) = last.replace(entry.timestamp_us) else { continue };
+        let period = entry.timestamp_us.wrapping_sub(t);
+        let bar = (period as u64 * width as u64 / max_period as u64) as usize;
+
+        write!(out, "{:>10} {:>7} ", entry.timestamp_us, period)?;
+        for _ in 0..bar.max(1) {
+            out.write_char('#')?;
+        }
+        let mut normal = true;
+        if let Some(reference) = reference {
+            if period as u64 * 2 > reference as u64 * 3 {
+                out.write_str(" <- hueco")?;
+                normal = false;
+            } else if (period as u64) * 2 < reference as u64 {
+                out.write_str(" <- ruido")?;
+                normal = false;
+            }
+        }
+        writeln!(out)?;
+        if normal {
+            reference = Some(period);
+        }
+    }
+    Ok(())
+}
+
+fn event_name(event: LogEvent) -> &'static str {
+    match event {
+        LogEvent::CrankEdge => "crank",
+        LogEvent::CamEdge => "cam",
+        LogEvent::SyncGained => "sync",
+        LogEvent::SyncLost => "sync_lost",
+    }
+}
+
+fn sync_name(sync: SyncState) -> &'static str {
+    match sync {
+        SyncState::NoSync => "no_sync",
+        SyncState::Syncing => "syncing",
+        SyncState::Synced => "synced",
+        SyncState::Lost => "lost",
+    }
+}
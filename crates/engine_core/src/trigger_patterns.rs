@@ -0,0 +1,274 @@
+/// Descripción de un patrón de disparo (rueda fónica o señal de distribuidor).
+///
+/// Cada patrón describe el ángulo de sus dientes (los flancos que recibe el
+/// decodificador) y su regla de sincronía. La regla por defecto compara las
+/// relaciones entre los últimos periodos medidos contra las relaciones que
+/// producen los ángulos del patrón; un patrón solo necesita dar sus ángulos.
+pub trait TriggerPattern {
+    /// Flancos que recibe el decodificador por ciclo del patrón
+    fn tooth_count(&self) -> usize;
+
+    /// Ángulo del diente (0 = referencia del patrón), ascendente y menor a `cycle_deg`
+    fn tooth_angle_deg(&self, tooth: usize) -> f32;
+
+    /// Grados que cubre el patrón: 360 en cigüeñal, 720 si está en la leva
+    fn cycle_deg(&self) -> f32 {
+        360.0
+    }
+
+    /// true si el patrón usa ambos flancos (subida y bajada) de la señal
+    fn uses_both_edges(&self) -> bool {
+        false
+    }
+
+    /// Periodos (los más recientes) que usa la regla de sincronía
+    fn sync_history(&self) -> usize {
+        3
+    }
+
+    /// Tolerancia de la relación entre periodos (ej. 1.4 = ±40%)
+    fn ratio_tolerance(&self) -> f32 {
+        1.4
+    }
+
+    /// Grados desde el diente anterior hasta este
+    fn gap_deg(&self, tooth: usize) -> f32 {
+        let n = self.tooth_count();
+        let prev = (tooth + n - 1) % n;
+        let gap = self.tooth_angle_deg(tooth) - self.tooth_angle_deg(prev);
+        if gap <= 0.0 { gap + self.cycle_deg() } else { gap }
+    }
+
+    /// Intenta identificar el diente recién recibido.
+    /// periods: periodos en orden cronológico (el último termina en este diente)
+    /// Regresa el índice solo si un único diente es compatible con la historia.
+    fn identify(&self, periods: &[u32]) -> Option<usize> {
+        let history = self.sync_history().min(periods.len());
+        if history < 2 {
+            return None;
+        }
+        let recent = &periods[periods.len() - history..];
+
+        let mut found = None;
+        for candidate in 0..self.tooth_count() {
+            if matches_history(self, candidate, recent) {
+                if found.is_some() {
+                    // Ambiguo: se necesita más historia
+                    return None;
+                }
+                found = Some(candidate);
+            }
+        }
+        found
+    }
+
+    /// Con sincronía: verifica que el diente esperado sea compatible con los
+    /// dos últimos periodos. Si regresa false el decodificador pierde la sincronía.
+    fn validate(&self, tooth: usize, periods: &[u32]) -> bool {
+        if periods.len() < 2 {
+            return true;
+        }
+        matches_history(self, tooth, &periods[periods.len() - 2..])
+    }
+}
+
+/// Compara las relaciones entre periodos medidos contra las del patrón,
+/// suponiendo que el último periodo termina en el diente `tooth`.
+fn matches_history<P: TriggerPattern + ?Sized>(pattern: &P, tooth: usize, periods: &[u32]) -> bool {
+    let n = pattern.tooth_count();
+    let tolerance = pattern.ratio_tolerance();
+    let last = periods.len() - 1;
+
+    for k in 1..periods.len() {
+        // Diente en el que termina el periodo k
+        let tooth_k = (tooth + n * periods.len() - (last - k)) % n;
+        let tooth_prev = (tooth_k + n - 1) % n;
+
+        if periods[k - 1] == 0 {
+            return false;
+        }
+        let measured = periods[k] as f32 / periods[k - 1] as f32;
+        let expected = pattern.gap_deg(tooth_k) / pattern.gap_deg(tooth_prev);
+        let error = measured / expected;
+
+        if error > tolerance || error < 1.0 / tolerance {
+            return false;
+        }
+    }
+    true
+}
+
+/// Rueda fónica de dientes faltantes (N-M), ej. 60-2, 36-1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingToothWheel {
+    /// Posiciones totales de la rueda (ej. 60 en una 60-2)
+    pub teeth: u16,
+    /// Dientes faltantes consecutivos (ej. 2 en una 60-2)
+    pub missing: u16,
+}
+
+impl MissingToothWheel {
+    pub const fn new(teeth: u16, missing: u16) -> Self {
+        Self { teeth, missing }
+    }
+
+    /// Dientes físicos por vuelta
+    pub fn real_teeth(&self) -> u16 {
+        self.teeth - self.missing
+    }
+
+    /// Grados entre dientes consecutivos
+    pub fn tooth_spacing_deg(&self) -> f32 {
+        360.0 / self.teeth as f32
+    }
+
+    /// Relación periodo/periodo_anterior a partir de la cual se considera hueco.
+    /// El hueco dura (M + 1) periodos; se usa el punto medio entre 1 y M + 1
+    /// para tolerar aceleración (60-2 => 2.0, 36-1 => 1.5).
+    pub fn gap_ratio(&self) -> f32 {
+        1.0 + self.missing as f32 * 0.5
+    }
+
+    fn is_gap(&self, periods: &[u32]) -> bool {
+        match periods {
+            [.., previous, last] => *last as f32 > *previous as f32 * self.gap_ratio(),
+            _ => false,
+        }
+    }
+}
+
+impl TriggerPattern for MissingToothWheel {
+    fn tooth_count(&self) -> usize {
+        self.real_teeth() as usize
+    }
+
+    fn tooth_angle_deg(&self, tooth: usize) -> f32 {
+        tooth as f32 * self.tooth_spacing_deg()
+    }
+
+    /// El primer diente después del hueco es el único identificable
+    fn identify(&self, periods: &[u32]) -> Option<usize> {
+        if self.is_gap(periods) { Some(0) } else { None }
+    }
+
+    /// El hueco debe llegar exactamente antes del diente 0
+    fn validate(&self, tooth: usize, periods: &[u32]) -> bool {
+        self.is_gap(periods) == (tooth == 0)
+    }
+}
+
+/// Arma un patrón a partir de una tabla fija de ángulos
+macro_rules! angle_table_pattern {
+    ($(#[$meta:meta])* $name:ident, $angles:expr, cycle: $cycle:expr, both_edges: $both:expr, history: $history:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+        pub struct $name;
+
+        impl $name {
+            pub const ANGLES: &'static [f32] = &$angles;
+        }
+
+        impl TriggerPattern for $name {
+            fn tooth_count(&self) -> usize {
+                Self::ANGLES.len()
+            }
+
+            fn tooth_angle_deg(&self, tooth: usize) -> f32 {
+                Self::ANGLES[tooth % Self::ANGLES.len()]
+            }
+
+            fn cycle_deg(&self) -> f32 {
+                $cycle
+            }
+
+            fn uses_both_edges(&self) -> bool {
+                $both
+            }
+
+            fn sync_history(&self) -> usize {
+                $history
+            }
+        }
+    };
+}
+
+angle_table_pattern!(
+    /// Nissan CAS 360 (SR20/KA24, 4 cilindros), señal de baja resolución.
+    /// Cuatro ventanas por ciclo de leva, una por cilindro, de anchos distintos
+    /// (32°, 24°, 16° y 8° de cigüeñal). Se usan ambos flancos de cada ventana;
+    /// la señal de 360 ranuras de alta resolución no se usa para sincronizar.
+    Nissan360,
+    [0.0, 32.0, 180.0, 204.0, 360.0, 376.0, 540.0, 548.0],
+    cycle: 720.0, both_edges: true, history: 4
+);
+
+angle_table_pattern!(
+    /// Subaru 36-2-2-2 (H4): 36 posiciones de 10° con tres huecos de dos dientes.
+    /// Dos de los huecos están separados por un solo diente, lo que identifica la vuelta.
+    Subaru36222,
+    [
+        0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0,
+        80.0, 90.0, 100.0, 110.0, 120.0, 130.0, 140.0, 150.0,
+        180.0,
+        210.0, 220.0, 230.0, 240.0, 250.0, 260.0, 270.0,
+        280.0, 290.0, 300.0, 310.0, 320.0, 330.0,
+    ],
+    cycle: 360.0, both_edges: false, history: 3
+);
+
+angle_table_pattern!(
+    /// Honda 12+1: 12 dientes cada 30° más un diente extra a 15° del diente 0
+    Honda12Plus1,
+    [0.0, 15.0, 30.0, 60.0, 90.0, 120.0, 150.0, 180.0, 210.0, 240.0, 270.0, 300.0, 330.0],
+    cycle: 360.0, both_edges: false, history: 3
+);
+
+angle_table_pattern!(
+    /// Mazda CAS 4+1 (Miata NA/NB en distribuidor): un diente por cilindro cada
+    /// 180° de cigüeñal más un diente de sincronía, todo en el ciclo de 720°
+    Mazda4Plus1,
+    [0.0, 90.0, 180.0, 360.0, 540.0],
+    cycle: 720.0, both_edges: false, history: 3
+);
+
+/// Secuencia de la rueda GM 24x del LS, bit 0 = muesca 0 (1 = flanco
+/// variable a 3°, 0 = a 12°). Cualquier ventana de 5 muescas consecutivas
+/// es única en la vuelta.
+const GM_24X_CODE: u32 = 0x0A33BE;
+/// Ángulo del flanco variable desde el flanco equiespaciado anterior
+const GM_24X_SHORT_DEG: f32 = 3.0;
+const GM_24X_LONG_DEG: f32 = 15.0 - GM_24X_SHORT_DEG;
+
+/// GM 24x (LS1/LS6, reluctor de cigüeñal): 24 flancos equiespaciados a 15°
+/// y entre cada par un flanco variable a 3° o a 12° según la secuencia de la
+/// rueda, por lo que se usan ambos flancos: diente 2i = flanco
+/// equiespaciado, diente 2i + 1 = flanco variable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Gm24x;
+
+impl TriggerPattern for Gm24x {
+    fn tooth_count(&self) -> usize {
+        48
+    }
+
+    fn tooth_angle_deg(&self, tooth: usize) -> f32 {
+        let slot = (tooth / 2) % 24;
+        let equal = slot as f32 * 15.0;
+        if tooth.is_multiple_of(2) {
+            equal
+        } else if GM_24X_CODE & (1 << slot) != 0 {
+            equal + GM_24X_SHORT_DEG
+        } else {
+            equal + GM_24X_LONG_DEG
+        }
+    }
+
+    fn uses_both_edges(&self) -> bool {
+        true
+    }
+
+    /// 5 muescas completas = 10 flancos, más el periodo de referencia
+    fn sync_history(&self) -> usize {
+        11
+    }
+}
@@ -0,0 +1,109 @@
+use ecu_traits::engine_io::AnalogSensor;
+use ecu_traits::mock::{MockAnalogInput, MockError};
+use engine_core::analog::{
+    CalibratedSensor, Converter, LinearConverter, SteinhartHart, Thermistor, ThermistorModel, DEFAULT_MAP,
+    DEFAULT_TPS, GM_THERMISTOR,
+};
+use engine_core::tables::Table2D;
+
+fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "esperado {} ± {}, se obtuvo {}",
+        expected,
+        tolerance,
+        actual
+    );
+}
+
+#[test]
+fn test_conversion_lineal() {
+    assert_close(DEFAULT_TPS.convert(0.5), 0.0, 1e-4);
+    assert_close(DEFAULT_TPS.convert(4.5), 100.0, 1e-4);
+    assert_close(DEFAULT_TPS.convert(1.5), 25.0, 1e-4);
+
+    assert_close(DEFAULT_MAP.convert(0.4), 20.0, 1e-3);
+    assert_close(DEFAULT_MAP.convert(4.65), 250.0, 1e-3);
+
+    // Fuera de los puntos se extrapola
+    assert_close(DEFAULT_TPS.convert(0.0), -12.5, 1e-4);
+
+    // Una calibración degenerada no divide entre cero
+    let flat = LinearConverter::new(1.0, 7.0, 1.0, 9.0);
+    assert_eq!(flat.convert(3.0), 7.0);
+}
+
+#[test]
+fn test_steinhart_hart_desde_puntos() {
+    let points = [(-40.0, 100_700.0), (30.0, 2_238.0), (100.0, 177.0)];
+    let model = SteinhartHart::from_points(points);
+
+    // Pasa por los tres puntos de calibración
+    for (celsius, ohms) in points {
+        assert_close(model.temperature_c(ohms), celsius, 0.05);
+    }
+
+    // Coincide con los coeficientes por defecto y con puntos intermedios de la hoja de datos
+    assert_close(model.temperature_c(5_670.0), GM_THERMISTOR.temperature_c(5_670.0), 0.05);
+    assert_close(GM_THERMISTOR.temperature_c(5_670.0), 10.0, 1.0);
+    assert_close(GM_THERMISTOR.temperature_c(467.0), 70.0, 1.0);
+}
+
+#[test]
+fn test_termistor_en_divisor() {
+    let sensor = Thermistor::new(GM_THERMISTOR, 2490.0, 5.0);
+
+    // Mitad de la alimentación: resistencia igual al pull-up
+    assert_close(sensor.resistance_ohms(2.5), 2490.0, 0.5);
+
+    // Voltaje que da 2238 Ω (30 °C)
+    let volts = 5.0 * 2238.0 / (2238.0 + 2490.0);
+    assert_close(sensor.convert(volts), 30.0, 0.1);
+
+    // Más frío: más resistencia, más voltaje
+    assert!(sensor.convert(4.0) < sensor.convert(1.0));
+
+    // Circuito abierto
+    assert_eq!(sensor.resistance_ohms(5.0), f32::INFINITY);
+}
+
+#[test]
+fn test_curvas_por_tabla() {
+    // Sensor no lineal calibrado por puntos (voltaje -> unidades)
+    let curve = Table2D::new([0.5, 2.0, 4.5], [0.0, 80.0, 100.0]);
+    assert_close(curve.convert(1.25), 40.0, 1e-4);
+
+    // Termistor con la curva de la hoja de datos (Ω -> °C)
+    let table = Table2D::new([177.0, 2_238.0, 100_700.0], [100.0, 30.0, -40.0]);
+    let sensor = Thermistor::new(table, 2490.0, 5.0);
+    let volts = 5.0 * 2238.0 / (2238.0 + 2490.0);
+    assert_close(sensor.convert(volts), 30.0, 0.1);
+}
+
+#[test]
+fn test_sensor_calibrado() {
+    let input = MockAnalogInput::new([0.5, 2.5, 4.5]);
+    let mut tps = CalibratedSensor::new(input, DEFAULT_TPS);
+
+    assert_close(tps.read().unwrap(), 0.0, 1e-4);
+    assert_close(tps.read().unwrap(), 50.0, 1e-4);
+    assert_close(tps.read().unwrap(), 100.0, 1e-4);
+    assert_eq!(tps.last_volts(), 4.5);
+
+    // Recalibración en caliente
+    tps.set_converter(LinearConverter::new(0.5, 0.0, 4.5, 1.0));
+    assert_close(tps.read().unwrap(), 1.0, 1e-4);
+}
+
+#[test]
+fn test_falla_de_lectura_se_propaga() {
+    let mut map = CalibratedSensor::new(MockAnalogInput::new([2.0]), DEFAULT_MAP);
+    map.input_mut().faults().fail_next(1);
+
+    assert_eq!(map.read(), Err(MockError::Injected));
+    // La última lectura válida no cambia con la falla
+    assert_eq!(map.last_volts(), 0.0);
+
+    assert!(map.read().is_ok());
+    assert_eq!(map.last_volts(), 2.0);
+}
@@ -0,0 +1,88 @@
+use engine_core::angle_predictor::{AnglePredictor, AnglePredictorConfig};
+
+const TOOTH_DEG: f32 = 10.0;
+
+/// Motor con aceleración angular constante: θ(t) = ω0·t + ½·α·t².
+/// Regresa el tiempo (µs) en que se alcanza `angle` (grados acumulados).
+fn time_at(angle: f64, w0: f64, accel: f64) -> f64 {
+    if accel == 0.0 {
+        angle / w0
+    } else {
+        (-w0 + (w0 * w0 + 2.0 * accel * angle).sqrt()) / accel
+    }
+}
+
+/// Alimenta `teeth` dientes y regresa el error de predicción (µs) del
+/// siguiente diente visto desde el último
+fn prediction_error(config: AnglePredictorConfig, w0: f64, accel: f64, teeth: u32) -> f64 {
+    let mut predictor = AnglePredictor::new(config);
+    for i in 0..teeth {
+        let angle = i as f64 * TOOTH_DEG as f64;
+        let t = time_at(angle, w0, accel);
+        predictor.on_tooth(t as u32, (angle % config.cycle_deg as f64) as f32);
+    }
+
+    let next_angle = teeth as f64 * TOOTH_DEG as f64 + 5.0;
+    let real = time_at(next_angle, w0, accel);
+    let predicted = predictor.predict_time_of_angle((next_angle % config.cycle_deg as f64) as f32).unwrap();
+    (predicted as f64 - real).abs()
+}
+
+#[test]
+fn test_rpm_constantes() {
+    let mut predictor = AnglePredictor::new(AnglePredictorConfig::default());
+    assert!(!predictor.is_valid());
+    assert_eq!(predictor.predict_time_of_angle(90.0), None);
+
+    // 3000 RPM = 0.018 grados/µs; un diente cada 10° = 555.5 µs
+    let w = 3000.0 * 360.0 / 60e6;
+    for i in 0..40u32 {
+        let angle = i as f32 * TOOTH_DEG;
+        predictor.on_tooth((angle as f64 / w) as u32, angle % 360.0);
+    }
+    assert!((predictor.rpm() - 3000.0).abs() < 10.0);
+    assert!((predictor.smoothed_rpm() - 3000.0).abs() < 10.0);
+    assert!(prediction_error(AnglePredictorConfig::default(), w, 0.0, 40) <= 2.0);
+}
+
+#[test]
+fn test_compensacion_de_aceleracion() {
+    // Aceleración fuerte (arranque/WOT en primera): de 1000 RPM, +0.5 grados/ms²
+    let w0 = 1000.0 * 360.0 / 60e6;
+    let accel = 5e-7;
+
+    let with = AnglePredictorConfig { acceleration_compensation: true, ..Default::default() };
+    let without = AnglePredictorConfig { acceleration_compensation: false, ..Default::default() };
+
+    let err_with = prediction_error(with, w0, accel, 30);
+    let err_without = prediction_error(without, w0, accel, 30);
+    assert!(err_with < err_without / 4.0, "con {} / sin {}", err_with, err_without);
+    assert!(err_with < 3.0);
+}
+
+#[test]
+fn test_prediccion_mas_alla_del_ciclo() {
+    // Dominio de 720°: el ángulo pedido es menor al del último diente (ya dio la vuelta)
+    let config = AnglePredictorConfig { cycle_deg: 720.0, acceleration_compensation: false, rpm_alpha: 1.0 };
+    let mut predictor = AnglePredictor::new(config);
+    for (i, angle) in [690.0, 700.0, 710.0].iter().enumerate() {
+        predictor.on_tooth(1_000 + i as u32 * 1_000, *angle);
+    }
+    // 710° -> 20° = 30° = 3000 µs
+    assert_eq!(predictor.predict_time_of_angle(20.0), Some(6_000));
+    assert!((predictor.angle_at(4_500) - 5.0).abs() < 0.01);
+}
+
+#[test]
+fn test_contador_que_da_la_vuelta() {
+    let mut predictor = AnglePredictor::new(AnglePredictorConfig::default());
+    let base = u32::MAX - 1_500;
+    for i in 0..4u32 {
+        predictor.on_tooth(base.wrapping_add(i * 1_000), i as f32 * TOOTH_DEG);
+    }
+    // Último diente en base + 3000 (ya dio la vuelta), 10° más = 1000 µs
+    assert_eq!(predictor.predict_time_of_angle(40.0), Some(base.wrapping_add(4_000)));
+
+    predictor.reset();
+    assert!(!predictor.is_valid());
+}
@@ -0,0 +1,163 @@
+use ecu_traits::mock::MockAnalogInput;
+use engine_core::battery::{
+    BatteryConfig, BatteryMonitor, BatteryStatus, DwellCurve, InjectorDeadTime, SupplyVoltage,
+};
+use engine_core::diagnostics::{DiagnosticLog, FaultCode, FaultKind, SensorId};
+
+const DIVIDER: f32 = 6.0;
+
+fn config() -> BatteryConfig {
+    BatteryConfig {
+        divider_ratio: DIVIDER,
+        low_volts: 11.0,
+        high_volts: 15.5,
+        max_safe_volts: 18.0,
+        limit_hysteresis_volts: 1.0,
+        limit_hold_us: 500_000,
+        debounce_us: 1_000_000,
+    }
+}
+
+fn pin(volts: f32) -> f32 {
+    volts / DIVIDER
+}
+
+#[test]
+fn test_divisor_y_publicacion() {
+    let input = MockAnalogInput::new([pin(13.8)]);
+    let mut battery = BatteryMonitor::new(input, config());
+    let mut diag: DiagnosticLog<4> = DiagnosticLog::new();
+    let supply = SupplyVoltage::default();
+
+    assert_eq!(battery.update(0, &mut diag, &supply), BatteryStatus::Normal);
+    assert!((supply.volts() - 13.8).abs() < 1e-4);
+    assert!(!supply.outputs_limited());
+}
+
+#[test]
+fn test_bajo_voltaje_con_debounce() {
+    let input = MockAnalogInput::new([pin(13.0), pin(9.5)]);
+    let mut battery = BatteryMonitor::new(input, config());
+    let mut diag: DiagnosticLog<4> = DiagnosticLog::new();
+    let supply = SupplyVoltage::default();
+    let low = FaultCode::new(SensorId::Battery, FaultKind::RangeLow);
+
+    battery.update(0, &mut diag, &supply);
+
+    // La caída del arranque es corta: se publica pero no es falla todavía
+    assert_eq!(battery.update(100_000, &mut diag, &supply), BatteryStatus::Normal);
+    assert!((supply.volts() - 9.5).abs() < 1e-4);
+
+    assert_eq!(battery.update(1_200_000, &mut diag, &supply), BatteryStatus::Low);
+    assert!(diag.is_active(low));
+    assert_eq!(low.obd_code(), 562);
+
+    battery.input_mut().set_volts(pin(13.0));
+    battery.update(1_300_000, &mut diag, &supply);
+    assert_eq!(battery.update(2_400_000, &mut diag, &supply), BatteryStatus::Normal);
+    assert!(!diag.is_active(low));
+}
+
+#[test]
+fn test_sobrevoltaje_limita_salidas_de_inmediato() {
+    let input = MockAnalogInput::new([pin(13.5), pin(19.0)]);
+    let mut battery = BatteryMonitor::new(input, config());
+    let mut diag: DiagnosticLog<4> = DiagnosticLog::new();
+    let supply = SupplyVoltage::default();
+    let dead_time = InjectorDeadTime::new([8.0, 14.0], [1500.0, 900.0]);
+    let dwell = DwellCurve::new([8.0, 14.0], [5000.0, 3000.0]);
+
+    battery.update(0, &mut diag, &supply);
+    assert!(dead_time.pulse_width_us(3000, &supply) > 3000);
+
+    battery.update(1_000, &mut diag, &supply);
+    assert!(supply.outputs_limited());
+    assert!(battery.outputs_limited());
+    assert_eq!(dead_time.pulse_width_us(3000, &supply), 0);
+    assert_eq!(dwell.dwell_us(&supply), 0);
+    // El diagnóstico sí espera el debounce
+    assert_eq!(battery.status(), BatteryStatus::Normal);
+
+    battery.update(1_100_000, &mut diag, &supply);
+    assert_eq!(battery.status(), BatteryStatus::High);
+    assert!(diag.is_active(FaultCode::new(SensorId::Battery, FaultKind::RangeHigh)));
+}
+
+#[test]
+fn test_limite_con_histeresis_y_tiempo() {
+    let input = MockAnalogInput::new([pin(19.0)]);
+    let mut battery = BatteryMonitor::new(input, config());
+    let mut diag: DiagnosticLog<4> = DiagnosticLog::new();
+    let supply = SupplyVoltage::default();
+
+    battery.update(0, &mut diag, &supply);
+    assert!(supply.outputs_limited());
+
+    // Ruido alrededor de 18 V: las salidas siguen limitadas
+    let mut now = 0;
+    for volts in [17.9, 18.1, 17.8, 17.95, 18.05, 17.5, 17.2] {
+        now += 100_000;
+        battery.input_mut().set_volts(pin(volts));
+        battery.update(now, &mut diag, &supply);
+        assert!(supply.outputs_limited(), "{} V", volts);
+    }
+
+    // Debajo de la histéresis tiene que sostenerse el tiempo completo
+    battery.input_mut().set_volts(pin(16.5));
+    now += 100_000;
+    battery.update(now, &mut diag, &supply);
+    battery.update(now + 400_000, &mut diag, &supply);
+    assert!(supply.outputs_limited());
+
+    // Un pico reinicia la espera
+    battery.input_mut().set_volts(pin(17.5));
+    battery.update(now + 450_000, &mut diag, &supply);
+    battery.input_mut().set_volts(pin(16.5));
+    battery.update(now + 500_000, &mut diag, &supply);
+    battery.update(now + 900_000, &mut diag, &supply);
+    assert!(supply.outputs_limited());
+
+    battery.update(now + 1_000_000, &mut diag, &supply);
+    assert!(!supply.outputs_limited());
+    assert!(!battery.outputs_limited());
+
+    // Volver a pasar el máximo limita de inmediato
+    battery.input_mut().set_volts(pin(18.2));
+    battery.update(now + 1_001_000, &mut diag, &supply);
+    assert!(supply.outputs_limited());
+}
+
+#[test]
+fn test_falla_de_lectura_conserva_voltaje() {
+    let input = MockAnalogInput::new([pin(12.5)]);
+    let mut battery = BatteryMonitor::new(input, BatteryConfig { debounce_us: 0, ..config() });
+    let mut diag: DiagnosticLog<4> = DiagnosticLog::new();
+    let supply = SupplyVoltage::default();
+
+    battery.update(0, &mut diag, &supply);
+    battery.input_mut().faults().set_failing(true);
+    battery.update(1_000, &mut diag, &supply);
+
+    assert!((supply.volts() - 12.5).abs() < 1e-4);
+    assert!(diag.is_active(FaultCode::new(SensorId::Battery, FaultKind::ReadError)));
+}
+
+#[test]
+fn test_tiempo_muerto_y_dwell_contra_voltaje() {
+    let supply = SupplyVoltage::new(11.0);
+    let dead_time = InjectorDeadTime::new([8.0, 11.0, 14.0], [1500.0, 1100.0, 900.0]);
+    let dwell = DwellCurve::new([8.0, 11.0, 14.0], [5000.0, 3800.0, 3000.0]);
+
+    assert_eq!(dead_time.dead_time_us(&supply), 1100);
+    assert_eq!(dead_time.pulse_width_us(2000, &supply), 3100);
+    assert_eq!(dead_time.pulse_width_us(0, &supply), 0);
+    assert_eq!(dwell.dwell_us(&supply), 3800);
+
+    // Con más voltaje el inyector abre antes y la bobina carga más rápido
+    supply.publish(12.5, false);
+    assert_eq!(dead_time.dead_time_us(&supply), 1000);
+    assert_eq!(dwell.dwell_us(&supply), 3400);
+
+    // 3000 RPM = 0.018 °/µs
+    assert!((dwell.dwell_deg(&supply, 0.018) - 61.2).abs() < 1e-3);
+}
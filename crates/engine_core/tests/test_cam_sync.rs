@@ -0,0 +1,120 @@
+use engine_core::cam_sync::{CamConfig, CamState, CamSync};
+use engine_core::crank_decoder::ToothEvent;
+
+const TOOTH_DEG: f32 = 10.0;
+
+/// Simula el motor girando: un diente de cigüeñal cada 10° y los flancos
+/// de leva en sus ángulos de ciclo. start_cycle_deg permite arrancar en
+/// cualquier punto del ciclo (el ECU no sabe en qué vuelta está).
+/// Regresa (ángulo de ciclo real, ángulo de ciclo reportado) por diente.
+fn spin<const K: usize>(
+    cam: &mut CamSync<K>,
+    cam_teeth: &[f32],
+    start_cycle_deg: f32,
+    cycles: u32,
+) -> Vec<(f32, f32)> {
+    let mut out = Vec::new();
+    let steps = (cycles as f32 * 720.0 / TOOTH_DEG) as u32;
+
+    for i in 0..steps {
+        let cycle_angle = (start_cycle_deg + i as f32 * TOOTH_DEG) % 720.0;
+        let crank_angle = cycle_angle % 360.0;
+
+        let event = ToothEvent { timestamp_us: i * 100, tooth: 0, angle_deg: crank_angle, period_us: 100 };
+        cam.on_crank_tooth(&event);
+        out.push((cycle_angle, cam.cycle_angle_deg(crank_angle)));
+
+        // Flancos de leva entre este diente y el siguiente
+        for &tooth in cam_teeth {
+            if tooth >= cycle_angle && tooth < cycle_angle + TOOTH_DEG {
+                cam.on_cam_edge(tooth % 360.0);
+            }
+        }
+    }
+    out
+}
+
+#[test]
+fn test_leva_un_diente() {
+    let config = CamConfig { teeth_cycle_deg: [630.0], tolerance_deg: 15.0, max_invalid_cycles: 2 };
+
+    // Arrancando en cualquiera de las dos vueltas se llega a la misma fase
+    for start in [0.0, 200.0, 400.0, 650.0] {
+        let mut cam = CamSync::new(config.clone());
+        assert_eq!(cam.cycle_deg(), 360.0);
+
+        let trace = spin(&mut cam, &[630.0], start, 3);
+        assert_eq!(cam.state(), CamState::Synced);
+        assert_eq!(cam.cycle_deg(), 720.0);
+        assert_eq!(cam.cam_errors(), 0);
+
+        // Después del primer ciclo el ángulo reportado es el real
+        for &(real, reported) in trace.iter().skip(72) {
+            assert_eq!(real, reported, "Arranque en {}", start);
+        }
+    }
+}
+
+#[test]
+fn test_leva_multidiente() {
+    // 4 dientes cada 180° (no dan fase) + 1 de sincronía en la primera vuelta
+    let teeth = [45.0, 60.0, 225.0, 405.0, 585.0];
+    let config = CamConfig { teeth_cycle_deg: teeth, tolerance_deg: 5.0, max_invalid_cycles: 2 };
+    let mut cam = CamSync::new(config);
+
+    let trace = spin(&mut cam, &teeth, 500.0, 3);
+    assert_eq!(cam.state(), CamState::Synced);
+    assert_eq!(cam.cam_errors(), 0);
+
+    for &(real, reported) in trace.iter().skip(72) {
+        assert_eq!(real, reported);
+    }
+}
+
+#[test]
+fn test_caida_a_360_sin_perder_cigueñal() {
+    let config = CamConfig { teeth_cycle_deg: [90.0], tolerance_deg: 15.0, max_invalid_cycles: 2 };
+    let mut cam = CamSync::new(config);
+
+    spin(&mut cam, &[90.0], 0.0, 2);
+    assert!(cam.phase_known());
+
+    // El sensor de leva se desconecta: tras 2 ciclos inválidos se pasa a 360°
+    let trace = spin(&mut cam, &[], 0.0, 3);
+    assert_eq!(cam.state(), CamState::Fallback);
+    assert_eq!(cam.cycle_deg(), 360.0);
+    assert!(cam.cam_errors() >= 2);
+
+    // El ángulo sigue disponible (módulo 360) con el cigüeñal
+    let &(real, reported) = trace.last().unwrap();
+    assert_eq!(real % 360.0, reported);
+
+    // Al regresar la señal se recupera la fase
+    spin(&mut cam, &[90.0], 0.0, 1);
+    assert!(cam.phase_known());
+}
+
+#[test]
+fn test_flanco_fuera_de_lugar() {
+    let config = CamConfig { teeth_cycle_deg: [90.0], tolerance_deg: 10.0, max_invalid_cycles: 3 };
+    let mut cam = CamSync::new(config);
+    spin(&mut cam, &[90.0], 0.0, 2);
+
+    // Ruido a 250°: se cuenta como error pero la fase se mantiene
+    spin(&mut cam, &[90.0, 250.0], 0.0, 1);
+    assert_eq!(cam.state(), CamState::Synced);
+    assert_eq!(cam.cam_errors(), 1);
+}
+
+#[test]
+fn test_correccion_de_fase() {
+    let config = CamConfig { teeth_cycle_deg: [90.0], tolerance_deg: 10.0, max_invalid_cycles: 3 };
+    let mut cam = CamSync::new(config);
+    spin(&mut cam, &[90.0], 0.0, 2);
+
+    // La leva aparece en la otra vuelta (ej. salto de cadena): la leva manda
+    let trace = spin(&mut cam, &[450.0], 0.0, 2);
+    assert!(cam.cam_errors() >= 1);
+    let &(real, reported) = trace.last().unwrap();
+    assert_eq!((real + 360.0) % 720.0, reported);
+}
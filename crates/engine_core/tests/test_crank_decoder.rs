@@ -0,0 +1,137 @@
+use engine_core::crank_decoder::{CrankDecoder, MissingToothWheel, SyncState, ToothEvent};
+
+/// Genera las marcas de tiempo de los dientes físicos de una rueda N-M.
+/// La posición 0 es el primer diente después del hueco.
+/// rpm_start/rpm_end: rampa lineal de RPM a lo largo de las vueltas
+fn edges(wheel: MissingToothWheel, revs: u32, rpm_start: f32, rpm_end: f32, start_pos: u16) -> Vec<u32> {
+    let total = (revs * wheel.teeth as u32) as usize;
+    let mut out = Vec::new();
+    let mut t = 1_000.0f64;
+
+    for i in 0..total {
+        let pos = ((start_pos as usize + i) % wheel.teeth as usize) as u16;
+        let rpm = rpm_start + (rpm_end - rpm_start) * (i as f32 / total as f32);
+        if pos < wheel.real_teeth() {
+            out.push(t as u32);
+        }
+        // Tiempo hasta la siguiente posición
+        t += 60_000_000.0 / (rpm as f64 * wheel.teeth as f64);
+    }
+    out
+}
+
+fn run(decoder: &mut CrankDecoder, stream: &[u32]) -> Vec<ToothEvent> {
+    stream.iter().filter_map(|&t| decoder.on_tooth(t)).collect()
+}
+
+#[test]
+fn test_sincronia_ruedas_comunes() {
+    for (teeth, missing) in [(60, 2), (36, 1), (24, 1), (12, 1)] {
+        let wheel = MissingToothWheel::new(teeth, missing);
+        let mut decoder = CrankDecoder::new(wheel, 0.0);
+
+        // Arrancamos a la mitad de la rueda: sin sincronía hasta el hueco
+        let stream = edges(wheel, 3, 1000.0, 1000.0, teeth / 2);
+        let events = run(&mut decoder, &stream);
+
+        assert_eq!(decoder.state(), SyncState::Synced, "Rueda {}-{}", teeth, missing);
+        assert_eq!(decoder.sync_losses(), 0);
+
+        // El primer evento es el diente 0 y después son consecutivos
+        assert_eq!(events[0].tooth, 0);
+        for pair in events.windows(2) {
+            let expected = (pair[0].tooth + 1) % wheel.real_teeth();
+            assert_eq!(pair[1].tooth, expected, "Rueda {}-{}", teeth, missing);
+        }
+    }
+}
+
+#[test]
+fn test_angulo_por_diente() {
+    let wheel = MissingToothWheel::new(60, 2);
+    // Primer diente después del hueco a 114° DPMS
+    let mut decoder = CrankDecoder::new(wheel, 114.0);
+    let events = run(&mut decoder, &edges(wheel, 2, 3000.0, 3000.0, 10));
+
+    for event in events {
+        let expected = (114.0 + event.tooth as f32 * 6.0) % 360.0;
+        assert!((event.angle_deg - expected).abs() < 0.001);
+    }
+    assert_eq!(decoder.tooth_angle_deg(41), 0.0);
+}
+
+#[test]
+fn test_sincronia_con_aceleracion() {
+    // Arranque: de 200 a 3000 RPM en 5 vueltas de una 36-1
+    let wheel = MissingToothWheel::new(36, 1);
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    run(&mut decoder, &edges(wheel, 5, 200.0, 3000.0, 0));
+
+    assert_eq!(decoder.state(), SyncState::Synced);
+    assert_eq!(decoder.sync_losses(), 0);
+    assert!(decoder.revolutions() >= 3);
+
+    // Desaceleración fuerte en una 60-2
+    let wheel = MissingToothWheel::new(60, 2);
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    run(&mut decoder, &edges(wheel, 5, 6000.0, 1500.0, 0));
+    assert_eq!(decoder.state(), SyncState::Synced);
+    assert_eq!(decoder.sync_losses(), 0);
+}
+
+#[test]
+fn test_perdida_y_recuperacion() {
+    let wheel = MissingToothWheel::new(36, 1);
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    let mut stream = edges(wheel, 4, 1500.0, 1500.0, 0);
+
+    // Se pierde un diente a la mitad de la segunda vuelta: parece un hueco fuera de lugar
+    stream.remove(35 + 17);
+
+    let mut states = Vec::new();
+    for &t in &stream {
+        decoder.on_tooth(t);
+        states.push(decoder.state());
+    }
+
+    assert!(states.contains(&SyncState::Lost));
+    assert_eq!(decoder.sync_losses(), 1);
+    // Se recupera en el siguiente hueco real
+    assert_eq!(decoder.state(), SyncState::Synced);
+}
+
+#[test]
+fn test_hueco_faltante() {
+    // Un diente extra donde debería estar el hueco (ej. rueda equivocada)
+    let wheel = MissingToothWheel::new(36, 1);
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    // Arranca en la posición 20: sincroniza en el índice 15, el siguiente hueco
+    // termina en el índice 50
+    let mut stream = edges(wheel, 3, 1500.0, 1500.0, 20);
+    let gap_start = stream[49];
+    let gap_end = stream[50];
+    stream.insert(50, gap_start + (gap_end - gap_start) / 2);
+
+    run(&mut decoder, &stream[..51]);
+    assert_eq!(decoder.state(), SyncState::Lost);
+    assert_eq!(decoder.sync_losses(), 1);
+
+    decoder.reset();
+    assert_eq!(decoder.state(), SyncState::NoSync);
+}
+
+#[test]
+fn test_contador_que_da_la_vuelta() {
+    // El contador de µs de 32 bits da la vuelta cada ~71 minutos
+    let wheel = MissingToothWheel::new(12, 1);
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    let offset = u32::MAX - 20_000;
+    let stream: Vec<u32> = edges(wheel, 3, 1000.0, 1000.0, 0)
+        .into_iter()
+        .map(|t| t.wrapping_add(offset))
+        .collect();
+
+    let events = run(&mut decoder, &stream);
+    assert_eq!(decoder.state(), SyncState::Synced);
+    assert!(events.iter().all(|e| e.period_us < 12_000));
+}
@@ -0,0 +1,119 @@
+use ecu_traits::engine_io::{CapturedEdge, EdgeCapture};
+use engine_core::crank_decoder::{CrankDecoder, MissingToothWheel, SyncState};
+use engine_core::edge_buffer::EdgeBuffer;
+use engine_core::trigger_patterns::{Nissan360, TriggerPattern};
+
+fn edge(timestamp_us: u32, rising: bool) -> CapturedEdge {
+    CapturedEdge { timestamp_us, rising }
+}
+
+/// Captura de entrada simulada: entrega los flancos pendientes y puede
+/// reportar una sobre-captura
+struct FakeCapture {
+    pending: Vec<Result<CapturedEdge, ()>>,
+}
+
+impl EdgeCapture for FakeCapture {
+    type Error = ();
+
+    fn read_edge(&mut self) -> Result<Option<CapturedEdge>, Self::Error> {
+        if self.pending.is_empty() {
+            return Ok(None);
+        }
+        self.pending.remove(0).map(Some)
+    }
+}
+
+#[test]
+fn test_cola_circular() {
+    let mut buffer: EdgeBuffer<3> = EdgeBuffer::new();
+    assert!(buffer.is_empty());
+
+    // Damos varias vueltas al índice circular
+    for round in 0..5u32 {
+        assert!(buffer.push(edge(round * 10, true)));
+        assert!(buffer.push(edge(round * 10 + 1, false)));
+        assert_eq!(buffer.pop(), Some(edge(round * 10, true)));
+        assert_eq!(buffer.pop(), Some(edge(round * 10 + 1, false)));
+    }
+    assert_eq!(buffer.pop(), None);
+    assert_eq!(buffer.overruns(), 0);
+}
+
+#[test]
+fn test_cola_llena() {
+    let mut buffer: EdgeBuffer<2> = EdgeBuffer::new();
+    assert!(buffer.push(edge(1, true)));
+    assert!(buffer.push(edge(2, true)));
+    assert!(!buffer.push(edge(3, true)));
+    assert_eq!(buffer.overruns(), 1);
+
+    // Se conservan los más antiguos
+    let times: Vec<u32> = buffer.drain().map(|e| e.timestamp_us).collect();
+    assert_eq!(times, [1, 2]);
+
+    buffer.push(edge(4, true));
+    buffer.clear();
+    assert_eq!(buffer.len(), 0);
+}
+
+#[test]
+fn test_llenado_desde_hardware() {
+    let mut capture = FakeCapture {
+        pending: vec![Ok(edge(100, true)), Err(()), Ok(edge(300, true)), Ok(edge(400, true))],
+    };
+    let mut buffer: EdgeBuffer<8> = EdgeBuffer::new();
+
+    assert_eq!(buffer.fill_from(&mut capture), 3);
+    assert_eq!(buffer.overruns(), 1);
+    assert_eq!(buffer.len(), 3);
+    assert_eq!(buffer.fill_from(&mut capture), 0);
+}
+
+#[test]
+fn test_decodificador_consume_la_cola() {
+    // Rueda 12-1 a 1000 RPM: 5000 µs por posición; se capturan ambos flancos
+    let wheel = MissingToothWheel::new(12, 1);
+    let mut buffer: EdgeBuffer<64> = EdgeBuffer::new();
+    let mut t = 0u32;
+    for pos in 0..36u32 {
+        if pos % 12 < 11 {
+            buffer.push(edge(t, true));
+            buffer.push(edge(t + 2_000, false));
+        }
+        t += 5_000;
+    }
+
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    let mut events = Vec::new();
+    for captured in buffer.drain() {
+        events.extend(decoder.on_edge(captured));
+    }
+
+    // Los flancos de bajada se ignoran en una rueda de un solo flanco
+    assert_eq!(decoder.state(), SyncState::Synced);
+    assert_eq!(decoder.sync_losses(), 0);
+    assert!(events.iter().all(|e| e.timestamp_us % 5_000 == 0));
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn test_patron_de_ambos_flancos() {
+    // Nissan 360 usa subida y bajada de cada ventana
+    let pattern = Nissan360;
+    let us_per_deg = 100u32;
+    let mut decoder = CrankDecoder::new(pattern, 0.0);
+    let mut t = 0u32;
+    let mut events = 0;
+
+    for i in 0..pattern.tooth_count() * 3 {
+        let tooth = i % pattern.tooth_count();
+        t += (pattern.gap_deg(tooth) as u32) * us_per_deg;
+        // Dientes pares = subida (inicio de ventana), impares = bajada
+        if decoder.on_edge(edge(t, tooth.is_multiple_of(2))).is_some() {
+            events += 1;
+        }
+    }
+    assert_eq!(decoder.state(), SyncState::Synced);
+    assert!(events > pattern.tooth_count());
+}
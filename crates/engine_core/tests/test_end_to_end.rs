@@ -0,0 +1,171 @@
+use ecu_traits::engine_io::{IgnitionCoil, Injector};
+use ecu_traits::mock::{MockCoil, MockEdgeCapture, MockError, MockInjector, SimTimer};
+use engine_core::angle_predictor::{AnglePredictor, AnglePredictorConfig};
+use engine_core::crank_decoder::{CrankDecoder, MissingToothWheel, SyncState};
+use engine_core::edge_buffer::EdgeBuffer;
+use engine_core::scheduler::{AngleEvent, AngleScheduler, CompareAction, TimerBackend};
+use engine_core::signal_gen::{SignalGenConfig, SignalGenerator, SignalNoise, TriggerSignal};
+
+const INJ: u8 = 0;
+const COIL: u8 = 4;
+
+/// ECU completa sobre drivers simulados: captura -> decodificador ->
+/// predictor -> scheduler -> timer -> inyector y bobina
+struct Ecu {
+    capture: MockEdgeCapture<16>,
+    buffer: EdgeBuffer<16>,
+    decoder: CrankDecoder,
+    predictor: AnglePredictor,
+    scheduler: AngleScheduler<4>,
+    timer: SimTimer<8, 64>,
+    injector: MockInjector<32>,
+    coil: MockCoil<32>,
+    driver_errors: u32,
+}
+
+impl Ecu {
+    fn new() -> Self {
+        Ecu {
+            capture: MockEdgeCapture::new(),
+            buffer: EdgeBuffer::new(),
+            decoder: CrankDecoder::new(MissingToothWheel::new(36, 1), 0.0),
+            predictor: AnglePredictor::new(AnglePredictorConfig::default()),
+            scheduler: AngleScheduler::new(30.0),
+            timer: SimTimer::new(),
+            injector: MockInjector::new(),
+            coil: MockCoil::new(),
+            driver_errors: 0,
+        }
+    }
+
+    /// Ejecuta los compares hasta `until_us` sobre los drivers simulados
+    fn run_until(&mut self, until_us: u32) {
+        let Ecu { scheduler, timer, injector, coil, driver_errors, .. } = self;
+        timer.run_until(until_us, |timer, channel, action| {
+            let now = timer.now_us();
+            injector.set_now(now);
+            coil.set_now(now);
+            let result = match (channel, action) {
+                (INJ, CompareAction::Activate) => injector.open(),
+                (INJ, CompareAction::Deactivate) => injector.close(),
+                (COIL, CompareAction::Activate) => coil.start_dwell(),
+                (COIL, CompareAction::Deactivate) => coil.coil_fire(),
+                _ => Ok(()),
+            };
+            if result.is_err() {
+                *driver_errors += 1;
+            }
+            scheduler.on_compare(channel, timer);
+        });
+    }
+
+    /// Un flanco llega al hardware de captura
+    fn edge(&mut self, edge: ecu_traits::engine_io::CapturedEdge) {
+        self.run_until(edge.timestamp_us);
+        self.capture.push(edge);
+        self.buffer.fill_from(&mut self.capture);
+
+        for edge in self.buffer.drain() {
+            let Some(event) = self.decoder.on_edge(edge) else { continue };
+            self.predictor.on_tooth(event.timestamp_us, event.angle_deg);
+            self.scheduler.on_tooth(&self.predictor, &mut self.timer);
+
+            // Un evento de cada tipo por vuelta, pedido en el diente de referencia
+            if event.tooth == 0 && self.predictor.is_valid() {
+                let _ = self.scheduler.schedule(AngleEvent::injection(INJ, 90.0, 3_000), &self.predictor, &mut self.timer);
+                let _ = self.scheduler.schedule(AngleEvent::spark(COIL, 300.0, 340.0), &self.predictor, &mut self.timer);
+            }
+        }
+    }
+}
+
+fn generator(rpm: f32) -> SignalGenerator<MissingToothWheel, 0> {
+    let config = SignalGenConfig {
+        start_us: 1_000,
+        start_angle_deg: 0.0,
+        rpm,
+        cam_teeth_deg: [],
+        cam_tooth_width_deg: 0.0,
+        noise: SignalNoise::default(),
+    };
+    SignalGenerator::new(MissingToothWheel::new(36, 1), config)
+}
+
+fn run(ecu: &mut Ecu, generator: &mut SignalGenerator<MissingToothWheel, 0>, edges: usize) {
+    for edge in generator.by_ref().take(edges) {
+        if edge.signal == TriggerSignal::Crank {
+            ecu.edge(edge.captured());
+        }
+    }
+}
+
+#[test]
+fn test_inyeccion_y_chispa_de_punta_a_punta() {
+    let mut ecu = Ecu::new();
+    let mut generator = generator(3000.0);
+    // 10 vueltas (2 flancos por diente)
+    run(&mut ecu, &mut generator, 35 * 2 * 10);
+
+    assert_eq!(ecu.decoder.state(), SyncState::Synced);
+    assert_eq!(ecu.driver_errors, 0);
+
+    // 3000 RPM = 55.6 µs/grado
+    let us_per_deg = 60e6 / (3000.0 * 360.0);
+    let pulses = ecu.injector.pulses();
+    assert!(pulses.len() >= 8, "{} pulsos", pulses.len());
+    for pulse in pulses {
+        assert!(pulse.width_us().abs_diff(3_000) <= 2);
+    }
+
+    let sparks = ecu.coil.sparks();
+    assert!(sparks.len() >= 8);
+    let expected_dwell = (40.0 * us_per_deg) as u32;
+    for spark in sparks {
+        assert!(spark.dwell_us().abs_diff(expected_dwell) <= 5, "dwell {}", spark.dwell_us());
+    }
+    // Una chispa por vuelta
+    for pair in sparks.windows(2) {
+        assert!(pair[1].fire_us.wrapping_sub(pair[0].fire_us).abs_diff(20_000) <= 5);
+    }
+    assert_eq!(ecu.coil.fires_without_dwell(), 0);
+}
+
+#[test]
+fn test_fallas_inyectadas() {
+    let mut ecu = Ecu::new();
+    let mut generator = generator(3000.0);
+    run(&mut ecu, &mut generator, 35 * 2 * 4);
+    let pulses = ecu.injector.pulses().len();
+
+    // El inyector deja de responder: no hay pulsos nuevos y se cuentan los errores
+    ecu.injector.faults().set_failing(true);
+    run(&mut ecu, &mut generator, 35 * 2 * 3);
+    assert_eq!(ecu.injector.pulses().len(), pulses);
+    assert!(ecu.driver_errors >= 3);
+    assert!(ecu.injector.faults().injected() >= 3);
+
+    // Se recupera
+    ecu.injector.faults().set_failing(false);
+    run(&mut ecu, &mut generator, 35 * 2 * 3);
+    assert!(ecu.injector.pulses().len() > pulses);
+
+    // Una sobre-captura se reporta en la cola sin perder la sincronía
+    ecu.capture.faults().fail_next(1);
+    run(&mut ecu, &mut generator, 10);
+    assert_eq!(ecu.buffer.overruns(), 1);
+    assert_eq!(ecu.decoder.state(), SyncState::Synced);
+
+    // La bobina no carga una vez: ese disparo no produce chispa
+    let errors = ecu.driver_errors;
+    let sparks = ecu.coil.sparks().len();
+    ecu.coil.faults().fail_next(1);
+    run(&mut ecu, &mut generator, 35 * 2 * 2);
+    assert_eq!(ecu.driver_errors, errors + 1);
+    assert_eq!(ecu.coil.fires_without_dwell(), 1);
+    assert_eq!(ecu.coil.sparks().len(), sparks + 1);
+
+    // Llamada directa al driver
+    ecu.coil.faults().fail_next(1);
+    assert_eq!(ecu.coil.start_dwell(), Err(MockError::Injected));
+    assert!(!ecu.coil.is_dwelling());
+}
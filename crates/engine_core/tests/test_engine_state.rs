@@ -0,0 +1,197 @@
+use engine_core::engine_state::{EngineInputs, EngineState, EngineStateConfig, EngineStateMachine};
+
+fn config() -> EngineStateConfig {
+    EngineStateConfig {
+        cranking_rpm: 400.0,
+        cranking_hysteresis_rpm: 100.0,
+        stall_timeout_us: 500_000,
+        flood_clear_tps: 80.0,
+        overrun_max_tps: 1.0,
+        overrun_enter_rpm: 1800.0,
+        overrun_exit_rpm: 1300.0,
+    }
+}
+
+fn inputs(synced: bool, rpm: f32, tps: f32) -> EngineInputs {
+    EngineInputs { key_on: true, synced, rpm, tps }
+}
+
+/// Gira el motor (un diente cada 10 ms) y actualiza la máquina al final
+fn spin(engine: &mut EngineStateMachine, now: &mut u32, ms: u32, inputs: EngineInputs) -> Vec<(EngineState, EngineState)> {
+    let mut transitions = Vec::new();
+    for _ in 0..ms / 10 {
+        *now += 10_000;
+        engine.on_tooth(*now);
+        if let Some(t) = engine.update(inputs, *now) {
+            transitions.push((t.from, t.to));
+        }
+    }
+    transitions
+}
+
+#[test]
+fn test_arranque_y_marcha() {
+    let mut engine = EngineStateMachine::new(config());
+    let mut now = 0;
+
+    // Llave encendida sin girar: detenido
+    assert_eq!(engine.update(inputs(false, 0.0, 0.0), now), None);
+    assert_eq!(engine.state(), EngineState::Stopped);
+
+    // Motor de arranque sin sincronía
+    let t = spin(&mut engine, &mut now, 100, inputs(false, 0.0, 0.0));
+    assert_eq!(t, [(EngineState::Stopped, EngineState::Cranking)]);
+
+    // Sincronía pero debajo de las RPM de marcha
+    spin(&mut engine, &mut now, 100, inputs(true, 250.0, 0.0));
+    assert_eq!(engine.state(), EngineState::Cranking);
+
+    // Arranca
+    now += 10_000;
+    engine.on_tooth(now);
+    let t = engine.update(inputs(true, 900.0, 5.0), now).unwrap();
+    assert!(t.is_start());
+    assert_eq!(t.timestamp_us, now);
+    assert_eq!(engine.starts(), 1);
+    assert_eq!(engine.time_in_state_us(now + 1_000), 1_000);
+
+    // Histéresis: 350 RPM sigue en marcha, 250 vuelve a arranque
+    spin(&mut engine, &mut now, 50, inputs(true, 350.0, 5.0));
+    assert_eq!(engine.state(), EngineState::Running);
+    let t = spin(&mut engine, &mut now, 50, inputs(true, 250.0, 5.0));
+    assert_eq!(t, [(EngineState::Running, EngineState::Cranking)]);
+}
+
+#[test]
+fn test_motor_que_falla_vuelve_a_arranque() {
+    let mut engine = EngineStateMachine::new(config());
+    let mut now = 0;
+    spin(&mut engine, &mut now, 50, inputs(true, 250.0, 5.0));
+    spin(&mut engine, &mut now, 50, inputs(true, 800.0, 5.0));
+    assert_eq!(engine.state(), EngineState::Running);
+
+    // El motor tose y cae a velocidad de motor de arranque con sincronía:
+    // vuelven el enriquecimiento de arranque y la limpieza de ahogo
+    let t = spin(&mut engine, &mut now, 50, inputs(true, 220.0, 5.0));
+    assert_eq!(t, [(EngineState::Running, EngineState::Cranking)]);
+    assert!(!engine.state().cuts_fuel());
+    let t = spin(&mut engine, &mut now, 50, inputs(true, 220.0, 100.0));
+    assert_eq!(t, [(EngineState::Cranking, EngineState::FloodClear)]);
+
+    // Al volver a prender cuenta como un arranque nuevo
+    let t = spin(&mut engine, &mut now, 50, inputs(true, 800.0, 5.0));
+    assert_eq!(t, [(EngineState::FloodClear, EngineState::Running)]);
+    assert_eq!(engine.starts(), 2);
+}
+
+#[test]
+fn test_perdida_de_sincronia_en_marcha() {
+    let mut engine = EngineStateMachine::new(config());
+    let mut now = 0;
+    spin(&mut engine, &mut now, 50, inputs(true, 250.0, 5.0));
+    spin(&mut engine, &mut now, 50, inputs(true, 5000.0, 100.0));
+    assert_eq!(engine.state(), EngineState::Running);
+    assert_eq!(engine.starts(), 1);
+
+    // Pérdida de sincronía a fondo: no es limpieza de ahogo ni corta combustible
+    let t = spin(&mut engine, &mut now, 100, inputs(false, 0.0, 100.0));
+    assert!(t.is_empty());
+    assert!(!engine.state().cuts_fuel());
+
+    // Al recuperarla no se cuenta un arranque nuevo
+    let t = spin(&mut engine, &mut now, 50, inputs(true, 5000.0, 100.0));
+    assert!(t.is_empty());
+    assert_eq!(engine.starts(), 1);
+
+    // Sin sincronía tampoco entra el corte en desaceleración
+    let t = spin(&mut engine, &mut now, 50, inputs(false, 5000.0, 0.0));
+    assert!(t.is_empty());
+
+    // Si los dientes se detienen sí se apaga
+    now += 600_000;
+    let t = engine.update(inputs(false, 0.0, 100.0), now).unwrap();
+    assert!(t.is_stall());
+}
+
+#[test]
+fn test_limpieza_de_ahogo() {
+    let mut engine = EngineStateMachine::new(config());
+    let mut now = 0;
+
+    let t = spin(&mut engine, &mut now, 50, inputs(true, 200.0, 95.0));
+    assert_eq!(t, [(EngineState::Stopped, EngineState::FloodClear)]);
+    assert!(engine.state().is_cranking());
+    assert!(engine.state().cuts_fuel());
+
+    // Al soltar el acelerador vuelve a inyectar
+    let t = spin(&mut engine, &mut now, 20, inputs(true, 200.0, 10.0));
+    assert_eq!(t, [(EngineState::FloodClear, EngineState::Cranking)]);
+    assert!(!engine.state().cuts_fuel());
+}
+
+#[test]
+fn test_corte_en_desaceleracion() {
+    let mut engine = EngineStateMachine::new(config());
+    let mut now = 0;
+    spin(&mut engine, &mut now, 50, inputs(true, 3000.0, 30.0));
+    assert_eq!(engine.state(), EngineState::Running);
+
+    // Acelerador cerrado a 3000 RPM: corte
+    let t = spin(&mut engine, &mut now, 20, inputs(true, 3000.0, 0.0));
+    assert_eq!(t, [(EngineState::Running, EngineState::Overrun)]);
+    assert!(engine.state().is_running() && engine.state().cuts_fuel());
+
+    // Se mantiene hasta las RPM de salida
+    spin(&mut engine, &mut now, 20, inputs(true, 1500.0, 0.0));
+    assert_eq!(engine.state(), EngineState::Overrun);
+    spin(&mut engine, &mut now, 20, inputs(true, 1200.0, 0.0));
+    assert_eq!(engine.state(), EngineState::Running);
+
+    // Tocar el acelerador también sale del corte
+    spin(&mut engine, &mut now, 20, inputs(true, 2500.0, 0.0));
+    let t = spin(&mut engine, &mut now, 20, inputs(true, 2500.0, 5.0));
+    assert_eq!(t, [(EngineState::Overrun, EngineState::Running)]);
+}
+
+#[test]
+fn test_motor_apagado_y_llave() {
+    let mut engine = EngineStateMachine::new(config());
+    let mut now = 0;
+    spin(&mut engine, &mut now, 50, inputs(true, 800.0, 5.0));
+    assert_eq!(engine.state(), EngineState::Running);
+
+    // Sin dientes: sigue en marcha hasta el timeout
+    now += 400_000;
+    assert_eq!(engine.update(inputs(true, 800.0, 5.0), now), None);
+    now += 200_000;
+    let t = engine.update(inputs(false, 0.0, 5.0), now).unwrap();
+    assert!(t.is_stall());
+    assert_eq!(engine.stalls(), 1);
+
+    // Se queda apagado con la llave encendida hasta volver a girar
+    now += 1_000_000;
+    assert_eq!(engine.update(inputs(false, 0.0, 5.0), now), None);
+    let t = spin(&mut engine, &mut now, 20, inputs(false, 0.0, 5.0));
+    assert_eq!(t, [(EngineState::Stalled, EngineState::Cranking)]);
+
+    // Llave apagada: detenido desde cualquier estado
+    let off = EngineInputs { key_on: false, ..inputs(false, 0.0, 0.0) };
+    let t = engine.update(off, now).unwrap();
+    assert_eq!(t.to, EngineState::Stopped);
+    assert!(!t.is_stall());
+}
+
+#[test]
+fn test_contador_que_da_la_vuelta() {
+    let mut engine = EngineStateMachine::new(config());
+    let mut now = u32::MAX - 25_000;
+    engine.on_tooth(now);
+    engine.update(inputs(true, 800.0, 5.0), now);
+    assert_eq!(engine.state(), EngineState::Running);
+
+    // 300 ms después del último diente (con el contador ya reiniciado) sigue en marcha
+    now = now.wrapping_add(300_000);
+    assert_eq!(engine.update(inputs(true, 800.0, 5.0), now), None);
+    now = now.wrapping_add(300_000);
+    assert_eq!(engine.update(inputs(true, 800.0, 5.0), now).unwrap().to, EngineState::Stalled);
+}
@@ -0,0 +1,114 @@
+use ecu_traits::engine_io::AnalogInput;
+use ecu_traits::mock::MockAnalogInput;
+use engine_core::filters::{FilterChain, FilterConfig, FilteredInput, LagFilter, MedianFilter, RateLimiter};
+
+const PERIOD_US: u32 = 1_000;
+
+/// Ruido determinista en ±amplitude (LCG)
+fn noise(seed: &mut u32, amplitude: f32) -> f32 {
+    *seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+    ((*seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0) * amplitude
+}
+
+#[test]
+fn test_respuesta_al_escalon_de_primer_orden() {
+    let tau_us = 50_000;
+    let mut lag = LagFilter::new(tau_us);
+
+    assert_eq!(lag.apply(0.0, PERIOD_US), 0.0);
+    let mut out = 0.0;
+    for _ in 0..tau_us / PERIOD_US {
+        out = lag.apply(1.0, PERIOD_US);
+    }
+    // 63% en una constante de tiempo
+    assert!((out - 0.632).abs() < 0.01, "salida {}", out);
+
+    for _ in 0..4 * tau_us / PERIOD_US {
+        out = lag.apply(1.0, PERIOD_US);
+    }
+    assert!(out > 0.99);
+
+    // Sin historia la primera muestra pasa directo
+    lag.reset();
+    assert_eq!(lag.apply(3.0, PERIOD_US), 3.0);
+}
+
+#[test]
+fn test_mediana_rechaza_picos() {
+    let mut median = MedianFilter::new(5);
+    let mut out = Vec::new();
+    for sample in [1.0, 1.0, 1.0, 9.0, 1.0, 1.0, -7.0, 9.0, 1.0, 1.0] {
+        out.push(median.apply(sample));
+    }
+    assert!(out.iter().all(|&v| v == 1.0), "{:?}", out);
+
+    // Un escalón sostenido pasa con un atraso de media ventana
+    let mut median = MedianFilter::new(3);
+    let out: Vec<f32> = [0.0, 0.0, 2.0, 2.0, 2.0].iter().map(|&s| median.apply(s)).collect();
+    assert_eq!(out, vec![0.0, 0.0, 0.0, 2.0, 2.0]);
+}
+
+#[test]
+fn test_limite_de_velocidad() {
+    // 100 V/s a 1 ms: 0.1 V por muestra
+    let mut rate = RateLimiter::new(100.0);
+    rate.apply(0.0, PERIOD_US);
+    let out: Vec<f32> = (0..3).map(|_| rate.apply(5.0, PERIOD_US)).collect();
+    assert!((out[0] - 0.1).abs() < 1e-5);
+    assert!((out[2] - 0.3).abs() < 1e-5);
+
+    assert!((rate.apply(0.0, PERIOD_US) - 0.2).abs() < 1e-5);
+}
+
+#[test]
+fn test_sobremuestreo_promedia() {
+    let input = MockAnalogInput::new([1.0, 2.0, 3.0, 6.0, 2.0, 2.0, 2.0, 2.0]);
+    let config = FilterConfig { oversample: 4, ..FilterConfig::none(PERIOD_US) };
+    let mut filtered = FilteredInput::new(input, config);
+
+    assert_eq!(filtered.read_volts().unwrap(), 3.0);
+    assert_eq!(filtered.read_volts().unwrap(), 2.0);
+    assert_eq!(filtered.input_mut().reads(), 8);
+}
+
+/// Error máximo contra 2.5 V de la cadena con 2.5 V ± 0.2 V de ruido y un pico de 5 V cada 50 muestras
+fn max_error(config: FilterConfig) -> f32 {
+    let mut chain = FilterChain::new(config);
+    let mut seed = 1;
+    let mut max_error: f32 = 0.0;
+    for i in 0..1000 {
+        let sample = if i % 50 == 25 { 5.0 } else { 2.5 + noise(&mut seed, 0.2) };
+        let out = chain.apply(sample, PERIOD_US);
+        if i > 100 {
+            max_error = max_error.max((out - 2.5).abs());
+        }
+    }
+    max_error
+}
+
+#[test]
+fn test_rechazo_de_ruido_de_la_cadena() {
+    let config = FilterConfig {
+        oversample: 1,
+        median_window: 5,
+        lag_tau_us: 20_000,
+        rate_limit_per_s: 0.0,
+        sample_period_us: PERIOD_US,
+    };
+    let filtered = max_error(config);
+    assert!(filtered < 0.1, "error máximo {}", filtered);
+
+    // Sin la mediana los picos pasan al primer orden
+    let without_median = max_error(FilterConfig { median_window: 1, ..config });
+    assert!(without_median > 0.1, "error máximo sin mediana {}", without_median);
+}
+
+#[test]
+fn test_falla_del_canal_se_propaga() {
+    let mut input = MockAnalogInput::new([1.0]);
+    input.faults().fail_next(1);
+    let mut filtered = FilteredInput::new(input, FilterConfig { oversample: 4, ..FilterConfig::none(PERIOD_US) });
+
+    assert!(filtered.read_volts().is_err());
+    assert_eq!(filtered.read_volts().unwrap(), 1.0);
+}
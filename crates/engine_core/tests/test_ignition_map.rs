@@ -0,0 +1,91 @@
+use engine_core::ignition_map::{IgnitionConfig, IgnitionConfigError, IgnitionMode, SparkEvent};
+
+#[test]
+fn test_coil_on_plug_secuencial() {
+    // 4 cilindros, orden 1-3-4-2, una bobina por cilindro
+    let config = IgnitionConfig::even_fire(IgnitionMode::CoilOnPlug, [1, 3, 4, 2]);
+    assert!(config.validate(4).is_ok());
+    assert!(config.requires_cam_sync());
+    assert_eq!(config.cycle_deg(), 720.0);
+
+    let mut events = [SparkEvent { cylinder: 0, output: 0, tdc_deg: 0.0 }; 4];
+    let mut count = 0;
+    for (slot, event) in events.iter_mut().zip(config.events()) {
+        *slot = event;
+        count += 1;
+    }
+    assert_eq!(count, 4);
+
+    // Cada cilindro dispara su propia salida cada 180° del ciclo de 720°
+    assert_eq!(events[0], SparkEvent { cylinder: 1, output: 0, tdc_deg: 0.0 });
+    assert_eq!(events[1], SparkEvent { cylinder: 3, output: 2, tdc_deg: 180.0 });
+    assert_eq!(events[2], SparkEvent { cylinder: 4, output: 3, tdc_deg: 360.0 });
+    assert_eq!(events[3], SparkEvent { cylinder: 2, output: 1, tdc_deg: 540.0 });
+}
+
+#[test]
+fn test_chispa_perdida_pares() {
+    // 6 cilindros, orden 1-5-3-6-2-4: gemelos (1,6) (5,2) (3,4)
+    let config = IgnitionConfig::even_fire(IgnitionMode::WastedSpark, [1, 5, 3, 6, 2, 4]);
+    assert!(config.validate(4).is_ok());
+    assert!(!config.requires_cam_sync());
+
+    assert_eq!(config.output_for_cylinder(1), config.output_for_cylinder(6));
+    assert_eq!(config.output_for_cylinder(5), config.output_for_cylinder(2));
+    assert_eq!(config.output_for_cylinder(3), config.output_for_cylinder(4));
+
+    // Solo 3 eventos cada 360°, uno por par
+    let mut count = 0;
+    let mut last_tdc = -1.0;
+    for event in config.events() {
+        assert!(event.tdc_deg < 360.0);
+        assert!(event.tdc_deg > last_tdc);
+        last_tdc = event.tdc_deg;
+        count += 1;
+    }
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn test_distribuidor_una_salida() {
+    let config = IgnitionConfig::even_fire(IgnitionMode::Distributor, [1, 3, 4, 2]);
+    assert!(config.validate(1).is_ok());
+
+    // Una sola bobina dispara cada 180° (2 eventos por vuelta de cigüeñal)
+    let mut count = 0;
+    for event in config.events() {
+        assert_eq!(event.output, 0);
+        count += 1;
+    }
+    assert_eq!(count, 2);
+}
+
+#[test]
+fn test_configuracion_manual_impar() {
+    // V-twin a 90° con PMS desparejos: se configura sin tocar código
+    let config = IgnitionConfig {
+        mode: IgnitionMode::CoilOnPlug,
+        firing_order: [1, 2],
+        tdc_offsets: [0.0, 270.0],
+        coil_outputs: [2, 3],
+    };
+    assert!(config.validate(4).is_ok());
+
+    let mut events = config.events();
+    assert_eq!(events.next(), Some(SparkEvent { cylinder: 1, output: 2, tdc_deg: 0.0 }));
+    assert_eq!(events.next(), Some(SparkEvent { cylinder: 2, output: 3, tdc_deg: 270.0 }));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn test_validacion_errores() {
+    let repetido = IgnitionConfig::even_fire(IgnitionMode::CoilOnPlug, [1, 3, 3, 2]);
+    assert_eq!(repetido.validate(4), Err(IgnitionConfigError::InvalidFiringOrder));
+
+    let impar = IgnitionConfig::even_fire(IgnitionMode::WastedSpark, [1, 2, 3]);
+    assert_eq!(impar.validate(4), Err(IgnitionConfigError::OddCylinderCount));
+
+    // 6 bobinas secuenciales en una Board de 4 salidas
+    let sin_salidas = IgnitionConfig::even_fire(IgnitionMode::CoilOnPlug, [1, 5, 3, 6, 2, 4]);
+    assert_eq!(sin_salidas.validate(4), Err(IgnitionConfigError::OutputOutOfRange));
+}
@@ -0,0 +1,168 @@
+use engine_core::knock::{KnockConfig, KnockController, KnockLearning};
+use engine_core::rev_limiter::{CutMode, CutPatternKind, RevLimiter, RevLimiterConfig};
+use engine_core::spark_advance::{corrected_advance, SparkCorrection};
+use engine_core::tables::Table2D;
+
+fn controller() -> KnockController<4, 2> {
+    KnockController::new(KnockConfig {
+        // Umbral más alto a RPM altas (más ruido mecánico)
+        threshold: Table2D::new([1000.0, 7000.0], [1.5, 3.0]),
+        retard_step_deg: 2.0,
+        max_retard_deg: 8.0,
+        recovery_step_deg: 0.5,
+        recovery_delay_events: 3,
+    })
+}
+
+#[test]
+fn test_retardo_por_cilindro() {
+    let mut knock = controller();
+
+    // Knock en el cilindro 2 (base 0) a 1000 RPM
+    assert!(knock.on_combustion(2, 2.0, 1000.0));
+    assert_eq!(knock.retard_deg(2), 2.0);
+    assert_eq!(knock.retard_deg(0), 0.0);
+    assert_eq!(knock.knock_count(2), 1);
+
+    // La misma intensidad a 7000 RPM no pasa el umbral
+    assert!(!knock.on_combustion(0, 2.0, 7000.0));
+    assert_eq!(knock.retard_deg(0), 0.0);
+}
+
+#[test]
+fn test_retardo_maximo() {
+    let mut knock = controller();
+    for _ in 0..10 {
+        knock.on_combustion(1, 5.0, 3000.0);
+    }
+    assert_eq!(knock.retard_deg(1), 8.0);
+}
+
+#[test]
+fn test_recuperacion_gradual() {
+    let mut knock = controller();
+
+    // Guion: dos eventos de knock y después combustiones limpias
+    let script = [3.0, 3.0, 0.1, 0.1, 0.1, 0.1, 0.1, 0.1];
+    let expected = [2.0, 4.0, 4.0, 4.0, 4.0, 3.5, 3.0, 2.5];
+
+    for (intensity, retard) in script.iter().zip(expected.iter()) {
+        knock.on_combustion(0, *intensity, 1000.0);
+        assert!((knock.retard_deg(0) - retard).abs() < 0.001);
+    }
+
+    // Un knock nuevo reinicia la espera de recuperación
+    knock.on_combustion(0, 3.0, 1000.0);
+    knock.on_combustion(0, 0.1, 1000.0);
+    assert!((knock.retard_deg(0) - 4.5).abs() < 0.001);
+}
+
+#[test]
+fn test_aprendizaje_adaptivo() {
+    let mut knock = controller();
+    let mut learning = KnockLearning::new([2000.0, 4000.0], [50.0, 100.0], 0.5, 0.1, 6.0);
+
+    // Knock repetido en todos los cilindros a 4000 RPM / 100 kPa
+    for cyl in 0..4 {
+        knock.on_combustion(cyl, 3.0, 4000.0);
+    }
+    assert_eq!(knock.retard_deg(3), 2.0);
+    learning.learn(4000.0, 100.0, &mut knock);
+    assert_eq!(learning.learned_retard_deg(4000.0, 100.0), 1.0);
+    assert_eq!(learning.learned_retard_deg(2000.0, 50.0), 0.0);
+
+    // El grado aprendido sale del retardo dinámico: el efectivo no se duplica
+    assert_eq!(knock.dynamic_retard_deg(3), 1.0);
+    assert_eq!(knock.retard_deg(3), 2.0);
+
+    // El resto también pasa a la tabla
+    learning.learn(4000.0, 100.0, &mut knock);
+    assert_eq!(learning.learned_retard_deg(4000.0, 100.0), 1.5);
+    assert_eq!(knock.dynamic_retard_deg(3), 0.5);
+    assert_eq!(knock.retard_deg(3), 2.0);
+
+    // Al volver al mismo punto el retardo aprendido ya se aplica
+    let mut fresh = controller();
+    fresh.set_learned_retard(learning.learned_retard_deg(4000.0, 100.0));
+    assert_eq!(fresh.retard_deg(3), 1.5);
+
+    // Sin knock la celda devuelve avance, y el controlador con ella
+    learning.learn(4000.0, 100.0, &mut fresh);
+    assert!((learning.learned_retard_deg(4000.0, 100.0) - 1.4).abs() < 0.001);
+    assert!((fresh.retard_deg(3) - 1.4).abs() < 0.001);
+}
+
+#[test]
+fn test_aprendizaje_con_un_cilindro_ruidoso() {
+    let mut knock = controller();
+    let mut learning = KnockLearning::new([2000.0, 4000.0], [50.0, 100.0], 0.5, 0.1, 6.0);
+
+    // Solo el cilindro 0 cascabelea: no es retardo común, la tabla no cambia
+    knock.on_combustion(0, 3.0, 4000.0);
+    knock.on_combustion(0, 3.0, 4000.0);
+    learning.learn(4000.0, 100.0, &mut knock);
+    assert_eq!(learning.learned_retard_deg(4000.0, 100.0), 0.0);
+    assert_eq!(knock.retard_deg(0), 4.0);
+    assert_eq!(knock.retard_deg(1), 0.0);
+
+    // El cilindro 1 también (menos): solo se aprende lo común
+    for cyl in 1..4 {
+        knock.on_combustion(cyl, 3.0, 4000.0);
+    }
+    learning.learn(4000.0, 100.0, &mut knock);
+    assert_eq!(learning.learned_retard_deg(4000.0, 100.0), 1.0);
+    assert_eq!(knock.retard_deg(0), 4.0);
+    assert_eq!(knock.retard_deg(1), 2.0);
+    assert_eq!(knock.dynamic_retard_deg(1), 1.0);
+}
+
+#[test]
+fn test_aprendizaje_entre_celdas() {
+    let mut knock = controller();
+    let mut learning = KnockLearning::new([2000.0, 4000.0], [50.0, 100.0], 0.5, 0.1, 6.0);
+
+    // A 3500 RPM la celda más cercana es la de 4000, pero el valor aplicado
+    // es el interpolado (la celda pesa 75%)
+    for cyl in 0..4 {
+        knock.on_combustion(cyl, 3.0, 3500.0);
+        knock.on_combustion(cyl, 3.0, 3500.0);
+    }
+    learning.learn(3500.0, 100.0, &mut knock);
+    let learned = learning.learned_retard_deg(3500.0, 100.0);
+    assert!((learned - 1.5).abs() < 0.001);
+    for cyl in 0..4 {
+        assert!((knock.retard_deg(cyl) - 4.0).abs() < 0.001);
+        assert!((knock.dynamic_retard_deg(cyl) - (4.0 - learned)).abs() < 0.001);
+    }
+
+    // Un controlador nuevo en el mismo punto parte del mismo valor
+    let mut fresh = controller();
+    fresh.set_learned_retard(learned);
+    assert!((fresh.retard_deg(0) - learned).abs() < 0.001);
+}
+
+#[test]
+fn test_pila_de_correcciones() {
+    let mut knock = controller();
+    knock.on_combustion(0, 3.0, 1000.0);
+
+    let mut limiter = RevLimiter::new(
+        RevLimiterConfig {
+            hard_limit_rpm: Table2D::new([0.0], [7000.0]),
+            soft_window_rpm: 500.0,
+            max_retard_deg: 10.0,
+            min_cut_fraction: 0.5,
+            cut_ramp_rpm: 200.0,
+            hysteresis_rpm: 100.0,
+            cut_mode: CutMode::Spark,
+            pattern: CutPatternKind::Rotating,
+        },
+        4,
+    );
+    limiter.update(6750.0, 90.0);
+
+    // 30° base - 2° knock - 5° limitador suave
+    let corrections: [&dyn SparkCorrection; 2] = [&knock, &limiter];
+    assert!((corrected_advance(30.0, 0, &corrections, -10.0) - 23.0).abs() < 0.001);
+    assert!((corrected_advance(30.0, 1, &corrections, -10.0) - 25.0).abs() < 0.001);
+}
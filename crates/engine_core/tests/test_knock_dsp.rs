@@ -0,0 +1,133 @@
+use engine_core::knock_dsp::{goertzel_amplitude, Biquad, KnockDspConfig, KnockFilterKind, KnockProcessor};
+
+const FS: f32 = 100_000.0;
+const KNOCK_HZ: f32 = 7_000.0;
+const WINDOW: usize = 256;
+
+// Generador de ruido determinista (LCG) para que el test sea repetible
+struct Noise(u32);
+
+impl Noise {
+    fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        ((self.0 >> 16) as f32 / 32768.0) - 1.0
+    }
+}
+
+/// Ventana sintética del ADC de 16 bits: media escala + ruido + ráfaga opcional
+fn window(noise: &mut Noise, burst_hz: f32, burst_amplitude: f32) -> [u16; WINDOW] {
+    let mut out = [0u16; WINDOW];
+    for (n, sample) in out.iter_mut().enumerate() {
+        let t = n as f32 / FS;
+        // La ráfaga arranca a la mitad de la ventana y decae
+        let burst = if n >= WINDOW / 2 {
+            let k = (n - WINDOW / 2) as f32;
+            burst_amplitude * (-k / 80.0).exp() * (2.0 * std::f32::consts::PI * burst_hz * t).sin()
+        } else {
+            0.0
+        };
+        let value = 32768.0 + 300.0 * noise.next() + burst;
+        *sample = value as u16;
+    }
+    out
+}
+
+fn config(filter: KnockFilterKind) -> KnockDspConfig {
+    KnockDspConfig {
+        sample_rate_hz: FS,
+        knock_freq_hz: KNOCK_HZ,
+        q: 4.0,
+        filter,
+        noise_alpha: 0.1,
+        noise_reject_ratio: 2.0,
+    }
+}
+
+#[test]
+fn test_goertzel_amplitud_de_tono() {
+    let samples: Vec<f32> = (0..200)
+        .map(|n| 100.0 * (2.0 * std::f32::consts::PI * KNOCK_HZ * n as f32 / FS).sin())
+        .collect();
+
+    let amplitude = goertzel_amplitude(&samples, KNOCK_HZ, FS);
+    assert!((amplitude - 100.0).abs() < 2.0, "Amplitud: {}", amplitude);
+
+    // Un bin lejano casi no tiene energía
+    assert!(goertzel_amplitude(&samples, 15_000.0, FS) < 5.0);
+}
+
+#[test]
+fn test_biquad_pasa_banda() {
+    // Ganancia ~1 en la frecuencia central, atenuada lejos de ella
+    let gain = |freq: f32| {
+        let mut filter = Biquad::band_pass(KNOCK_HZ, 4.0, FS);
+        let mut peak: f32 = 0.0;
+        for n in 0..2000 {
+            let x = (2.0 * std::f32::consts::PI * freq * n as f32 / FS).sin();
+            let y = filter.process(x);
+            if n > 1000 { peak = peak.max(y.abs()); }
+        }
+        peak
+    };
+
+    assert!((gain(KNOCK_HZ) - 1.0).abs() < 0.05);
+    assert!(gain(500.0) < 0.1);
+    assert!(gain(30_000.0) < 0.25);
+}
+
+fn detecta_rafagas(filter: KnockFilterKind) {
+    let mut noise = Noise(12345);
+    let mut dsp: KnockProcessor<4, WINDOW> = KnockProcessor::new(config(filter));
+
+    // Ruido de fondo: la intensidad promedio se estabiliza alrededor de 1.0
+    let mut sum = 0.0;
+    let mut max_noise: f32 = 0.0;
+    for _ in 0..50 {
+        for cyl in 0..4 {
+            let intensity = dsp.process_window(cyl, &window(&mut noise, 0.0, 0.0));
+            sum += intensity;
+            max_noise = max_noise.max(intensity);
+        }
+    }
+    let mean = sum / 200.0;
+    assert!(mean > 0.8 && mean < 1.2, "Promedio de ruido: {}", mean);
+    let background = dsp.background(2);
+
+    // Ráfaga de knock en el cilindro 2
+    let knock = dsp.process_window(2, &window(&mut noise, KNOCK_HZ, 3000.0));
+    assert!(knock > 1.5 * max_noise, "Knock no detectado: {} (ruido max {})", knock, max_noise);
+
+    // El knock no contamina el ruido de fondo
+    assert_eq!(dsp.background(2), background);
+
+    // La misma ráfaga fuera de la banda (ej. válvulas a 20 kHz) casi no pesa
+    let out_of_band = dsp.process_window(1, &window(&mut noise, 20_000.0, 3000.0));
+    assert!(out_of_band < knock / 3.0, "Ráfaga fuera de banda: {} vs {}", out_of_band, knock);
+}
+
+#[test]
+fn test_intensidad_pasa_banda() {
+    detecta_rafagas(KnockFilterKind::BandPass);
+}
+
+#[test]
+fn test_intensidad_goertzel() {
+    detecta_rafagas(KnockFilterKind::Goertzel);
+}
+
+#[test]
+fn test_ruido_de_fondo_por_cilindro() {
+    let mut noise = Noise(777);
+    let mut dsp: KnockProcessor<2, WINDOW> = KnockProcessor::new(config(KnockFilterKind::BandPass));
+
+    // El cilindro 1 es más ruidoso de forma normal (ej. cerca del sensor)
+    for _ in 0..100 {
+        dsp.process_window(0, &window(&mut noise, 0.0, 0.0));
+        dsp.process_window(1, &window(&mut noise, KNOCK_HZ, 400.0));
+    }
+    assert!(dsp.background(1) > dsp.background(0));
+
+    // Para el cilindro 1 ese nivel ya es "normal"
+    let intensity = dsp.process_window(1, &window(&mut noise, KNOCK_HZ, 400.0));
+    assert!(intensity < 1.5);
+}
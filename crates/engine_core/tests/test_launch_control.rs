@@ -0,0 +1,103 @@
+use ecu_traits::engine_io::DigitalInput;
+use ecu_traits::mock::MockSwitch;
+use engine_core::launch_control::{LaunchConfig, LaunchControl, LaunchState};
+use engine_core::rev_limiter::{CutMode, CutPatternKind};
+
+fn launch() -> LaunchControl {
+    let config = LaunchConfig {
+        launch_rpm: 4000.0,
+        max_launch_speed_kph: 5.0,
+        launch_retard_deg: 15.0,
+        flat_shift_offset_rpm: 300.0,
+        flat_shift_retard_deg: 8.0,
+        min_flat_shift_rpm: 3000.0,
+        min_cut_fraction: 0.5,
+        cut_ramp_rpm: 200.0,
+        cut_mode: CutMode::Spark,
+        pattern: CutPatternKind::Rotating,
+    };
+    LaunchControl::new(config, 4)
+}
+
+#[test]
+fn test_launch_sostiene_rpm() {
+    let mut control = launch();
+    let mut clutch = MockSwitch::new(true);
+
+    // Clutch pisado, detenido, todavía debajo del objetivo
+    let status = control.update(clutch.is_active().unwrap(), 0.0, 3500.0);
+    assert_eq!(status.state, LaunchState::Launch);
+    assert_eq!(status.cut_fraction, 0.0);
+    assert_eq!(status.retard_deg, 0.0);
+
+    // Alcanza el objetivo: retardo y corte parcial
+    let status = control.update(clutch.is_active().unwrap(), 0.0, 4100.0);
+    assert_eq!(status.retard_deg, 15.0);
+    assert!((status.cut_fraction - 0.75).abs() < 0.01);
+    let cut = control.next_cut();
+    assert!(cut.spark && !cut.fuel);
+
+    // Suelta el clutch: se apaga
+    clutch.set_active(false);
+    let status = control.update(clutch.is_active().unwrap(), 0.0, 4100.0);
+    assert_eq!(status.state, LaunchState::Off);
+    assert!(!control.next_cut().any());
+}
+
+#[test]
+fn test_launch_no_cambia_a_flat_shift_al_acelerar() {
+    let mut control = launch();
+    control.update(true, 0.0, 4000.0);
+
+    // Mientras el clutch siga pisado el modo no cambia aunque el auto se mueva
+    assert_eq!(control.update(true, 20.0, 4000.0).state, LaunchState::Launch);
+}
+
+#[test]
+fn test_flat_shift_captura_rpm_previas() {
+    let mut control = launch();
+
+    // Acelerando en segunda sin clutch
+    assert_eq!(control.update(false, 80.0, 7000.0).state, LaunchState::Off);
+
+    // Pisa el clutch en movimiento: objetivo = 7000 - 300
+    let status = control.update(true, 80.0, 7000.0);
+    assert_eq!(status.state, LaunchState::FlatShift);
+    assert_eq!(status.target_rpm, 6700.0);
+    assert_eq!(status.retard_deg, 8.0);
+    assert_eq!(status.cut_fraction, 1.0);
+
+    // El objetivo se sostiene durante todo el cambio
+    let status = control.update(true, 80.0, 6650.0);
+    assert_eq!(status.target_rpm, 6700.0);
+    assert_eq!(status.cut_fraction, 0.0);
+
+    // Al soltar el clutch termina el flat-shift
+    assert_eq!(control.update(false, 82.0, 5200.0).state, LaunchState::Off);
+}
+
+#[test]
+fn test_cambio_normal_debajo_de_rpm_minimas() {
+    let mut control = launch();
+    assert_eq!(control.update(true, 40.0, 2000.0).state, LaunchState::Off);
+}
+
+#[test]
+fn test_modo_se_decide_al_pisar_el_clutch() {
+    let mut control = launch();
+    let mut clutch = MockSwitch::new(true);
+
+    // Pisa el clutch en movimiento debajo de las RPM mínimas: cambio normal
+    assert_eq!(control.update(clutch.is_active().unwrap(), 40.0, 2500.0).state, LaunchState::Off);
+
+    // Las RPM suben con el clutch todavía pisado: no entra al flat-shift
+    assert_eq!(control.update(clutch.is_active().unwrap(), 40.0, 3500.0).state, LaunchState::Off);
+
+    // Suelta y vuelve a pisar arriba de las mínimas: ahora sí
+    clutch.set_active(false);
+    control.update(clutch.is_active().unwrap(), 60.0, 5000.0);
+    clutch.set_active(true);
+    let status = control.update(clutch.is_active().unwrap(), 60.0, 5000.0);
+    assert_eq!(status.state, LaunchState::FlatShift);
+    assert_eq!(status.target_rpm, 4700.0);
+}
@@ -0,0 +1,198 @@
+use ecu_traits::mock::SimTimer;
+use ecu_traits::timer::TimerBackend;
+use engine_core::angle_predictor::{AnglePredictor, AnglePredictorConfig};
+use engine_core::filters::LagFilter;
+use engine_core::fuel_model::SpeedDensity;
+use engine_core::map_sampler::{MapSampleMode, MapSampler, MapSamplerConfig};
+use engine_core::scheduler::AngleScheduler;
+
+const TOOTH_DEG: f64 = 10.0;
+const MAP_CHANNEL: u8 = 6;
+const SAMPLE_CHANNEL: u8 = 7;
+/// Paso de la simulación
+const STEP_US: u32 = 50;
+/// Lectura periódica de la placa: cada 10 ms con primer orden de 20 ms (MAP_FILTER)
+const PERIODIC_US: u32 = 10_000;
+const PERIODIC_TAU_US: u32 = 20_000;
+
+/// MAP de un 4 cilindros a 720°: un vacío por carrera de admisión (cada 180°),
+/// de 50 kPa a 35 kPa a la mitad de cada ventana
+fn map_kpa(angle: f64) -> f32 {
+    let phase = (angle % 180.0) / 180.0 * core::f64::consts::PI;
+    (50.0 - 15.0 * phase.sin()) as f32
+}
+
+struct Engine {
+    timer: SimTimer<8, 64>,
+    predictor: AnglePredictor,
+    scheduler: AngleScheduler<4>,
+    sampler: MapSampler<4>,
+    periodic: LagFilter,
+    next_periodic_us: u32,
+    us_per_deg: f64,
+    synced: bool,
+    next_tooth_deg: f64,
+    now: u32,
+}
+
+impl Engine {
+    fn new(rpm: f64, mode: MapSampleMode) -> Self {
+        let config = MapSamplerConfig {
+            channel: MAP_CHANNEL,
+            sample_channel: SAMPLE_CHANNEL,
+            sample_interval_us: 250,
+            window_start_deg: [0.0, 180.0, 360.0, 540.0],
+            window_deg: 180.0 - 20.0,
+            mode,
+            fallback_tau_us: 20_000,
+        };
+        Engine {
+            timer: SimTimer::default(),
+            predictor: AnglePredictor::new(AnglePredictorConfig { cycle_deg: 720.0, ..Default::default() }),
+            scheduler: AngleScheduler::new(30.0),
+            sampler: MapSampler::new(config),
+            periodic: LagFilter::new(PERIODIC_TAU_US),
+            next_periodic_us: 0,
+            us_per_deg: 60e6 / (rpm * 360.0),
+            synced: false,
+            next_tooth_deg: TOOTH_DEG,
+            now: 0,
+        }
+    }
+
+    /// Avanza el tiempo; los dientes solo se reportan con sincronía. Los
+    /// eventos de la ventana convierten el MAP crudo en su instante y la
+    /// lectura periódica pasa por el filtro de la placa.
+    fn run_degrees(&mut self, degrees: f64) {
+        let end = self.now + (degrees * self.us_per_deg) as u32;
+        while self.now < end {
+            self.now += STEP_US;
+            let us_per_deg = self.us_per_deg;
+            let Engine { timer, scheduler, sampler, .. } = self;
+            timer.run_until(self.now, |timer, channel, action| {
+                let now = timer.now_us();
+                let raw = map_kpa(now as f64 / us_per_deg);
+                match channel {
+                    MAP_CHANNEL => sampler.on_window(action, raw, now, timer),
+                    SAMPLE_CHANNEL => sampler.on_sample(raw, now, timer),
+                    _ => {}
+                }
+                scheduler.on_compare(channel, timer);
+            });
+
+            let angle = self.now as f64 / self.us_per_deg;
+            while angle >= self.next_tooth_deg {
+                let t = (self.next_tooth_deg * self.us_per_deg) as u32;
+                if self.synced {
+                    self.predictor.on_tooth(t, (self.next_tooth_deg % 720.0) as f32);
+                    self.scheduler.on_tooth(&self.predictor, &mut self.timer);
+                    if self.predictor.is_valid() {
+                        let _ = self.sampler.schedule(&mut self.scheduler, &self.predictor, &mut self.timer);
+                    }
+                }
+                self.next_tooth_deg += TOOTH_DEG;
+            }
+
+            if self.now >= self.next_periodic_us {
+                self.next_periodic_us += PERIODIC_US;
+                let filtered = self.periodic.apply(map_kpa(angle), PERIODIC_US);
+                self.sampler.add_sample(filtered, self.now);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_respaldo_por_tiempo_antes_de_sincronia() {
+    let mut engine = Engine::new(1500.0, MapSampleMode::Minimum);
+    engine.run_degrees(720.0 * 4.0);
+
+    assert!(!engine.sampler.is_synchronous());
+    let load = engine.sampler.load_kpa().unwrap();
+    // Sigue el promedio de la pulsación, no el mínimo
+    assert!(load > 38.0 && load < 45.0, "carga {}", load);
+    assert_eq!(engine.sampler.fallback_kpa(), Some(load));
+}
+
+#[test]
+fn test_minimo_por_ventana_sincronizado() {
+    let mut engine = Engine::new(1500.0, MapSampleMode::Minimum);
+    engine.run_degrees(720.0);
+    engine.synced = true;
+    engine.run_degrees(720.0 * 4.0);
+
+    assert!(engine.sampler.is_synchronous());
+    let expected = (0..1800).map(|i| map_kpa(i as f64 * 0.1)).fold(f32::MAX, f32::min);
+    for cylinder in 0..4 {
+        let kpa = engine.sampler.cylinder_kpa(cylinder).expect("ventana de cada cilindro");
+        assert!((kpa - expected).abs() < 0.2, "cilindro {}: {} kPa", cylinder, kpa);
+    }
+    assert!((engine.sampler.load_kpa().unwrap() - expected).abs() < 0.2);
+    assert_eq!(engine.sampler.empty_windows(), 0);
+}
+
+#[test]
+fn test_conversiones_disparadas_por_la_ventana() {
+    // A 3000 RPM la ventana dura ~9 ms, menos que el periodo de la lectura
+    // periódica: las conversiones salen de los eventos de la ventana
+    let mut engine = Engine::new(3000.0, MapSampleMode::Minimum);
+    engine.run_degrees(720.0);
+    engine.synced = true;
+    engine.run_degrees(720.0 * 2.0);
+
+    // Abre la siguiente ventana y cuenta sus conversiones
+    while engine.sampler.window_samples().is_none() {
+        engine.run_degrees(1.0);
+    }
+    let mut samples = 0;
+    while let Some(n) = engine.sampler.window_samples() {
+        samples = n;
+        engine.run_degrees(1.0);
+    }
+    // 160° a 3000 RPM = 8.9 ms en pasos de 250 µs
+    assert!(samples >= 30, "{} conversiones", samples);
+
+    // El mínimo es el de la señal cruda, no el de la lectura filtrada
+    let expected = (0..1800).map(|i| map_kpa(i as f64 * 0.1)).fold(f32::MAX, f32::min);
+    assert!((engine.sampler.load_kpa().unwrap() - expected).abs() < 0.2);
+    assert!(engine.sampler.fallback_kpa().unwrap() > expected + 2.0);
+}
+
+#[test]
+fn test_promedio_por_ventana_estable() {
+    let mut engine = Engine::new(2500.0, MapSampleMode::Average);
+    engine.run_degrees(720.0);
+    engine.synced = true;
+    engine.run_degrees(720.0 * 2.0);
+
+    // La carga sincronizada no depende del momento en que se lee
+    let mut loads = Vec::new();
+    for _ in 0..20 {
+        engine.run_degrees(37.0);
+        loads.push(engine.sampler.load_kpa().unwrap());
+    }
+    let min = loads.iter().copied().fold(f32::MAX, f32::min);
+    let max = loads.iter().copied().fold(f32::MIN, f32::max);
+    assert!(max - min < 0.5, "variación {}..{}", min, max);
+    assert!(min > 35.0 && max < 45.0);
+}
+
+#[test]
+fn test_perdida_de_sincronia_y_masa_de_aire() {
+    let mut engine = Engine::new(1500.0, MapSampleMode::Minimum);
+    engine.run_degrees(720.0);
+    engine.synced = true;
+    engine.run_degrees(720.0 * 2.0);
+
+    let model = SpeedDensity::new(2000.0, 4, 440.0);
+    let load = engine.sampler.load_kpa().unwrap();
+    let mass = engine.sampler.air_mass_g(&model, 25.0, 80.0).unwrap();
+    assert_eq!(mass, model.calculate_air_mass(load, 25.0, 80.0));
+
+    engine.synced = false;
+    engine.scheduler.cancel_all(&mut engine.timer);
+    engine.sampler.sync_lost();
+    assert!(!engine.sampler.is_synchronous());
+    assert_eq!(engine.sampler.load_kpa(), engine.sampler.fallback_kpa());
+    assert_eq!(engine.sampler.cylinder_kpa(0), None);
+}
@@ -0,0 +1,109 @@
+use engine_core::analog::LinearConverter;
+use engine_core::o2::{
+    MixtureState, Narrowband, NarrowbandConfig, Wideband, WidebandConfig, STOICH_AFR_GASOLINE,
+};
+
+const WARMUP_US: u32 = 20_000_000;
+
+fn wideband() -> Wideband {
+    Wideband::new(WidebandConfig {
+        lambda: LinearConverter::new(0.0, 0.68, 5.0, 1.36),
+        stoich_afr: STOICH_AFR_GASOLINE,
+        valid_min_volts: 0.05,
+        valid_max_volts: 4.95,
+        warmup_us: WARMUP_US,
+    })
+}
+
+fn narrowband() -> Narrowband {
+    Narrowband::new(NarrowbandConfig {
+        threshold_volts: 0.45,
+        hysteresis_volts: 0.1,
+        valid_min_volts: 0.02,
+        valid_max_volts: 1.1,
+        warmup_us: WARMUP_US,
+        switch_timeout_us: 3_000_000,
+    })
+}
+
+#[test]
+fn test_banda_ancha_lambda_y_afr() {
+    let mut sensor = wideband();
+
+    // Sin arranque no hay lecturas
+    assert_eq!(sensor.update(2.35, WARMUP_US), None);
+
+    sensor.start(1_000);
+    assert_eq!(sensor.update(2.35, WARMUP_US), None);
+    assert!(!sensor.is_warm(WARMUP_US));
+
+    let reading = sensor.update(2.35, WARMUP_US + 1_000).unwrap();
+    assert!((reading.lambda - 0.9996).abs() < 1e-3);
+    assert!((reading.afr - 14.69).abs() < 0.02);
+
+    // Rico a 0.5 V
+    let rich = sensor.update(0.5, WARMUP_US + 2_000).unwrap();
+    assert!(rich.afr < 11.0);
+}
+
+#[test]
+fn test_banda_ancha_fuera_de_rango() {
+    let mut sensor = wideband();
+    sensor.start(0);
+    assert!(sensor.update(2.5, WARMUP_US).is_some());
+
+    // Controlador en error (salida a 0 V)
+    assert_eq!(sensor.update(0.0, WARMUP_US + 1), None);
+    assert_eq!(sensor.reading(), None);
+
+    sensor.stop();
+    assert_eq!(sensor.update(2.5, WARMUP_US + 2), None);
+}
+
+#[test]
+fn test_banda_angosta_conmutacion() {
+    let mut sensor = narrowband();
+    sensor.start(0);
+
+    // Fría: fija en 0.45 V, no conmuta
+    let mut now = 0;
+    while now < WARMUP_US + 1_000_000 {
+        sensor.update(0.45, now);
+        now += 100_000;
+    }
+    assert!(!sensor.is_ready(now));
+    assert_eq!(sensor.switches(), 0);
+
+    // Caliente en lazo cerrado: rico/pobre cada 500 ms
+    for i in 0..10 {
+        let volts = if i % 2 == 0 { 0.8 } else { 0.1 };
+        sensor.update(volts, now);
+        now += 500_000;
+    }
+    assert!(sensor.is_ready(now));
+    assert_eq!(sensor.state(), Some(MixtureState::Lean));
+    assert_eq!(sensor.switch_period_us(), Some(500_000));
+    assert!(sensor.switches() >= 9);
+
+    // Deja de conmutar
+    now += 3_000_000;
+    sensor.update(0.1, now);
+    assert!(!sensor.is_ready(now));
+}
+
+#[test]
+fn test_banda_angosta_histeresis_y_validez() {
+    let mut sensor = narrowband();
+    sensor.start(0);
+
+    assert_eq!(sensor.update(0.6, 0), Some(MixtureState::Rich));
+    // Dentro de la histéresis no cambia
+    assert_eq!(sensor.update(0.42, 1), Some(MixtureState::Rich));
+    assert_eq!(sensor.update(0.39, 2), Some(MixtureState::Lean));
+    assert_eq!(sensor.update(0.48, 3), Some(MixtureState::Lean));
+    assert_eq!(sensor.update(0.51, 4), Some(MixtureState::Rich));
+
+    // Circuito abierto
+    assert_eq!(sensor.update(0.0, 5), None);
+    assert!(!sensor.is_ready(WARMUP_US));
+}
@@ -0,0 +1,115 @@
+use engine_core::rev_limiter::{
+    CutMode, CutPattern, CutPatternKind, LimiterState, RevLimiter, RevLimiterConfig,
+};
+use engine_core::tables::Table2D;
+
+fn config(pattern: CutPatternKind) -> RevLimiterConfig<2> {
+    RevLimiterConfig {
+        // 5000 RPM en frío (0°C), 7000 RPM caliente (80°C)
+        hard_limit_rpm: Table2D::new([0.0, 80.0], [5000.0, 7000.0]),
+        soft_window_rpm: 200.0,
+        max_retard_deg: 10.0,
+        min_cut_fraction: 0.25,
+        cut_ramp_rpm: 200.0,
+        hysteresis_rpm: 100.0,
+        cut_mode: CutMode::Fuel,
+        pattern,
+    }
+}
+
+#[test]
+fn test_etapas_y_histeresis() {
+    let mut limiter = RevLimiter::new(config(CutPatternKind::Rotating), 4);
+
+    assert_eq!(limiter.update(6000.0, 90.0).state, LimiterState::Inactive);
+
+    // Límite suave: 6800 a 7000 RPM, retardo progresivo
+    let status = limiter.update(6900.0, 90.0);
+    assert_eq!(status.state, LimiterState::Soft);
+    assert!((status.retard_deg - 5.0).abs() < 0.01);
+    assert_eq!(status.cut_fraction, 0.0);
+
+    // Límite duro
+    let status = limiter.update(7000.0, 90.0);
+    assert_eq!(status.state, LimiterState::Hard);
+    assert!((status.cut_fraction - 0.25).abs() < 0.01);
+
+    // Dentro de la histéresis se sostiene el corte
+    assert_eq!(limiter.update(6950.0, 90.0).state, LimiterState::Hard);
+    assert_eq!(limiter.update(6890.0, 90.0).state, LimiterState::Soft);
+
+    // Bajando de 6800 se sigue en suave hasta 6700
+    assert_eq!(limiter.update(6750.0, 90.0).state, LimiterState::Soft);
+    assert_eq!(limiter.update(6650.0, 90.0).state, LimiterState::Inactive);
+}
+
+#[test]
+fn test_limite_por_temperatura() {
+    let mut limiter = RevLimiter::new(config(CutPatternKind::Rotating), 4);
+
+    // Motor frío: 5500 RPM ya está arriba del límite de 5000
+    let status = limiter.update(5500.0, 0.0);
+    assert_eq!(status.state, LimiterState::Hard);
+    assert_eq!(status.hard_limit_rpm, 5000.0);
+    assert_eq!(status.cut_fraction, 1.0);
+
+    // A 40°C el límite está a la mitad de la curva
+    assert_eq!(limiter.update(5500.0, 40.0).hard_limit_rpm, 6000.0);
+}
+
+#[test]
+fn test_corte_total_y_contador() {
+    let mut limiter = RevLimiter::new(config(CutPatternKind::Rotating), 4);
+    limiter.update(7300.0, 90.0);
+
+    for _ in 0..8 {
+        let cut = limiter.next_cut();
+        assert!(cut.fuel);
+        assert!(!cut.spark);
+    }
+    assert_eq!(limiter.status().cut_events, 8);
+}
+
+#[test]
+fn test_patron_rotativo_reparte_cilindros() {
+    // 50% de corte en 4 cilindros: cada cilindro debe cortarse la mitad de las veces
+    let mut pattern = CutPattern::new(CutPatternKind::Rotating, 4);
+    let mut cuts_per_cyl = [0u32; 4];
+
+    for event in 0..400 {
+        if pattern.should_cut(0.5) {
+            cuts_per_cyl[event % 4] += 1;
+        }
+    }
+    for cuts in cuts_per_cyl {
+        assert_eq!(cuts, 50);
+    }
+
+    // 25%: un cilindro por ciclo, rotando
+    let mut pattern = CutPattern::new(CutPatternKind::Rotating, 4);
+    let mut cuts_per_cyl = [0u32; 4];
+    for event in 0..400 {
+        if pattern.should_cut(0.25) {
+            cuts_per_cyl[event % 4] += 1;
+        }
+    }
+    for cuts in cuts_per_cyl {
+        assert_eq!(cuts, 25);
+    }
+}
+
+#[test]
+fn test_patron_aleatorio_reparte_cilindros() {
+    let mut pattern = CutPattern::new(CutPatternKind::Random, 4);
+    let mut cuts_per_cyl = [0u32; 4];
+
+    for event in 0..4000 {
+        if pattern.should_cut(0.5) {
+            cuts_per_cyl[event % 4] += 1;
+        }
+    }
+    // Tolerancia estadística de +/-10%
+    for cuts in cuts_per_cyl {
+        assert!(cuts > 450 && cuts < 550, "Corte desbalanceado: {}", cuts);
+    }
+}
@@ -0,0 +1,196 @@
+use engine_core::angle_predictor::{AnglePredictor, AnglePredictorConfig};
+use ecu_traits::mock::SimTimer;
+use engine_core::scheduler::{
+    AngleEvent, AngleScheduler, CompareAction, ScheduleError, ScheduleOutcome, TimerBackend,
+};
+
+const TOOTH_DEG: f32 = 10.0;
+
+/// Motor simulado en el dominio de 720° con un diente cada 10°
+struct Engine {
+    timer: SimTimer<8, 32>,
+    predictor: AnglePredictor,
+    scheduler: AngleScheduler<4>,
+    us_per_deg: f64,
+    // Ángulo acumulado del último diente
+    angle: f64,
+}
+
+impl Engine {
+    fn new(rpm: f64) -> Self {
+        let config = AnglePredictorConfig { cycle_deg: 720.0, ..Default::default() };
+        let mut engine = Engine {
+            timer: SimTimer::default(),
+            predictor: AnglePredictor::new(config),
+            scheduler: AngleScheduler::new(30.0),
+            us_per_deg: 60e6 / (rpm * 360.0),
+            angle: 0.0,
+        };
+        // Un par de dientes para tener velocidad
+        for _ in 0..3 {
+            engine.tooth();
+        }
+        engine
+    }
+
+    fn time_of(&self, angle: f64) -> u32 {
+        (angle * self.us_per_deg) as u32
+    }
+
+    /// Ejecuta los compares que vencen antes de `until`, en orden
+    fn run_compares(&mut self, until: u32) {
+        let scheduler = &mut self.scheduler;
+        self.timer.run_until(until, |timer, ch, _| scheduler.on_compare(ch, timer));
+    }
+
+    /// Avanza al siguiente diente
+    fn tooth(&mut self) {
+        self.angle += TOOTH_DEG as f64;
+        let t = self.time_of(self.angle);
+        self.run_compares(t);
+        self.predictor.on_tooth(t, (self.angle % 720.0) as f32);
+        self.scheduler.on_tooth(&self.predictor, &mut self.timer);
+    }
+
+    fn schedule(&mut self, event: AngleEvent) -> Result<ScheduleOutcome, ScheduleError> {
+        self.scheduler.schedule(event, &self.predictor, &mut self.timer)
+    }
+}
+
+#[test]
+fn test_inyeccion_por_angulo() {
+    let mut engine = Engine::new(3000.0);
+    assert_eq!(engine.schedule(AngleEvent::injection(3, 412.0, 3000)), Ok(ScheduleOutcome::OnTime));
+
+    // El pulso cruza varios dientes
+    while engine.angle < 720.0 {
+        engine.tooth();
+    }
+    let timeline = engine.timer.timeline();
+    assert_eq!(timeline.len(), 2);
+
+    let (open, ch, action) = (timeline[0].timestamp_us, timeline[0].channel, timeline[0].action);
+    assert_eq!((ch, action), (3, CompareAction::Activate));
+    assert!(open.abs_diff(engine.time_of(412.0)) <= 2, "abre en {}", open);
+
+    let (close, action) = (timeline[1].timestamp_us, timeline[1].action);
+    assert_eq!(action, CompareAction::Deactivate);
+    assert!(close.abs_diff(open + 3000) <= 2);
+    assert_eq!(engine.scheduler.pending(), 0);
+}
+
+#[test]
+fn test_chispa_que_cruza_720() {
+    let mut engine = Engine::new(6000.0);
+    while engine.angle < 650.0 {
+        engine.tooth();
+    }
+    // Dwell desde 705° hasta 15° del siguiente ciclo
+    engine.schedule(AngleEvent::spark(1, 705.0, 15.0)).unwrap();
+    while engine.angle < 760.0 {
+        engine.tooth();
+    }
+
+    let timeline = engine.timer.timeline();
+    assert_eq!(timeline.len(), 2);
+    assert!(timeline[0].timestamp_us.abs_diff(engine.time_of(705.0)) <= 2);
+    assert!(timeline[1].timestamp_us.abs_diff(engine.time_of(735.0)) <= 2);
+    assert!(!engine.timer.pin(1));
+}
+
+#[test]
+fn test_evento_tarde_se_acorta_o_descarta() {
+    let mut engine = Engine::new(1000.0);
+    while engine.angle < 400.0 {
+        engine.tooth();
+    }
+    // 5° después del último diente (400°)
+    engine.timer.set_now(engine.time_of(405.0));
+
+    // El inicio (402°) ya pasó pero el fin no: se activa de inmediato
+    assert_eq!(engine.schedule(AngleEvent::spark(0, 402.0, 420.0)), Ok(ScheduleOutcome::Truncated));
+    assert_eq!(engine.timer.armed(0), Some((engine.time_of(405.0), CompareAction::Activate)));
+
+    // El evento completo ya pasó: se descarta
+    assert_eq!(engine.schedule(AngleEvent::spark(1, 395.0, 401.0)), Err(ScheduleError::Late));
+    assert_eq!(engine.scheduler.late_drops(), 1);
+    assert_eq!(engine.scheduler.truncated(), 1);
+
+    // Fuera de la ventana de tarde el ángulo se toma como el siguiente ciclo
+    assert_eq!(engine.schedule(AngleEvent::injection(2, 300.0, 1000)), Ok(ScheduleOutcome::OnTime));
+    while engine.angle < 1100.0 {
+        engine.tooth();
+    }
+    let open = engine.timer.changes(2).next().unwrap().timestamp_us;
+    assert!(open.abs_diff(engine.time_of(1020.0)) <= 2);
+}
+
+#[test]
+fn test_correccion_con_aceleracion() {
+    // El motor acelera mientras el evento está pendiente: el scheduler corrige en cada diente
+    let mut engine = Engine::new(2000.0);
+    engine.schedule(AngleEvent::injection(0, 305.0, 500)).unwrap();
+
+    let mut t = engine.timer.now_us() as f64;
+    let mut period = TOOTH_DEG as f64 * engine.us_per_deg;
+    let mut tooth_times = Vec::new();
+    while engine.angle < 400.0 {
+        // Cada periodo es 1% más corto que el anterior
+        period *= 0.99;
+        t += period;
+        engine.angle += TOOTH_DEG as f64;
+        tooth_times.push((engine.angle, t));
+
+        engine.run_compares(t as u32);
+        engine.predictor.on_tooth(t as u32, (engine.angle % 720.0) as f32);
+        engine.scheduler.on_tooth(&engine.predictor, &mut engine.timer);
+    }
+
+    // 305° está a la mitad entre los dientes de 300° y 310°
+    let &(_, t300) = tooth_times.iter().find(|(a, _)| *a == 300.0).unwrap();
+    let &(_, t310) = tooth_times.iter().find(|(a, _)| *a == 310.0).unwrap();
+    let open = engine.timer.timeline()[0].timestamp_us;
+    assert!(open as f64 > t300 && (open as f64) < t310);
+    assert!((open as f64 - (t300 + t310) / 2.0).abs() < 5.0, "abre en {}", open);
+    assert_eq!(engine.timer.timeline().len(), 2);
+}
+
+#[test]
+fn test_canal_ocupado_y_sin_lugar() {
+    let mut engine = Engine::new(3000.0);
+    engine.schedule(AngleEvent::injection(0, 100.0, 1000)).unwrap();
+    assert_eq!(engine.schedule(AngleEvent::injection(0, 200.0, 1000)), Err(ScheduleError::ChannelBusy));
+
+    for ch in 1..4 {
+        engine.schedule(AngleEvent::injection(ch, 200.0, 1000)).unwrap();
+    }
+    assert_eq!(engine.schedule(AngleEvent::injection(5, 200.0, 1000)), Err(ScheduleError::NoFreeSlot));
+
+    engine.scheduler.cancel_all(&mut engine.timer);
+    assert_eq!(engine.scheduler.pending(), 0);
+    assert!((0..8).all(|ch| engine.timer.armed(ch).is_none()));
+
+    let idle = AnglePredictor::new(AnglePredictorConfig::default());
+    let mut scheduler: AngleScheduler<1> = AngleScheduler::new(30.0);
+    let mut timer: SimTimer<1, 4> = SimTimer::new();
+    assert_eq!(
+        scheduler.schedule(AngleEvent::injection(0, 10.0, 100), &idle, &mut timer),
+        Err(ScheduleError::NoSpeed)
+    );
+}
+
+#[test]
+fn test_fin_sobre_un_diente() {
+    // El disparo cae justo en un diente y el dwell empieza más de una
+    // ventana de atraso antes: el fin no debe brincar al siguiente ciclo
+    let mut engine = Engine::new(3000.0);
+    assert_eq!(engine.schedule(AngleEvent::spark(1, 600.0, 650.0)), Ok(ScheduleOutcome::OnTime));
+
+    while engine.angle < 700.0 {
+        engine.tooth();
+    }
+    let timeline = engine.timer.timeline();
+    assert_eq!(timeline.len(), 2);
+    assert!(timeline[1].timestamp_us.abs_diff(engine.time_of(650.0)) <= 2);
+    assert_eq!(engine.scheduler.pending(), 0);
+}
@@ -0,0 +1,266 @@
+use ecu_traits::mock::MockAnalogInput;
+use engine_core::analog::{CalibratedSensor, Thermistor, DEFAULT_MAP, GM_THERMISTOR};
+use engine_core::diagnostics::{DiagnosticLog, FaultCode, FaultKind, SensorId};
+use engine_core::filters::{FilterConfig, FilteredInput};
+use engine_core::sensor_fault::{Fallback, FaultTransition, MonitoredSensor, RangeCheckConfig, RangeMonitor};
+use engine_core::tables::Table3D;
+
+const DEBOUNCE_US: u32 = 100_000;
+
+fn cts_range() -> RangeCheckConfig {
+    RangeCheckConfig::new(0.1, 4.9, DEBOUNCE_US)
+}
+
+/// Canal sin filtro para `MonitoredSensor`
+fn unfiltered<const N: usize>(input: MockAnalogInput<N>) -> FilteredInput<MockAnalogInput<N>> {
+    FilteredInput::new(input, FilterConfig::none(10_000))
+}
+
+/// Filtro de temperaturas de la placa: lectura cada 10 ms, mediana de 5,
+/// primer orden de 1 s y límite de 1 V/s
+fn temp_filter() -> FilterConfig {
+    FilterConfig {
+        oversample: 1,
+        median_window: 5,
+        lag_tau_us: 1_000_000,
+        rate_limit_per_s: 1.0,
+        sample_period_us: 10_000,
+    }
+}
+
+/// Voltaje del CTS a una temperatura (resistencias del termistor GM)
+fn cts_volts(ohms: f32) -> f32 {
+    5.0 * ohms / (ohms + 2490.0)
+}
+
+#[test]
+fn test_clasificacion_de_rango() {
+    let range = cts_range();
+    assert_eq!(range.classify(0.05), Some(FaultKind::RangeLow));
+    assert_eq!(range.classify(2.5), None);
+    assert_eq!(range.classify(4.95), Some(FaultKind::RangeHigh));
+}
+
+#[test]
+fn test_debounce_de_falla() {
+    let mut monitor = RangeMonitor::new(DEBOUNCE_US);
+
+    // Un pico corto no activa la falla
+    assert_eq!(monitor.observe(Some(FaultKind::RangeHigh), 0), None);
+    assert!(monitor.is_pending());
+    assert_eq!(monitor.observe(None, 50_000), None);
+    assert!(!monitor.is_pending());
+
+    // Sostenida sí
+    assert_eq!(monitor.observe(Some(FaultKind::RangeHigh), 60_000), None);
+    assert_eq!(monitor.observe(Some(FaultKind::RangeHigh), 150_000), None);
+    assert_eq!(monitor.observe(Some(FaultKind::RangeHigh), 160_000), Some(FaultTransition::Set(FaultKind::RangeHigh)));
+    assert_eq!(monitor.fault(), Some(FaultKind::RangeHigh));
+
+    // La recuperación también se filtra
+    assert_eq!(monitor.observe(None, 170_000), None);
+    assert_eq!(monitor.fault(), Some(FaultKind::RangeHigh));
+    assert_eq!(monitor.observe(None, 270_000), Some(FaultTransition::Cleared(FaultKind::RangeHigh)));
+    assert_eq!(monitor.fault(), None);
+}
+
+#[test]
+fn test_cable_roto_de_cts_usa_respaldo() {
+    // 30 °C, y luego el cable se rompe (el pull-up lleva la entrada a 5 V)
+    let normal = 5.0 * 2238.0 / (2238.0 + 2490.0);
+    let input = MockAnalogInput::new([normal, 5.0]);
+    let mut cts = MonitoredSensor::new(
+        CalibratedSensor::new(unfiltered(input), Thermistor::new(GM_THERMISTOR, 2490.0, 5.0)),
+        SensorId::Cts,
+        cts_range(),
+        Fallback::Fixed(80.0),
+    );
+    let mut diag: DiagnosticLog<8> = DiagnosticLog::new();
+    let code = FaultCode::new(SensorId::Cts, FaultKind::RangeHigh);
+
+    let t = cts.update(0, None, &mut diag);
+    assert!((t - 30.0).abs() < 0.5);
+
+    // Durante el debounce se sostiene la última lectura válida (nunca -40 °C)
+    let held = cts.update(10_000, None, &mut diag);
+    assert_eq!(held, t);
+    assert!(!diag.is_active(code));
+
+    assert_eq!(cts.update(120_000, None, &mut diag), 80.0);
+    assert!(diag.is_active(code));
+    assert_eq!(cts.fault(), Some(code));
+    assert_eq!(code.obd_code(), 118);
+
+    // Se repara el cable: el código queda en el historial como inactivo
+    cts.sensor_mut().input_mut().input_mut().set_volts(normal);
+    assert_eq!(cts.update(130_000, None, &mut diag), 80.0);
+    let t = cts.update(240_000, None, &mut diag);
+    assert!((t - 30.0).abs() < 0.5);
+    assert!(!diag.is_active(code));
+    assert!(!diag.any_active());
+    assert_eq!(diag.records().count(), 1);
+
+    diag.clear_inactive();
+    assert_eq!(diag.records().count(), 0);
+}
+
+#[test]
+fn test_map_modelado_con_tps_y_rpm() {
+    // MAP estimado (kPa) por TPS (%) y RPM
+    let model: Table3D<2, 2> = Table3D::new(
+        [0.0, 100.0],
+        [1000.0, 6000.0],
+        [[30.0, 100.0], [25.0, 98.0]],
+    );
+
+    let input = MockAnalogInput::new([0.0]);
+    let mut map = MonitoredSensor::new(
+        CalibratedSensor::new(unfiltered(input), DEFAULT_MAP),
+        SensorId::Map,
+        RangeCheckConfig::new(0.2, 4.8, 0),
+        Fallback::Modelled { default: 100.0 },
+    );
+    let mut diag: DiagnosticLog<8> = DiagnosticLog::new();
+
+    let estimate = model.interpolate(0.0, 1000.0);
+    assert_eq!(map.update(0, Some(estimate), &mut diag), 30.0);
+    assert_eq!(map.fault(), Some(FaultCode::new(SensorId::Map, FaultKind::RangeLow)));
+    assert_eq!(diag.sensor_fault(SensorId::Map).map(|c| c.obd_code()), Some(107));
+
+    // Sin estimación se usa el valor por defecto
+    assert_eq!(map.update(1000, None, &mut diag), 100.0);
+}
+
+#[test]
+fn test_error_de_lectura_y_cambio_de_tipo() {
+    let input = MockAnalogInput::new([5.0]);
+    let mut cts = MonitoredSensor::new(
+        CalibratedSensor::new(unfiltered(input), Thermistor::new(GM_THERMISTOR, 2490.0, 5.0)),
+        SensorId::Cts,
+        RangeCheckConfig::new(0.1, 4.9, 0),
+        Fallback::LastGood { default: 60.0 },
+    );
+    let mut diag: DiagnosticLog<8> = DiagnosticLog::new();
+
+    // Sin lecturas válidas previas se usa el valor por defecto
+    assert_eq!(cts.update(0, None, &mut diag), 60.0);
+    assert!(diag.is_active(FaultCode::new(SensorId::Cts, FaultKind::RangeHigh)));
+
+    // El canal deja de responder: cambia el código activo
+    cts.sensor_mut().input_mut().input_mut().faults().set_failing(true);
+    assert_eq!(cts.update(1000, None, &mut diag), 60.0);
+    assert!(diag.is_active(FaultCode::new(SensorId::Cts, FaultKind::ReadError)));
+    assert!(!diag.is_active(FaultCode::new(SensorId::Cts, FaultKind::RangeHigh)));
+    assert_eq!(diag.records().count(), 2);
+}
+
+#[test]
+fn test_registro_lleno() {
+    let mut diag: DiagnosticLog<1> = DiagnosticLog::new();
+    let cts = FaultCode::new(SensorId::Cts, FaultKind::RangeHigh);
+
+    diag.report(cts, true, 0);
+    diag.report(cts, false, 10);
+    diag.report(cts, true, 20);
+    diag.report(FaultCode::new(SensorId::Iat, FaultKind::RangeLow), true, 30);
+
+    let record = diag.records().next().unwrap();
+    assert_eq!(record.occurrences, 2);
+    assert_eq!(record.first_seen_us, 0);
+    assert_eq!(record.last_seen_us, 20);
+    assert_eq!(diag.overflowed(), 1);
+}
+
+#[test]
+fn test_cable_roto_con_filtro_lento() {
+    // CTS con el filtro de temperaturas de la placa
+    let normal = cts_volts(2238.0);
+    let mut input = MockAnalogInput::new([normal]);
+    input.set_volts(normal);
+    let mut cts = MonitoredSensor::new(
+        CalibratedSensor::new(FilteredInput::new(input, temp_filter()), Thermistor::new(GM_THERMISTOR, 2490.0, 5.0)),
+        SensorId::Cts,
+        cts_range(),
+        Fallback::Fixed(80.0),
+    );
+    let mut diag: DiagnosticLog<8> = DiagnosticLog::new();
+    let code = FaultCode::new(SensorId::Cts, FaultKind::RangeHigh);
+
+    let mut now = 0;
+    for _ in 0..100 {
+        now += 10_000;
+        cts.update(now, None, &mut diag);
+    }
+    assert!((cts.value() - 30.0).abs() < 0.5);
+
+    // Cable roto: el voltaje crudo salta a 5 V. La falla se confirma con el
+    // debounce aunque el filtro siga en rango, y nunca se entrega un valor frío
+    cts.sensor_mut().input_mut().input_mut().set_volts(5.0);
+    while !diag.is_active(code) {
+        now += 10_000;
+        let t = cts.update(now, None, &mut diag);
+        assert!(t >= 29.5, "{} °C a los {} µs", t, now);
+        assert!(now < 1_000_000 + DEBOUNCE_US + 20_000);
+    }
+    assert_eq!(cts.value(), 80.0);
+
+    // Al reconectar, el filtro arranca desde el voltaje real y no desde 5 V
+    cts.sensor_mut().input_mut().input_mut().set_volts(normal);
+    for _ in 0..20 {
+        now += 10_000;
+        cts.update(now, None, &mut diag);
+    }
+    assert!(!diag.is_active(code));
+    assert!((cts.value() - 30.0).abs() < 0.5);
+}
+
+#[test]
+fn test_pico_aislado_no_toca_el_filtro() {
+    // Dos CTS iguales calentándose (el primer orden de 1 s va a medio
+    // camino); a uno le llega un pico de 5 V que no alcanza a ser falla
+    let cold = cts_volts(2238.0);
+    let warm = cts_volts(467.0);
+    let sensor = || {
+        let mut input = MockAnalogInput::new([cold]);
+        input.set_volts(cold);
+        MonitoredSensor::new(
+            CalibratedSensor::new(FilteredInput::new(input, temp_filter()), Thermistor::new(GM_THERMISTOR, 2490.0, 5.0)),
+            SensorId::Cts,
+            cts_range(),
+            Fallback::Fixed(80.0),
+        )
+    };
+    let mut spiked = sensor();
+    let mut clean = sensor();
+    let mut diag: DiagnosticLog<8> = DiagnosticLog::new();
+
+    let mut now = 0;
+    for i in 0..150 {
+        now += 10_000;
+        if i == 100 {
+            spiked.sensor_mut().input_mut().input_mut().set_volts(warm);
+            clean.sensor_mut().input_mut().input_mut().set_volts(warm);
+        }
+        spiked.update(now, None, &mut diag);
+        clean.update(now, None, &mut diag);
+    }
+
+    spiked.sensor_mut().input_mut().input_mut().set_volts(5.0);
+    now += 10_000;
+    spiked.update(now, None, &mut diag);
+    clean.update(now, None, &mut diag);
+    spiked.sensor_mut().input_mut().input_mut().set_volts(warm);
+
+    for _ in 0..20 {
+        now += 10_000;
+        spiked.update(now, None, &mut diag);
+        clean.update(now, None, &mut diag);
+        let a = spiked.sensor().input().chain().output();
+        let b = clean.sensor().input().chain().output();
+        assert!((a - b).abs() < 1e-4, "{} V contra {} V", a, b);
+        assert!((spiked.value() - clean.value()).abs() < 0.1);
+    }
+    // El filtro sigue a medio camino, no saltó al voltaje crudo
+    assert!(spiked.sensor().input().chain().output() > warm + 0.2);
+    assert!(!diag.any_active());
+}
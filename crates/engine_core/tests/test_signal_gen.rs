@@ -0,0 +1,172 @@
+use engine_core::crank_decoder::{CrankDecoder, MissingToothWheel, SyncState};
+use engine_core::signal_gen::{GeneratedEdge, SignalGenConfig, SignalGenerator, SignalNoise, TriggerSignal};
+use engine_core::trigger_patterns::{Gm24x, Honda12Plus1, Mazda4Plus1, Nissan360, Subaru36222, TriggerPattern};
+
+fn config<const K: usize>(rpm: f32, cam_teeth_deg: [f32; K], noise: SignalNoise) -> SignalGenConfig<K> {
+    SignalGenConfig {
+        start_us: 1_000,
+        start_angle_deg: 0.0,
+        rpm,
+        cam_teeth_deg,
+        cam_tooth_width_deg: 20.0,
+        noise,
+    }
+}
+
+/// Alimenta el decodificador con los flancos de cigüeñal.
+/// check: verifica que cada diente reportado sea el generado
+fn decode<P: TriggerPattern + Clone, const K: usize>(
+    generator: &mut SignalGenerator<P, K>,
+    decoder: &mut CrankDecoder<P>,
+    edges: usize,
+    check: bool,
+) {
+    for edge in generator.by_ref().take(edges) {
+        if edge.signal != TriggerSignal::Crank {
+            continue;
+        }
+        if let Some(event) = decoder.on_edge(edge.captured()) {
+            if let (true, Some(tooth)) = (check, edge.tooth) {
+                assert_eq!(event.tooth, tooth);
+            }
+        }
+    }
+}
+
+fn check_pattern<P: TriggerPattern + Clone>(pattern: P, name: &str) {
+    let mut generator = SignalGenerator::new(pattern.clone(), config(1500.0, [], SignalNoise::default()));
+    let mut decoder = CrankDecoder::new(pattern.clone(), 0.0);
+    decode(&mut generator, &mut decoder, pattern.tooth_count() * 8, true);
+    assert_eq!(decoder.state(), SyncState::Synced, "{}", name);
+    assert_eq!(decoder.sync_losses(), 0, "{}", name);
+}
+
+#[test]
+fn test_todos_los_patrones() {
+    check_pattern(MissingToothWheel::new(60, 2), "60-2");
+    check_pattern(MissingToothWheel::new(36, 1), "36-1");
+    check_pattern(Nissan360, "Nissan 360");
+    check_pattern(Gm24x, "GM 24x");
+    check_pattern(Subaru36222, "Subaru 36-2-2-2");
+    check_pattern(Honda12Plus1, "Honda 12+1");
+    check_pattern(Mazda4Plus1, "Mazda 4+1");
+}
+
+#[test]
+fn test_tiempos_a_rpm_constantes() {
+    // 60-2 a 1000 RPM: 1000 µs por diente, un pulso por diente
+    let wheel = MissingToothWheel::new(60, 2);
+    let mut generator = SignalGenerator::new(wheel, config(1000.0, [], SignalNoise::default()));
+    let edges: Vec<GeneratedEdge> = generator.by_ref().take(117).collect();
+
+    assert_eq!(edges[0], GeneratedEdge { timestamp_us: 1_000, signal: TriggerSignal::Crank, rising: true, tooth: Some(0) });
+    // Bajada a la mitad del diente
+    assert_eq!((edges[1].timestamp_us, edges[1].rising), (1_500, false));
+    assert_eq!(edges[2].timestamp_us, 2_000);
+    // Hueco: del diente 57 (342°) al 0 de la siguiente vuelta (360°)
+    assert_eq!(edges[114].tooth, Some(57));
+    assert_eq!(edges[116].timestamp_us - edges[114].timestamp_us, 3_000);
+    assert_eq!(generator.now_us(), 1_000 + 60_000);
+}
+
+#[test]
+fn test_rampa_de_rpm() {
+    let wheel = MissingToothWheel::new(60, 2);
+    let mut generator = SignalGenerator::new(wheel, config(0.0, [], SignalNoise::default()));
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+
+    // Arranque: de 0 a 250 RPM en 0.5 s, luego a 6000 en 1 s
+    generator.ramp_to(250.0, 500_000);
+    while generator.rpm() < 250.0 {
+        decode(&mut generator, &mut decoder, 1, true);
+    }
+    generator.ramp_to(6000.0, 1_000_000);
+    while generator.rpm() < 6000.0 {
+        decode(&mut generator, &mut decoder, 1, true);
+    }
+    decode(&mut generator, &mut decoder, 200, true);
+
+    assert_eq!(decoder.state(), SyncState::Synced);
+    assert_eq!(decoder.sync_losses(), 0);
+    assert!((decoder.rpm() - 6000.0).abs() < 60.0, "{}", decoder.rpm());
+}
+
+#[test]
+fn test_senal_de_leva() {
+    let wheel = MissingToothWheel::new(36, 1);
+    let mut generator = SignalGenerator::new(wheel, config(1000.0, [630.0], SignalNoise::default()));
+
+    let cam: Vec<GeneratedEdge> = generator
+        .by_ref()
+        .take(400)
+        .filter(|e| e.signal == TriggerSignal::Cam)
+        .collect();
+    // Dos ciclos de 720°: subida en 630° y bajada 20° después
+    assert_eq!(cam.len(), 4);
+    assert!(cam[0].rising && !cam[1].rising);
+    // 1000 RPM = 1/6 de grado por µs
+    assert_eq!(cam[0].timestamp_us, 1_000 + 105_000);
+    assert_eq!(cam[1].timestamp_us, 1_000 + 108_333);
+    assert_eq!(cam[2].timestamp_us, 1_000 + 225_000);
+}
+
+#[test]
+fn test_angulo_de_arranque() {
+    let wheel = MissingToothWheel::new(36, 1);
+    let mut cfg = config(1000.0, [630.0], SignalNoise::default());
+    cfg.start_angle_deg = 455.0;
+    let mut generator = SignalGenerator::new(wheel, cfg);
+
+    // 455° = diente 9 de la segunda vuelta más 5°: la bajada de ese diente es lo siguiente
+    let first = generator.next_edge();
+    assert_eq!((first.tooth, first.rising), (Some(9), false));
+    let cam = generator.find(|e| e.signal == TriggerSignal::Cam).unwrap();
+    assert_eq!(cam.timestamp_us, 1_000 + 29_167);
+}
+
+#[test]
+fn test_dientes_perdidos_y_ruido() {
+    let wheel = MissingToothWheel::new(36, 1);
+
+    // Un diente perdido cada 100: se pierde la sincronía y se recupera
+    let noise = SignalNoise { drop_every: 100, ..SignalNoise::default() };
+    let mut generator = SignalGenerator::new(wheel, config(2000.0, [], noise));
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    decode(&mut generator, &mut decoder, 36 * 2 * 5, false);
+    assert!(decoder.sync_losses() >= 1);
+    // 175 dientes generados = 1 perdido
+    assert_eq!(decoder.sync_losses(), 1);
+    assert_eq!(decoder.state(), SyncState::Synced);
+
+    // Pulsos falsos: el filtro los rechaza sin perder la sincronía
+    let noise = SignalNoise { glitch_every: 25, ..SignalNoise::default() };
+    let mut generator = SignalGenerator::new(wheel, config(2000.0, [], noise));
+    let mut decoder = CrankDecoder::new(wheel, 0.0).with_noise_filter(0.25);
+    decode(&mut generator, &mut decoder, 36 * 2 * 5, true);
+    assert_eq!(decoder.state(), SyncState::Synced);
+    assert_eq!(decoder.sync_losses(), 0);
+    assert!(decoder.diagnostics().rejected_edges >= 5);
+}
+
+#[test]
+fn test_jitter_acotado_y_repetible() {
+    let wheel = MissingToothWheel::new(60, 2);
+    let noise = SignalNoise { jitter_us: 15, seed: 42, ..SignalNoise::default() };
+
+    let clean: Vec<u32> = SignalGenerator::new(wheel, config(3000.0, [], SignalNoise::default()))
+        .take(500)
+        .map(|e| e.timestamp_us)
+        .collect();
+    let noisy: Vec<u32> = SignalGenerator::new(wheel, config(3000.0, [], noise))
+        .take(500)
+        .map(|e| e.timestamp_us)
+        .collect();
+    let again: Vec<u32> = SignalGenerator::new(wheel, config(3000.0, [], noise))
+        .take(500)
+        .map(|e| e.timestamp_us)
+        .collect();
+
+    assert_eq!(noisy, again);
+    assert_ne!(noisy, clean);
+    assert!(clean.iter().zip(&noisy).all(|(c, n)| c.abs_diff(*n) <= 15));
+}
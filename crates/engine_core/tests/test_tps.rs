@@ -0,0 +1,238 @@
+use engine_core::analog::Converter;
+use engine_core::diagnostics::{DiagnosticLog, FaultCode, FaultKind, SensorId};
+use engine_core::tps::{
+    DualTps, DualTpsConfig, TpsCalError, TpsCalibration, TpsLimits, TpsMode, TpsTrack,
+};
+
+fn limits_rising() -> TpsLimits {
+    TpsLimits {
+        closed_min_volts: 0.3,
+        closed_max_volts: 1.0,
+        wot_min_volts: 3.8,
+        wot_max_volts: 4.8,
+        min_span_volts: 2.5,
+        closed_stable_volts: 0.02,
+        closed_stable_us: 500_000,
+        closed_step_volts: 0.005,
+    }
+}
+
+fn limits_falling() -> TpsLimits {
+    TpsLimits {
+        closed_min_volts: 3.8,
+        closed_max_volts: 4.8,
+        wot_min_volts: 0.3,
+        wot_max_volts: 1.0,
+        min_span_volts: 2.5,
+        closed_stable_volts: 0.02,
+        closed_stable_us: 500_000,
+        closed_step_volts: 0.005,
+    }
+}
+
+fn dual(debounce_us: u32) -> DualTps {
+    let a = TpsTrack::new(TpsCalibration::new(0.5, 4.5), limits_rising());
+    let b = TpsTrack::new(TpsCalibration::new(4.5, 0.5), limits_falling());
+    DualTps::new(a, b, DualTpsConfig { disagreement_pct: 8.0, debounce_us, limp_max_pct: 15.0 })
+}
+
+#[test]
+fn test_conversion_y_limites() {
+    let cal = TpsCalibration::new(0.5, 4.5);
+    assert_eq!(cal.percent(2.5), 50.0);
+    assert_eq!(cal.percent(0.3), 0.0);
+    assert_eq!(cal.percent(4.9), 100.0);
+
+    // Convertidor para CalibratedSensor (sin limitar)
+    assert_eq!(cal.converter().convert(0.5), 0.0);
+
+    // Pendiente inversa
+    let inverted = TpsCalibration::new(4.5, 0.5);
+    assert_eq!(inverted.percent(1.5), 75.0);
+}
+
+#[test]
+fn test_comando_de_calibracion() {
+    let mut track = TpsTrack::new(TpsCalibration::new(0.5, 4.5), limits_rising());
+
+    assert_eq!(track.finish_calibration(), Err(TpsCalError::NotCalibrating));
+
+    track.begin_calibration();
+    assert!(track.is_calibrating());
+    for v in [0.62, 0.61, 1.5, 3.0, 4.31, 4.3, 2.0, 0.62] {
+        track.update(v, 0);
+    }
+    assert_eq!(track.finish_calibration(), Ok(TpsCalibration::new(0.61, 4.31)));
+    assert!((track.update(4.31, 0) - 100.0).abs() < 1e-4);
+    assert!(track.update(0.61, 0).abs() < 1e-4);
+}
+
+#[test]
+fn test_calibracion_invalida_conserva_la_anterior() {
+    let mut track = TpsTrack::new(TpsCalibration::new(0.5, 4.5), limits_rising());
+
+    // No se llegó a fondo
+    track.begin_calibration();
+    for v in [0.6, 1.5, 2.5] {
+        track.update(v, 0);
+    }
+    assert_eq!(track.finish_calibration(), Err(TpsCalError::WotOutOfRange));
+    assert_eq!(track.calibration(), TpsCalibration::new(0.5, 4.5));
+
+    // Cerrado fuera de límites (sensor en corto)
+    track.begin_calibration();
+    for v in [0.1, 4.5] {
+        track.update(v, 0);
+    }
+    assert_eq!(track.finish_calibration(), Err(TpsCalError::ClosedOutOfRange));
+    assert!(!track.is_calibrating());
+
+    // Recorrido demasiado corto con límites amplios
+    let wide = TpsLimits { wot_min_volts: 0.3, min_span_volts: 2.5, ..limits_rising() };
+    let mut track = TpsTrack::new(TpsCalibration::new(0.5, 4.5), wide);
+    track.begin_calibration();
+    for v in [0.6, 2.0] {
+        track.update(v, 0);
+    }
+    assert_eq!(track.finish_calibration(), Err(TpsCalError::SpanTooSmall));
+}
+
+/// Actualiza la pista cada 10 ms durante `duration_us`; regresa el tiempo final
+fn hold(track: &mut TpsTrack, volts: f32, from_us: u32, duration_us: u32) -> u32 {
+    let mut now = from_us;
+    while now < from_us + duration_us {
+        now += 10_000;
+        track.update(volts, now);
+    }
+    now
+}
+
+#[test]
+fn test_seguimiento_continuo_del_cerrado() {
+    let mut track = TpsTrack::new(TpsCalibration::new(0.6, 4.5), limits_rising());
+
+    // El sensor envejeció y en cerrado marca 0.55 V: se aprende después de
+    // sostenerse estable, de a poco
+    let now = hold(&mut track, 0.55, 0, 400_000);
+    assert_eq!(track.calibration().closed_volts, 0.6);
+    let now = hold(&mut track, 0.55, now, 150_000);
+    let closed = track.calibration().closed_volts;
+    assert!(closed < 0.6 && closed > 0.55, "cerrado {}", closed);
+    let now = hold(&mut track, 0.55, now, 200_000);
+    assert!((track.calibration().closed_volts - 0.55).abs() < 1e-4);
+    assert!(track.update(0.55, now).abs() < 1e-3);
+
+    // Lecturas fuera de los límites no se aprenden
+    let now = hold(&mut track, 0.1, now, 2_000_000);
+    assert!((track.calibration().closed_volts - 0.55).abs() < 1e-4);
+
+    // Abrir el acelerador no mueve el cerrado
+    hold(&mut track, 2.0, now, 2_000_000);
+    assert!((track.calibration().closed_volts - 0.55).abs() < 1e-4);
+
+    // Pista inversa: el cerrado se sigue hacia arriba
+    let mut inverted = TpsTrack::new(TpsCalibration::new(4.4, 0.5), limits_falling());
+    hold(&mut inverted, 4.45, 0, 2_000_000);
+    assert!((inverted.calibration().closed_volts - 4.45).abs() < 1e-4);
+}
+
+#[test]
+fn test_picos_no_mueven_el_cerrado() {
+    let mut track = TpsTrack::new(TpsCalibration::new(0.6, 4.5), limits_rising());
+
+    // Picos aislados dentro de los límites (ruido del ADC) entre lecturas normales
+    let mut now = 0;
+    for _ in 0..50 {
+        now = hold(&mut track, 0.6, now, 90_000);
+        now += 10_000;
+        track.update(0.35, now);
+    }
+    assert_eq!(track.calibration().closed_volts, 0.6);
+
+    // Ruido que alterna más que la banda de estabilidad tampoco se aprende
+    for i in 0..200 {
+        now += 10_000;
+        track.update(if i % 2 == 0 { 0.5 } else { 0.56 }, now);
+    }
+    assert_eq!(track.calibration().closed_volts, 0.6);
+
+    // Un valor estable sí, y cada actualización mueve como mucho un paso
+    now = hold(&mut track, 0.4, now, 500_000);
+    let mut previous = track.calibration().closed_volts;
+    for _ in 0..10 {
+        now += 10_000;
+        track.update(0.4, now);
+        let closed = track.calibration().closed_volts;
+        assert!(previous - closed <= 0.005 + 1e-6);
+        previous = closed;
+    }
+    assert!(previous < 0.6);
+}
+
+#[test]
+fn test_doble_pista_coincide() {
+    let mut tps = dual(100_000);
+    let mut diag: DiagnosticLog<4> = DiagnosticLog::new();
+
+    for (i, pct) in [0.0f32, 25.0, 60.0, 100.0].iter().enumerate() {
+        let va = 0.5 + 4.0 * pct / 100.0;
+        let vb = 4.5 - 4.0 * pct / 100.0;
+        let reading = tps.update(va, vb, i as u32 * 10_000, &mut diag);
+        assert_eq!(reading.mode, TpsMode::Normal);
+        assert!((reading.percent - pct).abs() < 1e-3);
+    }
+    assert!(!diag.any_active());
+}
+
+#[test]
+fn test_desacuerdo_entra_en_modo_limitado() {
+    let mut tps = dual(100_000);
+    let mut diag: DiagnosticLog<4> = DiagnosticLog::new();
+    let code = FaultCode::new(SensorId::Tps, FaultKind::Disagreement);
+
+    // La pista B se queda pegada en 20% mientras A abre a 50%
+    let reading = tps.update(2.5, 3.7, 0, &mut diag);
+    assert_eq!(reading.mode, TpsMode::Normal);
+    assert!(!diag.is_active(code));
+
+    let reading = tps.update(2.5, 3.7, 150_000, &mut diag);
+    assert_eq!(reading.mode, TpsMode::Limited);
+    assert_eq!(reading.percent, 15.0);
+    assert!(diag.is_active(code));
+    assert_eq!(code.obd_code(), 2135);
+
+    // Retenido aunque las pistas vuelvan a coincidir
+    let reading = tps.update(1.0, 4.0, 300_000, &mut diag);
+    assert_eq!(reading.mode, TpsMode::Limited);
+    // Con menos apertura que el límite se usa la menor de las pistas
+    assert!((reading.percent - 12.5).abs() < 1e-3);
+
+    tps.clear_fault(400_000, &mut diag);
+    let reading = tps.update(1.0, 4.0, 410_000, &mut diag);
+    assert_eq!(reading.mode, TpsMode::Normal);
+    assert!(!diag.is_active(code));
+}
+
+#[test]
+fn test_calibracion_de_doble_pista() {
+    let mut tps = dual(0);
+    let mut diag: DiagnosticLog<4> = DiagnosticLog::new();
+
+    tps.begin_calibration();
+    for (va, vb) in [(0.6, 4.4), (4.4, 0.6), (0.6, 4.4)] {
+        tps.update(va, vb, 0, &mut diag);
+    }
+    let (a, b) = tps.finish_calibration().unwrap();
+    assert_eq!(a, TpsCalibration::new(0.6, 4.4));
+    assert_eq!(b, TpsCalibration::new(4.4, 0.6));
+
+    // Si una pista falla ninguna cambia
+    tps.begin_calibration();
+    for (va, vb) in [(0.6, 4.4), (4.4, 3.0)] {
+        tps.update(va, vb, 0, &mut diag);
+    }
+    assert_eq!(tps.finish_calibration(), Err(TpsCalError::WotOutOfRange));
+    assert_eq!(tps.track_a().calibration(), a);
+    assert_eq!(tps.track_b().calibration(), b);
+    assert!(!tps.track_b().is_calibrating());
+}
@@ -0,0 +1,203 @@
+use ecu_traits::engine_io::CapturedEdge;
+use engine_core::crank_decoder::{CrankDecoder, MissingToothWheel, SyncState};
+use engine_core::trigger_log::{
+    decode_entry, render_periods, LogCommandError, LogEvent, LogMode, TriggerLogger, CMD_READ, CMD_START,
+    CMD_STATUS, CMD_STOP, ENTRY_BYTES, STATUS_BYTES,
+};
+
+/// Flancos de subida de una rueda 36-1 a periodo constante, desde la posición 0
+fn wheel_edges(revs: usize, period_us: u32) -> Vec<CapturedEdge> {
+    let mut out = Vec::new();
+    for pos in 0..revs * 36 {
+        if pos % 36 < 35 {
+            out.push(CapturedEdge { timestamp_us: 1_000 + pos as u32 * period_us, rising: true });
+        }
+    }
+    out
+}
+
+/// Pasa los flancos por el decodificador y el logger
+fn capture<const N: usize>(logger: &mut TriggerLogger<N>, edges: &[CapturedEdge]) {
+    let mut decoder = CrankDecoder::new(MissingToothWheel::new(36, 1), 0.0);
+    for &edge in edges {
+        let event = decoder.on_edge(edge);
+        logger.on_crank_edge(edge, decoder.state(), event.as_ref());
+    }
+}
+
+#[test]
+fn test_captura_acotada() {
+    let mut logger: TriggerLogger<50> = TriggerLogger::new();
+    capture(&mut logger, &wheel_edges(2, 1_000));
+    // Sin iniciar no se registra nada
+    assert!(logger.entries().is_empty());
+
+    logger.start(LogMode::Tooth);
+    capture(&mut logger, &wheel_edges(3, 1_000));
+    assert!(logger.is_full());
+    assert!(!logger.is_recording());
+    assert_eq!(logger.entries().len(), 50);
+}
+
+#[test]
+fn test_eventos_de_sincronia() {
+    let mut logger: TriggerLogger<256> = TriggerLogger::new();
+    logger.start(LogMode::Tooth);
+
+    let mut edges = wheel_edges(3, 1_000);
+    // Diente perdido en la segunda vuelta: pérdida y recuperación de sincronía
+    edges.remove(35 + 10);
+    capture(&mut logger, &edges);
+
+    let sync_events: Vec<LogEvent> = logger
+        .entries()
+        .iter()
+        .map(|e| e.event)
+        .filter(|e| matches!(e, LogEvent::SyncGained | LogEvent::SyncLost))
+        .collect();
+    assert_eq!(sync_events, [LogEvent::SyncGained, LogEvent::SyncLost, LogEvent::SyncGained]);
+
+    // Con sincronía se guarda el diente
+    let synced = logger.entries().iter().find(|e| e.sync == SyncState::Synced).unwrap();
+    assert_eq!(synced.tooth, Some(0));
+}
+
+#[test]
+fn test_marca_de_sincronia_no_se_pierde_al_llenarse() {
+    // La sincronía se gana en el flanco 36 (entrada 35) y su marca iría en la
+    // entrada 36: con 36 lugares el flanco y la marca no caben juntos
+    let mut logger: TriggerLogger<36> = TriggerLogger::new();
+    logger.start(LogMode::Tooth);
+    capture(&mut logger, &wheel_edges(2, 1_000));
+
+    assert!(logger.is_full());
+    assert_eq!(logger.entries().len(), 35);
+    assert!(logger.entries().iter().all(|e| e.sync != SyncState::Synced));
+    // Se cuentan el flanco con su marca y todo lo que llegó después
+    assert_eq!(logger.dropped(), 2 + 34);
+
+    // Con espacio el flanco y la marca quedan juntos
+    let mut logger: TriggerLogger<37> = TriggerLogger::new();
+    logger.start(LogMode::Tooth);
+    capture(&mut logger, &wheel_edges(2, 1_000));
+    assert_eq!(logger.entries()[36].event, LogEvent::SyncGained);
+    assert_eq!(logger.entries()[35].timestamp_us, logger.entries()[36].timestamp_us);
+}
+
+#[test]
+fn test_composite_con_leva() {
+    let mut logger: TriggerLogger<128> = TriggerLogger::new();
+    logger.start(LogMode::Composite);
+    let edges = wheel_edges(1, 1_000);
+
+    let mut decoder = CrankDecoder::new(MissingToothWheel::new(36, 1), 0.0);
+    for (i, &edge) in edges.iter().enumerate() {
+        if i == 10 {
+            logger.on_cam_edge(CapturedEdge { timestamp_us: edge.timestamp_us - 500, rising: true });
+        }
+        let event = decoder.on_edge(edge);
+        logger.on_crank_edge(edge, decoder.state(), event.as_ref());
+    }
+
+    let entries = logger.entries();
+    let cam = entries.iter().position(|e| e.event == LogEvent::CamEdge).unwrap();
+    assert_eq!(cam, 10);
+    // El nivel de leva queda en las entradas siguientes
+    assert!(!entries[cam - 1].cam_level);
+    assert!(entries[cam + 1].cam_level);
+
+    // En modo tooth la leva no ocupa lugar
+    logger.start(LogMode::Tooth);
+    logger.on_cam_edge(CapturedEdge { timestamp_us: 5, rising: false });
+    assert!(logger.entries().is_empty());
+}
+
+#[test]
+fn test_frames_del_protocolo() {
+    let mut logger: TriggerLogger<128> = TriggerLogger::new();
+    logger.start(LogMode::Tooth);
+    capture(&mut logger, &wheel_edges(2, 1_000));
+
+    // Lectura paginada en frames de 64 bytes (8 entradas)
+    let mut decoded = Vec::new();
+    let mut frame = [0u8; 64];
+    loop {
+        let n = logger.read_frame(decoded.len(), &mut frame);
+        if n == 0 {
+            break;
+        }
+        for chunk in frame[..n].chunks_exact(ENTRY_BYTES) {
+            decoded.push(decode_entry(chunk.try_into().unwrap()));
+        }
+    }
+    assert_eq!(decoded.as_slice(), logger.entries());
+
+    // Un buffer menor a una entrada no recibe nada
+    assert_eq!(logger.read_frame(0, &mut [0u8; ENTRY_BYTES - 1]), 0);
+}
+
+#[test]
+fn test_comandos_del_protocolo() {
+    let mut logger: TriggerLogger<128> = TriggerLogger::new();
+    let mut response = [0u8; 64];
+
+    assert_eq!(logger.handle_command(&[CMD_START, 1], &mut response), Ok(0));
+    assert_eq!(logger.mode(), LogMode::Tooth);
+    capture(&mut logger, &wheel_edges(1, 1_000));
+    assert_eq!(logger.handle_command(&[CMD_STOP], &mut response), Ok(0));
+
+    // Estado: modo apagado, no lleno, 35 entradas de 128, nada descartado
+    assert_eq!(logger.handle_command(&[CMD_STATUS], &mut response), Ok(STATUS_BYTES));
+    assert_eq!(response[..STATUS_BYTES], [0, 0, 35, 0, 128, 0, 0, 0]);
+
+    // Lectura paginada desde la entrada 32: quedan 3
+    let n = logger.handle_command(&[CMD_READ, 32, 0], &mut response).unwrap();
+    assert_eq!(n, 3 * ENTRY_BYTES);
+    assert_eq!(decode_entry(response[..ENTRY_BYTES].try_into().unwrap()), logger.entries()[32]);
+
+    assert_eq!(logger.handle_command(&[CMD_START, 7], &mut response), Err(LogCommandError::BadRequest));
+    assert_eq!(logger.handle_command(&[], &mut response), Err(LogCommandError::BadRequest));
+    assert_eq!(
+        logger.handle_command(&[CMD_STATUS], &mut [0u8; 4]),
+        Err(LogCommandError::ResponseTooSmall)
+    );
+}
+
+#[test]
+fn test_csv() {
+    let mut logger: TriggerLogger<64> = TriggerLogger::new();
+    logger.start(LogMode::Tooth);
+    capture(&mut logger, &wheel_edges(1, 1_000)[..3]);
+
+    let mut csv = String::new();
+    logger.write_csv(&mut csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "timestamp_us,delta_us,event,rising,cam,sync,tooth");
+    assert_eq!(lines[1], "1000,0,crank,1,0,syncing,");
+    assert_eq!(lines[2], "2000,1000,crank,1,0,syncing,");
+    assert_eq!(lines.len(), 4);
+}
+
+#[test]
+fn test_grafica_de_periodos() {
+    let mut logger: TriggerLogger<128> = TriggerLogger::new();
+    logger.start(LogMode::Tooth);
+
+    let mut edges = wheel_edges(2, 1_000);
+    // Ruido a la mitad de un diente de la segunda vuelta
+    edges.insert(50, CapturedEdge { timestamp_us: edges[49].timestamp_us + 300, rising: true });
+    capture(&mut logger, &edges);
+
+    let mut text = String::new();
+    render_periods(logger.entries(), &mut text, 40).unwrap();
+    println!("{}", text);
+
+    // Un hueco por vuelta (el primero es antes de empezar a registrar) y el ruido
+    assert_eq!(text.matches("<- hueco").count(), 1);
+    assert_eq!(text.matches("<- ruido").count(), 1);
+    assert!(text.contains("== sincronía =="));
+    // El hueco tiene la barra más larga
+    let gap_line = text.lines().find(|l| l.contains("hueco")).unwrap();
+    assert!(gap_line.contains(&"#".repeat(40)));
+}
+
@@ -0,0 +1,112 @@
+use engine_core::cam_sync::{plausible_rpm, CamRpm};
+use engine_core::crank_decoder::{CrankDecoder, MissingToothWheel, SyncState};
+
+/// Dientes físicos de una rueda N-M a RPM constantes, arrancando en la posición 0
+fn edges(wheel: MissingToothWheel, revs: u32, rpm: f32) -> Vec<u32> {
+    let step = 60_000_000.0 / (rpm as f64 * wheel.teeth as f64);
+    let mut t = 1_000.0f64;
+    let mut out = Vec::new();
+    for i in 0..revs as usize * wheel.teeth as usize {
+        if (i % wheel.teeth as usize) < wheel.real_teeth() as usize {
+            out.push(t as u32);
+        }
+        t += step;
+    }
+    out
+}
+
+#[test]
+fn test_glitch_rechazado_sin_perder_sincronia() {
+    let wheel = MissingToothWheel::new(36, 1);
+    let mut decoder = CrankDecoder::new(wheel, 0.0).with_noise_filter(0.25);
+    let mut stream = edges(wheel, 3, 1500.0);
+
+    // Glitches a 10% del periodo después de un diente normal y después del hueco
+    for k in [50, 35] {
+        let glitch = stream[k] + (stream[k + 1] - stream[k]) / 10;
+        stream.insert(k + 1, glitch);
+    }
+
+    let events: Vec<_> = stream.iter().filter_map(|&t| decoder.on_tooth(t)).collect();
+    assert_eq!(decoder.state(), SyncState::Synced);
+
+    let diag = decoder.diagnostics();
+    assert_eq!(diag.rejected_edges, 2);
+    assert_eq!(diag.sync_losses, 0);
+    assert_eq!(diag.ratio_errors, 0);
+
+    // Los eventos siguen siendo consecutivos
+    for pair in events.windows(2) {
+        assert_eq!(pair[1].tooth, (pair[0].tooth + 1) % wheel.real_teeth());
+    }
+}
+
+#[test]
+fn test_sin_filtro_el_glitch_pierde_sincronia() {
+    let wheel = MissingToothWheel::new(36, 1);
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    let mut stream = edges(wheel, 3, 1500.0);
+    let glitch = stream[50] + (stream[51] - stream[50]) / 10;
+    stream.insert(51, glitch);
+
+    for &t in &stream {
+        decoder.on_tooth(t);
+    }
+    let diag = decoder.diagnostics();
+    assert_eq!(diag.rejected_edges, 0);
+    assert!(diag.ratio_errors >= 1);
+    assert_eq!(diag.sync_losses, diag.ratio_errors);
+}
+
+#[test]
+fn test_flancos_ignorados_antes_de_sincronizar() {
+    let wheel = MissingToothWheel::new(36, 1);
+    let mut decoder = CrankDecoder::new(wheel, 0.0).with_noise_filter(0.25);
+    let stream = edges(wheel, 2, 1000.0);
+
+    // Desde la posición 0 el primer hueco termina en el diente 35
+    for &t in &stream[..36] {
+        decoder.on_tooth(t);
+    }
+    assert_eq!(decoder.state(), SyncState::Synced);
+    assert_eq!(decoder.diagnostics().ignored_edges, 35);
+}
+
+#[test]
+fn test_rpm_del_cigueñal() {
+    let wheel = MissingToothWheel::new(60, 2);
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    assert_eq!(decoder.rpm(), 0.0);
+
+    for t in edges(wheel, 3, 3000.0) {
+        decoder.on_tooth(t);
+    }
+    assert!((decoder.rpm() - 3000.0).abs() < 15.0, "rpm {}", decoder.rpm());
+}
+
+#[test]
+fn test_respaldo_de_rpm_con_leva() {
+    let wheel = MissingToothWheel::new(36, 1);
+    let mut decoder = CrankDecoder::new(wheel, 0.0);
+    let mut cam: CamRpm<4> = CamRpm::new();
+
+    // Sin ninguna señal no hay RPM
+    assert_eq!(plausible_rpm(&decoder, &cam), None);
+
+    // 4 dientes de leva por ciclo a 2000 RPM: un ciclo de 720° = 60 ms
+    for i in 0..6u32 {
+        cam.on_edge(5_000 + i * 15_000);
+    }
+    let rpm = plausible_rpm(&decoder, &cam).unwrap();
+    assert!((rpm - 2000.0).abs() < 1.0, "rpm {}", rpm);
+
+    // Con el cigüeñal sincronizado manda el cigüeñal
+    for t in edges(wheel, 2, 3000.0) {
+        decoder.on_tooth(t);
+    }
+    let rpm = plausible_rpm(&decoder, &cam).unwrap();
+    assert!((rpm - 3000.0).abs() < 20.0, "rpm {}", rpm);
+
+    cam.reset();
+    assert_eq!(cam.rpm(), 0.0);
+}
@@ -0,0 +1,124 @@
+use engine_core::crank_decoder::{CrankDecoder, SyncState};
+use engine_core::trigger_patterns::{
+    Gm24x, Honda12Plus1, Mazda4Plus1, MissingToothWheel, Nissan360, Subaru36222, TriggerPattern,
+};
+
+/// Flanco sintético: (marca de tiempo, diente real)
+type Edge = (u32, usize);
+
+/// Genera los flancos de `cycles` ciclos del patrón a RPM constantes,
+/// arrancando en el diente `start`.
+fn edges<P: TriggerPattern>(pattern: &P, cycles: usize, rpm: f32, start: usize) -> Vec<Edge> {
+    let n = pattern.tooth_count();
+    let us_per_deg = 60_000_000.0 / (rpm as f64 * 360.0);
+    let mut out = Vec::new();
+    let mut t = 5_000.0f64;
+
+    for i in 0..cycles * n {
+        let tooth = (start + i) % n;
+        t += pattern.gap_deg(tooth) as f64 * us_per_deg;
+        out.push((t as u32, tooth));
+    }
+    out
+}
+
+/// Verifica sincronía, estado estable y recuperación después de ruido
+fn check_pattern<P: TriggerPattern + Clone>(pattern: P, name: &str) {
+    let n = pattern.tooth_count();
+
+    for start in [0, n / 3, n - 1] {
+        let mut decoder = CrankDecoder::new(pattern.clone(), 0.0);
+        let stream = edges(&pattern, 3, 1200.0, start);
+
+        // 1. Adquisición: debe sincronizar antes de completar el primer ciclo
+        let mut first_sync = None;
+        for (i, &(t, real)) in stream.iter().enumerate() {
+            if let Some(event) = decoder.on_tooth(t) {
+                first_sync.get_or_insert(i);
+                // 2. Estado estable: el diente reportado es el real
+                assert_eq!(event.tooth as usize, real, "{}: diente incorrecto", name);
+                assert!((event.angle_deg - pattern.tooth_angle_deg(real)).abs() < 0.01);
+            }
+        }
+        let first_sync = first_sync.unwrap_or_else(|| panic!("{}: nunca sincronizó", name));
+        assert!(first_sync <= n + pattern.sync_history(), "{}: sincronía lenta ({})", name, first_sync);
+        assert_eq!(decoder.state(), SyncState::Synced, "{}", name);
+        assert_eq!(decoder.sync_losses(), 0, "{}", name);
+    }
+
+    // 3. Ruido: un flanco falso a la mitad de un periodo
+    let mut decoder = CrankDecoder::new(pattern.clone(), 0.0);
+    let mut stream = edges(&pattern, 4, 1200.0, 0);
+    let k = n + n / 2;
+    let glitch = stream[k].0 + (stream[k + 1].0 - stream[k].0) / 2;
+    stream.insert(k + 1, (glitch, usize::MAX));
+
+    let mut last_event = None;
+    for &(t, real) in &stream {
+        if let Some(event) = decoder.on_tooth(t) {
+            if real != usize::MAX {
+                last_event = Some((event.tooth as usize, real));
+            }
+        }
+    }
+    assert!(decoder.sync_losses() >= 1, "{}: el ruido no se detectó", name);
+    assert_eq!(decoder.state(), SyncState::Synced, "{}: no se recuperó", name);
+    let (reported, real) = last_event.unwrap();
+    assert_eq!(reported, real, "{}: recuperó en el diente equivocado", name);
+}
+
+#[test]
+fn test_rueda_60_2() {
+    check_pattern(MissingToothWheel::new(60, 2), "60-2");
+}
+
+#[test]
+fn test_nissan_360() {
+    assert_eq!(Nissan360.cycle_deg(), 720.0);
+    assert!(Nissan360.uses_both_edges());
+    check_pattern(Nissan360, "Nissan 360");
+}
+
+#[test]
+fn test_gm_24x() {
+    assert!(Gm24x.uses_both_edges());
+    for slot in 0..24 {
+        // 24 flancos equiespaciados a 15°
+        assert_eq!(Gm24x.tooth_angle_deg(slot * 2), slot as f32 * 15.0);
+        // El flanco variable queda a 3° o 12° del anterior
+        let gap = Gm24x.gap_deg(slot * 2 + 1);
+        assert!(gap == 3.0 || gap == 12.0, "muesca {}: {}°", slot, gap);
+    }
+    check_pattern(Gm24x, "GM 24x");
+}
+
+#[test]
+fn test_subaru_36_2_2_2() {
+    assert_eq!(Subaru36222.tooth_count(), 30);
+    check_pattern(Subaru36222, "Subaru 36-2-2-2");
+}
+
+#[test]
+fn test_honda_12_mas_1() {
+    assert_eq!(Honda12Plus1.tooth_count(), 13);
+    check_pattern(Honda12Plus1, "Honda 12+1");
+}
+
+#[test]
+fn test_mazda_4_mas_1() {
+    assert_eq!(Mazda4Plus1.cycle_deg(), 720.0);
+    check_pattern(Mazda4Plus1, "Mazda 4+1");
+}
+
+#[test]
+fn test_angulo_de_ciclo_720() {
+    // En patrones de leva el ángulo reportado cubre el ciclo completo
+    let mut decoder = CrankDecoder::new(Mazda4Plus1, 10.0);
+    let mut max_angle: f32 = 0.0;
+    for (t, _) in edges(&Mazda4Plus1, 3, 1000.0, 0) {
+        if let Some(event) = decoder.on_tooth(t) {
+            max_angle = max_angle.max(event.angle_deg);
+        }
+    }
+    assert_eq!(max_angle, 550.0);
+}